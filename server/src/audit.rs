@@ -0,0 +1,147 @@
+//! Audit log of presenter actions
+//!
+//! Presenter actions that change what everyone in a session sees (slide
+//! changes, overlay updates, presenter authentication) are recorded through an
+//! [`AuditSink`]. The default sink, [`FileAuditSink`], appends one JSON object
+//! per line from a background task so that handlers never block on disk I/O.
+//! Auditing is disabled unless `AUDIT_LOG_PATH` is set.
+
+use crate::protocol::LayerPatch;
+use crate::session::state::now_millis;
+use metrics::counter;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::warn;
+use uuid::Uuid;
+
+/// A presenter action worth auditing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum AuditAction {
+    /// Presenter changed the session's slide
+    SlideChange { slide_id: String },
+    /// Presenter updated the cell overlay layer
+    CellOverlayUpdate {
+        enabled: bool,
+        opacity: f64,
+        visible_cell_types: Vec<String>,
+    },
+    /// Presenter updated the tissue overlay layer
+    TissueOverlayUpdate {
+        enabled: bool,
+        opacity: f64,
+        visible_tissue_types: Vec<i32>,
+    },
+    /// Presenter changed some overlay fields
+    LayerPatch { patch: LayerPatch },
+    /// Presenter showed or hid follower cursors
+    FollowerCursorsToggle { visible: bool },
+    /// Connection authenticated as presenter with the presenter key
+    PresenterAuth,
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    pub session_id: String,
+    /// Participant who performed the action
+    pub actor_id: Option<Uuid>,
+    #[serde(flatten)]
+    pub action: AuditAction,
+}
+
+impl AuditRecord {
+    pub fn new(session_id: &str, actor_id: Option<Uuid>, action: AuditAction) -> Self {
+        Self {
+            timestamp: now_millis(),
+            session_id: session_id.to_string(),
+            actor_id,
+            action,
+        }
+    }
+}
+
+/// Destination for audit records
+pub trait AuditSink: Send + Sync {
+    /// Record an action. Sinks must not fail the caller; errors are logged.
+    fn record(&self, record: &AuditRecord);
+}
+
+/// Records queued for the writer task before new ones are dropped
+const AUDIT_QUEUE_CAPACITY: usize = 1024;
+
+/// Appends audit records as JSON lines to a file
+///
+/// Records are queued on a bounded channel and written by a single task.
+/// When the writer falls behind and the queue fills up, new records are
+/// dropped and counted in `pathcollab_audit_dropped_total`.
+pub struct FileAuditSink {
+    tx: mpsc::Sender<AuditRecord>,
+}
+
+impl FileAuditSink {
+    /// Open (or create) the audit log at `path` in append mode and start its
+    /// writer task. Must be called from within a Tokio runtime.
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())?;
+        let (tx, rx) = mpsc::channel(AUDIT_QUEUE_CAPACITY);
+        tokio::spawn(write_records(tokio::fs::File::from_std(file), rx));
+        Ok(Self { tx })
+    }
+}
+
+impl AuditSink for FileAuditSink {
+    fn record(&self, record: &AuditRecord) {
+        match self.tx.try_send(record.clone()) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                counter!("pathcollab_audit_dropped_total").increment(1);
+                warn!("Audit log queue is full, dropping record");
+            }
+            Err(TrySendError::Closed(_)) => {
+                counter!("pathcollab_audit_write_errors_total").increment(1);
+                warn!("Audit log writer has stopped, dropping record");
+            }
+        }
+    }
+}
+
+/// Write queued records until every sender is gone, batching whatever has
+/// piled up since the last write
+async fn write_records(mut file: tokio::fs::File, mut rx: mpsc::Receiver<AuditRecord>) {
+    let mut batch = Vec::new();
+    while rx.recv_many(&mut batch, AUDIT_QUEUE_CAPACITY).await > 0 {
+        let mut lines = String::new();
+        let mut count = 0;
+        for record in batch.drain(..) {
+            match serde_json::to_string(&record) {
+                Ok(line) => {
+                    lines.push_str(&line);
+                    lines.push('\n');
+                    count += 1;
+                }
+                Err(e) => warn!("Failed to serialize audit record: {}", e),
+            }
+        }
+
+        let result = match file.write_all(lines.as_bytes()).await {
+            Ok(()) => file.flush().await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => counter!("pathcollab_audit_records_total").increment(count),
+            Err(e) => {
+                counter!("pathcollab_audit_write_errors_total").increment(count);
+                warn!("Failed to write audit records: {}", e);
+            }
+        }
+    }
+}
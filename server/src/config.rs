@@ -11,9 +11,13 @@
 //!
 //! See also: `docker-compose.yml`, `README.md`, `.env.example`, `web/vite.config.ts`
 
+use crate::fovea::OverlayPathTemplate;
+use crate::protocol::{OPTIONAL_FEATURES, REPLAYABLE_MESSAGE_TYPES};
+use crate::server::client_ip::IpCidr;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
+use thiserror::Error;
 
 /// Main server configuration
 #[derive(Debug, Clone)]
@@ -26,6 +30,12 @@ pub struct Config {
     pub public_base_url: Option<String>,
     /// Whether server is behind a reverse proxy
     pub behind_proxy: bool,
+    /// Proxy networks (CIDR) whose `X-Forwarded-For`/`Forwarded` headers are trusted
+    pub trusted_proxies: Vec<String>,
+    /// Bearer token for the `/api/admin/*` endpoints (admin API disabled if None)
+    pub admin_token: Option<String>,
+    /// Log line format
+    pub log_format: LogFormat,
 
     /// WSIStreamer URL
     pub wsistreamer_url: String,
@@ -36,6 +46,9 @@ pub struct Config {
     /// Presence configuration
     pub presence: PresenceConfig,
 
+    /// WebSocket keepalive configuration
+    pub websocket: WebSocketConfig,
+
     /// Slide configuration
     pub slide: SlideConfig,
 
@@ -47,6 +60,68 @@ pub struct Config {
 
     /// Static file serving configuration
     pub static_files: StaticFilesConfig,
+
+    /// Presenter action audit log configuration
+    pub audit: AuditConfig,
+
+    /// Session transcript recording configuration
+    pub recording: RecordingConfig,
+
+    /// Session lifecycle webhook configuration
+    pub webhook: WebhookConfig,
+
+    /// Demo session configuration
+    pub demo: DemoConfig,
+
+    /// In-process TLS configuration
+    pub tls: TlsConfig,
+}
+
+/// A single configuration problem found by [`Config::validate`]
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ConfigProblem {
+    #[error("PORT must be non-zero")]
+    InvalidPort,
+
+    #[error("{0} must be greater than zero")]
+    NotPositive(&'static str),
+
+    #[error("{name} {path:?} is not a directory and cannot be created")]
+    BadDirectory { name: &'static str, path: PathBuf },
+
+    #[error("PUBLIC_BASE_URL {0:?} must be an absolute http(s) URL")]
+    InvalidPublicBaseUrl(String),
+
+    #[error("{0} must be set when DEMO_ENABLED is on")]
+    MissingDemoSetting(&'static str),
+
+    #[error("TLS_CERT_PATH and TLS_KEY_PATH must be set together")]
+    IncompleteTls,
+
+    #[error("TRUSTED_PROXIES entry {0:?} is not an IP address or CIDR network")]
+    InvalidTrustedProxy(String),
+
+    #[error("SESSION_FEATURES entry {0:?} is not a known feature")]
+    UnknownFeature(String),
+
+    #[error("SESSION_REPLAY_TYPES entry {0:?} is not a replayable message type")]
+    UnknownReplayType(String),
+
+    #[error("OVERLAY_PATH_TEMPLATE {template:?} is invalid: {reason}")]
+    InvalidOverlayTemplate { template: String, reason: String },
+
+    #[error("WEBHOOK_URL {0:?} must be an absolute http(s) URL")]
+    InvalidWebhookUrl(String),
+
+    #[error("WEBHOOK_SECRET must be set when WEBHOOK_URL is set")]
+    MissingWebhookSecret,
+}
+
+/// All problems found in a configuration
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("invalid configuration: {}", .problems.iter().map(|p| p.to_string()).collect::<Vec<_>>().join("; "))]
+pub struct ConfigError {
+    pub problems: Vec<ConfigProblem>,
 }
 
 /// Session-related configuration
@@ -54,21 +129,83 @@ pub struct Config {
 pub struct SessionConfig {
     /// Maximum number of followers per session
     pub max_followers: usize,
+    /// Upper bound on a per-session follower limit requested by the presenter
+    pub max_followers_cap: usize,
     /// Maximum concurrent sessions
     pub max_concurrent_sessions: usize,
+    /// Presenter viewports kept for rewinding
+    pub viewport_history_len: usize,
     /// Session maximum duration
     pub max_duration: Duration,
+    /// Warning participants get before their session expires (0 disables)
+    pub expiry_warning: Duration,
+    /// How far each presenter extension pushes the expiry back
+    pub extension: Duration,
+    /// Longest a session can last, counting extensions, from its creation
+    pub max_total_duration: Duration,
     /// Grace period after presenter disconnects
     pub presenter_grace_period: Duration,
+    /// How often expired sessions are removed
+    pub cleanup_interval: Duration,
+    /// Random delay of up to this much added to each cleanup pass, so that
+    /// replicas started together do not clean up in lockstep
+    pub cleanup_jitter: Duration,
+    /// Optional features enabled for new sessions (e.g. `chat`)
+    pub features: Vec<String>,
+    /// Recent events replayed to participants joining mid-session (0 disables)
+    pub replay_len: usize,
+    /// Message types kept for replay (see `REPLAYABLE_MESSAGE_TYPES`)
+    pub replay_types: Vec<String>,
+    /// Notice followers get before a presenter's slide change is applied
+    /// (0 applies changes immediately)
+    pub slide_change_delay: Duration,
 }
 
 /// Presence-related configuration
 #[derive(Debug, Clone)]
 pub struct PresenceConfig {
-    /// Cursor broadcast frequency in Hz
+    /// Cursor broadcast frequency in Hz (the batching tick rate)
     pub cursor_broadcast_hz: u32,
-    /// Viewport broadcast frequency in Hz
+    /// Batch cursor updates into one presence delta per session per tick,
+    /// instead of broadcasting each update immediately
+    pub batch_cursors: bool,
+    /// Presenter viewport broadcast frequency in Hz; faster updates are
+    /// coalesced, keeping the newest
     pub viewport_broadcast_hz: u32,
+    /// Cursors idle longer than this are reported as removed
+    pub cursor_stale_after: Duration,
+    /// Cursor updates broadcast per participant per second; faster updates are
+    /// stored but not broadcast
+    pub cursor_max_hz: u32,
+}
+
+/// WebSocket keepalive configuration
+#[derive(Debug, Clone)]
+pub struct WebSocketConfig {
+    /// Interval between server pings
+    pub ping_interval: Duration,
+    /// Extra silence allowed after a missed ping before the connection is closed
+    pub ping_timeout: Duration,
+    /// Reject upgrades requesting subprotocols other than `pathcollab.v1`
+    pub strict_subprotocol: bool,
+    /// Log and count fields of client messages the server does not know.
+    /// Such fields are always ignored; this only makes them visible.
+    pub report_unknown_fields: bool,
+    /// Accept `create_session_with_slide`, where the client supplies the slide
+    /// metadata. Only for deployments whose clients are trusted.
+    pub allow_client_slides: bool,
+    /// Open WebSocket connections allowed at once; further upgrades are rejected
+    pub max_connections: usize,
+}
+
+/// Log line format
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Pretty,
+    /// One JSON object per line, for log aggregators
+    Json,
 }
 
 /// Slide source mode
@@ -115,6 +252,27 @@ pub struct SlideConfig {
     pub tile_size: u32,
     /// Maximum number of cached OpenSlide handles (for metadata reads)
     pub max_cached_slides: usize,
+    /// Slides whose metadata is loaded at startup
+    pub preload: SlidePreload,
+    /// Keep preloaded slides open instead of closing them once their
+    /// metadata is cached
+    pub preload_keep_open: bool,
+    /// Lowercase slide ids and strip known WSI extensions, so `Slide1`,
+    /// `slide1` and `slide1.svs` all resolve to the same slide
+    pub normalize_ids: bool,
+}
+
+/// Slides opened at startup, so the first session on them doesn't pay the
+/// OpenSlide open cost
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SlidePreload {
+    /// Slides are opened on first use
+    #[default]
+    Disabled,
+    /// Every slide in the slides directory
+    All,
+    /// Only these slide ids
+    Ids(Vec<String>),
 }
 
 /// Overlay-related configuration
@@ -122,16 +280,88 @@ pub struct SlideConfig {
 pub struct OverlayConfig {
     /// Directory containing overlay files
     pub overlays_dir: PathBuf,
+    /// Path of each slide's primary overlay, e.g. `{overlays_dir}/{slide_id}.bin`.
+    /// If None, the built-in layouts under `overlays_dir` are searched.
+    pub path_template: Option<String>,
 }
 
 impl Default for OverlayConfig {
     fn default() -> Self {
         Self {
             overlays_dir: PathBuf::from("./data/overlays"),
+            path_template: None,
+        }
+    }
+}
+
+/// Presenter action audit log configuration
+#[derive(Debug, Clone, Default)]
+pub struct AuditConfig {
+    /// JSON-lines file presenter actions are appended to.
+    /// If None, audit logging is disabled
+    pub log_path: Option<PathBuf>,
+}
+
+/// Session lifecycle webhook configuration
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// Endpoint session and participant events are POSTed to.
+    /// If None, webhooks are disabled
+    pub url: Option<String>,
+    /// Key for the HMAC-SHA256 signature sent with every event
+    pub secret: Option<String>,
+    /// Delivery attempts per event before it is dead-lettered
+    pub max_attempts: u32,
+    /// Timeout of a single delivery attempt
+    pub timeout: Duration,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            secret: None,
+            max_attempts: 4,
+            timeout: Duration::from_secs(5),
         }
     }
 }
 
+/// Demo session configuration
+#[derive(Debug, Clone, Default)]
+pub struct DemoConfig {
+    /// Keep a presenter-less demo session alive for visitors to join
+    pub enabled: bool,
+    /// Slide shown in the demo session
+    pub slide_id: Option<String>,
+    /// Well-known join secret of the demo session
+    pub join_secret: Option<String>,
+}
+
+/// In-process TLS configuration. Serves HTTPS/WSS when both paths are set.
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// PEM certificate chain
+    pub cert_path: Option<PathBuf>,
+    /// PEM private key
+    pub key_path: Option<PathBuf>,
+}
+
+impl TlsConfig {
+    /// Certificate and key paths, if TLS is enabled
+    pub fn paths(&self) -> Option<(&Path, &Path)> {
+        Some((self.cert_path.as_deref()?, self.key_path.as_deref()?))
+    }
+}
+
+/// Session transcript recording configuration
+#[derive(Debug, Clone, Default)]
+pub struct RecordingConfig {
+    /// Directory session transcripts are written to, one file per session.
+    /// If None, recording is disabled
+    pub dir: Option<PathBuf>,
+}
+
 /// Fovea rendering-data configuration. These feed `fovea_pack::SourceOptions`
 /// when a slide's renderable sources (tile pyramid, cell chunks, heatmap) are
 /// prepared and served via `/api/fovea/*`.
@@ -151,6 +381,20 @@ pub struct FoveaConfig {
     pub heatmap_tile_size: u32,
     /// Maximum RAM (MB) for each slide's encoded tile cache.
     pub tile_cache_mb: usize,
+    /// Maximum concurrently served fovea requests (OpenSlide read + encode is
+    /// CPU-bound); excess requests queue. Defaults to the number of CPUs.
+    pub max_concurrent_encodes: usize,
+    /// Maximum tiles accepted by one `POST /api/slide/:id/tiles` request.
+    pub max_batch_tiles: usize,
+    /// Serve a placeholder JPEG instead of an error when a slide tile read
+    /// fails (invalid levels/coordinates still return their 4xx).
+    pub placeholder_tiles: bool,
+    /// Allow `?lossless=true` (PNG) slide tiles. A slide viewed losslessly
+    /// gets its own prepared sources and tile cache, doubling its memory.
+    pub lossless_tiles: bool,
+    /// Longest a single fovea-pack request may take before it is abandoned
+    /// with 504 Gateway Timeout (time queued for an encode slot excluded).
+    pub request_timeout: Duration,
 }
 
 impl Default for FoveaConfig {
@@ -163,6 +407,13 @@ impl Default for FoveaConfig {
             heatmap_bin_size: 128,
             heatmap_tile_size: 256,
             tile_cache_mb: 512,
+            max_concurrent_encodes: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(4),
+            max_batch_tiles: 64,
+            placeholder_tiles: false,
+            lossless_tiles: false,
+            request_timeout: Duration::from_secs(10),
         }
     }
 }
@@ -174,13 +425,22 @@ impl Default for Config {
             port: 8080,
             public_base_url: None,
             behind_proxy: false,
+            trusted_proxies: Vec::new(),
+            admin_token: None,
+            log_format: LogFormat::default(),
             wsistreamer_url: "http://wsistreamer:3000".to_string(),
             session: SessionConfig::default(),
             presence: PresenceConfig::default(),
+            websocket: WebSocketConfig::default(),
             slide: SlideConfig::default(),
             overlay: OverlayConfig::default(),
             fovea: FoveaConfig::default(),
             static_files: StaticFilesConfig::default(),
+            audit: AuditConfig::default(),
+            recording: RecordingConfig::default(),
+            webhook: WebhookConfig::default(),
+            demo: DemoConfig::default(),
+            tls: TlsConfig::default(),
         }
     }
 }
@@ -189,9 +449,20 @@ impl Default for SessionConfig {
     fn default() -> Self {
         Self {
             max_followers: 20,
-            max_concurrent_sessions: 50,
+            max_followers_cap: 100,
+            max_concurrent_sessions: 1000,
+            viewport_history_len: 32,
             max_duration: Duration::from_secs(4 * 60 * 60), // 4 hours
+            expiry_warning: Duration::from_secs(5 * 60),
+            extension: Duration::from_secs(60 * 60),
+            max_total_duration: Duration::from_secs(8 * 60 * 60),
             presenter_grace_period: Duration::from_secs(30),
+            cleanup_interval: Duration::from_secs(60),
+            cleanup_jitter: Duration::from_secs(5),
+            features: OPTIONAL_FEATURES.iter().map(|f| f.to_string()).collect(),
+            replay_len: 20,
+            replay_types: vec!["chat_message".to_string()],
+            slide_change_delay: Duration::ZERO,
         }
     }
 }
@@ -200,7 +471,23 @@ impl Default for PresenceConfig {
     fn default() -> Self {
         Self {
             cursor_broadcast_hz: 30,
+            batch_cursors: false,
             viewport_broadcast_hz: 10,
+            cursor_stale_after: Duration::from_secs(30),
+            cursor_max_hz: 30,
+        }
+    }
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval: Duration::from_secs(30),
+            ping_timeout: Duration::from_secs(10),
+            strict_subprotocol: false,
+            report_unknown_fields: false,
+            allow_client_slides: false,
+            max_connections: 10_000,
         }
     }
 }
@@ -213,6 +500,9 @@ impl Default for SlideConfig {
             slides_dir: PathBuf::from("./data/slides"),
             tile_size: 256,
             max_cached_slides: 10,
+            preload: SlidePreload::Disabled,
+            preload_keep_open: false,
+            normalize_ids: false,
         }
     }
 }
@@ -240,6 +530,19 @@ impl Config {
         if let Ok(val) = env::var("BEHIND_PROXY") {
             config.behind_proxy = val.to_lowercase() == "true" || val == "1";
         }
+        if let Ok(val) = env::var("TRUSTED_PROXIES") {
+            config.trusted_proxies = val
+                .split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        if let Ok(token) = env::var("ADMIN_TOKEN") {
+            if !token.is_empty() {
+                config.admin_token = Some(token);
+            }
+        }
 
         // WSIStreamer config
         if let Ok(url) = env::var("WSISTREAMER_URL") {
@@ -252,23 +555,89 @@ impl Config {
                 config.session.max_followers = v;
             }
         }
+        if let Ok(val) = env::var("MAX_FOLLOWERS_CAP") {
+            if let Ok(v) = val.parse() {
+                config.session.max_followers_cap = v;
+            }
+        }
         if let Ok(val) = env::var("MAX_CONCURRENT_SESSIONS") {
             if let Ok(v) = val.parse() {
                 config.session.max_concurrent_sessions = v;
             }
         }
+        if let Ok(val) = env::var("VIEWPORT_HISTORY_LEN") {
+            if let Ok(v) = val.parse() {
+                config.session.viewport_history_len = v;
+            }
+        }
+        if let Ok(val) = env::var("SESSION_FEATURES") {
+            config.session.features = val
+                .split(',')
+                .map(str::trim)
+                .filter(|feature| !feature.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        if let Ok(val) = env::var("SESSION_REPLAY_LEN") {
+            if let Ok(v) = val.parse() {
+                config.session.replay_len = v;
+            }
+        }
+        if let Ok(val) = env::var("SESSION_REPLAY_TYPES") {
+            config.session.replay_types = val
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
         if let Ok(val) = env::var("SESSION_MAX_DURATION_HOURS") {
             if let Ok(hours) = val.parse::<u64>() {
                 config.session.max_duration = Duration::from_secs(hours * 60 * 60);
             }
         }
+        if let Ok(val) = env::var("SESSION_EXPIRY_WARNING_SECS") {
+            if let Ok(secs) = val.parse::<u64>() {
+                config.session.expiry_warning = Duration::from_secs(secs);
+            }
+        }
+        if let Ok(val) = env::var("SESSION_EXTENSION_MINS") {
+            if let Ok(mins) = val.parse::<u64>() {
+                config.session.extension = Duration::from_secs(mins * 60);
+            }
+        }
+        if let Ok(val) = env::var("SESSION_MAX_TOTAL_DURATION_HOURS") {
+            if let Ok(hours) = val.parse::<u64>() {
+                config.session.max_total_duration = Duration::from_secs(hours * 60 * 60);
+            }
+        }
         if let Ok(val) = env::var("PRESENTER_GRACE_PERIOD_SECS") {
             if let Ok(secs) = val.parse::<u64>() {
                 config.session.presenter_grace_period = Duration::from_secs(secs);
             }
         }
+        if let Ok(val) = env::var("SESSION_CLEANUP_INTERVAL_SECS") {
+            if let Ok(secs) = val.parse::<u64>() {
+                config.session.cleanup_interval = Duration::from_secs(secs);
+            }
+        }
+        if let Ok(val) = env::var("SESSION_CLEANUP_JITTER_SECS") {
+            if let Ok(secs) = val.parse::<u64>() {
+                config.session.cleanup_jitter = Duration::from_secs(secs);
+            }
+        }
+        if let Ok(val) = env::var("SLIDE_CHANGE_DELAY_SECS") {
+            if let Ok(secs) = val.parse::<u64>() {
+                config.session.slide_change_delay = Duration::from_secs(secs);
+            }
+        }
 
         // Presence config
+        if let Ok(val) = env::var("CURSOR_MAX_HZ") {
+            if let Ok(hz) = val.parse() {
+                config.presence.cursor_max_hz = hz;
+            }
+        }
         if let Ok(val) = env::var("CURSOR_BROADCAST_HZ") {
             if let Ok(hz) = val.parse() {
                 config.presence.cursor_broadcast_hz = hz;
@@ -279,6 +648,48 @@ impl Config {
                 config.presence.viewport_broadcast_hz = hz;
             }
         }
+        if let Ok(val) = env::var("PRESENCE_BATCHING") {
+            config.presence.batch_cursors = val.to_lowercase() == "true" || val == "1";
+        }
+        if let Ok(val) = env::var("CURSOR_STALE_SECS") {
+            if let Ok(secs) = val.parse::<u64>() {
+                config.presence.cursor_stale_after = Duration::from_secs(secs);
+            }
+        }
+
+        // WebSocket keepalive config
+        if let Ok(val) = env::var("WS_PING_INTERVAL_SECS") {
+            if let Ok(secs) = val.parse::<u64>() {
+                config.websocket.ping_interval = Duration::from_secs(secs);
+            }
+        }
+        if let Ok(val) = env::var("WS_PING_TIMEOUT_SECS") {
+            if let Ok(secs) = val.parse::<u64>() {
+                config.websocket.ping_timeout = Duration::from_secs(secs);
+            }
+        }
+        if let Ok(val) = env::var("WS_STRICT_SUBPROTOCOL") {
+            config.websocket.strict_subprotocol = val.to_lowercase() == "true" || val == "1";
+        }
+        if let Ok(val) = env::var("WS_REPORT_UNKNOWN_FIELDS") {
+            config.websocket.report_unknown_fields = val.to_lowercase() == "true" || val == "1";
+        }
+        if let Ok(val) = env::var("WS_ALLOW_CLIENT_SLIDES") {
+            config.websocket.allow_client_slides = val.to_lowercase() == "true" || val == "1";
+        }
+        if let Ok(val) = env::var("WS_MAX_CONNECTIONS") {
+            if let Ok(max) = val.parse() {
+                config.websocket.max_connections = max;
+            }
+        }
+
+        // Logging config
+        if let Ok(val) = env::var("LOG_FORMAT") {
+            config.log_format = match val.to_lowercase().as_str() {
+                "json" => LogFormat::Json,
+                _ => LogFormat::Pretty,
+            };
+        }
 
         // Slide config
         if let Ok(val) = env::var("SLIDE_SOURCE") {
@@ -301,11 +712,33 @@ impl Config {
                 config.slide.max_cached_slides = size;
             }
         }
+        if let Ok(val) = env::var("SLIDE_PRELOAD") {
+            let ids: Vec<String> = val
+                .split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect();
+            config.slide.preload = match ids.as_slice() {
+                [] => SlidePreload::Disabled,
+                [all] if all.eq_ignore_ascii_case("all") => SlidePreload::All,
+                _ => SlidePreload::Ids(ids),
+            };
+        }
+        if let Ok(val) = env::var("SLIDE_PRELOAD_KEEP_OPEN") {
+            config.slide.preload_keep_open = val.to_lowercase() == "true" || val == "1";
+        }
+        if let Ok(val) = env::var("SLIDE_NORMALIZE_IDS") {
+            config.slide.normalize_ids = val.to_lowercase() == "true" || val == "1";
+        }
 
         // Overlay config
         if let Ok(path) = env::var("OVERLAY_DIR") {
             config.overlay.overlays_dir = PathBuf::from(path);
         }
+        if let Ok(template) = env::var("OVERLAY_PATH_TEMPLATE") {
+            config.overlay.path_template = Some(template);
+        }
 
         // Fovea rendering config
         if let Ok(val) = env::var("FOVEA_TILE_SIZE") {
@@ -336,6 +769,27 @@ impl Config {
                 config.fovea.tile_cache_mb = v;
             }
         }
+        if let Ok(val) = env::var("FOVEA_MAX_CONCURRENT_ENCODES") {
+            if let Ok(v) = val.parse() {
+                config.fovea.max_concurrent_encodes = v;
+            }
+        }
+        if let Ok(val) = env::var("FOVEA_PLACEHOLDER_TILES") {
+            config.fovea.placeholder_tiles = val.to_lowercase() == "true" || val == "1";
+        }
+        if let Ok(val) = env::var("FOVEA_LOSSLESS_TILES") {
+            config.fovea.lossless_tiles = val.to_lowercase() == "true" || val == "1";
+        }
+        if let Ok(val) = env::var("FOVEA_MAX_BATCH_TILES") {
+            if let Ok(v) = val.parse() {
+                config.fovea.max_batch_tiles = v;
+            }
+        }
+        if let Ok(val) = env::var("FOVEA_REQUEST_TIMEOUT_SECS") {
+            if let Ok(secs) = val.parse::<u64>() {
+                config.fovea.request_timeout = Duration::from_secs(secs);
+            }
+        }
 
         // Static files config
         if let Ok(path) = env::var("STATIC_FILES_DIR") {
@@ -347,6 +801,327 @@ impl Config {
             config.static_files.compression = val.to_lowercase() == "true" || val == "1";
         }
 
+        // Audit log config
+        if let Ok(path) = env::var("AUDIT_LOG_PATH") {
+            if !path.is_empty() {
+                config.audit.log_path = Some(PathBuf::from(path));
+            }
+        }
+
+        // Demo config
+        if let Ok(val) = env::var("DEMO_ENABLED") {
+            config.demo.enabled = val.to_lowercase() == "true" || val == "1";
+        }
+        if let Ok(val) = env::var("DEMO_SLIDE_ID") {
+            if !val.is_empty() {
+                config.demo.slide_id = Some(val);
+            }
+        }
+        if let Ok(val) = env::var("DEMO_JOIN_SECRET") {
+            if !val.is_empty() {
+                config.demo.join_secret = Some(val);
+            }
+        }
+
+        // Session recording config
+        if let Ok(dir) = env::var("SESSION_RECORDING_DIR") {
+            if !dir.is_empty() {
+                config.recording.dir = Some(PathBuf::from(dir));
+            }
+        }
+
+        // Webhook config
+        if let Ok(url) = env::var("WEBHOOK_URL") {
+            if !url.is_empty() {
+                config.webhook.url = Some(url);
+            }
+        }
+        if let Ok(secret) = env::var("WEBHOOK_SECRET") {
+            if !secret.is_empty() {
+                config.webhook.secret = Some(secret);
+            }
+        }
+        if let Ok(val) = env::var("WEBHOOK_MAX_ATTEMPTS") {
+            if let Ok(attempts) = val.parse::<u32>() {
+                config.webhook.max_attempts = attempts;
+            }
+        }
+        if let Ok(val) = env::var("WEBHOOK_TIMEOUT_SECS") {
+            if let Ok(secs) = val.parse::<u64>() {
+                config.webhook.timeout = Duration::from_secs(secs);
+            }
+        }
+
+        // TLS config
+        if let Ok(path) = env::var("TLS_CERT_PATH") {
+            if !path.is_empty() {
+                config.tls.cert_path = Some(PathBuf::from(path));
+            }
+        }
+        if let Ok(path) = env::var("TLS_KEY_PATH") {
+            if !path.is_empty() {
+                config.tls.key_path = Some(PathBuf::from(path));
+            }
+        }
+
         config
     }
+
+    /// Parsed `TRUSTED_PROXIES`, skipping entries rejected by [`Config::validate`]
+    pub fn trusted_proxy_networks(&self) -> Vec<IpCidr> {
+        self.trusted_proxies
+            .iter()
+            .filter_map(|entry| entry.parse().ok())
+            .collect()
+    }
+
+    /// Check configuration invariants, reporting every problem found
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+
+        if self.port == 0 {
+            problems.push(ConfigProblem::InvalidPort);
+        }
+
+        let positive = [
+            ("MAX_FOLLOWERS", self.session.max_followers > 0),
+            ("MAX_FOLLOWERS_CAP", self.session.max_followers_cap > 0),
+            (
+                "MAX_CONCURRENT_SESSIONS",
+                self.session.max_concurrent_sessions > 0,
+            ),
+            (
+                "SESSION_MAX_DURATION_HOURS",
+                !self.session.max_duration.is_zero(),
+            ),
+            ("SESSION_EXTENSION_MINS", !self.session.extension.is_zero()),
+            (
+                "SESSION_CLEANUP_INTERVAL_SECS",
+                !self.session.cleanup_interval.is_zero(),
+            ),
+            (
+                "WS_PING_INTERVAL_SECS",
+                !self.websocket.ping_interval.is_zero(),
+            ),
+            (
+                "WS_PING_TIMEOUT_SECS",
+                !self.websocket.ping_timeout.is_zero(),
+            ),
+            ("WS_MAX_CONNECTIONS", self.websocket.max_connections > 0),
+            ("CURSOR_BROADCAST_HZ", self.presence.cursor_broadcast_hz > 0),
+            ("CURSOR_MAX_HZ", self.presence.cursor_max_hz > 0),
+            (
+                "VIEWPORT_BROADCAST_HZ",
+                self.presence.viewport_broadcast_hz > 0,
+            ),
+            (
+                "CURSOR_STALE_SECS",
+                !self.presence.cursor_stale_after.is_zero(),
+            ),
+            ("FOVEA_TILE_SIZE", self.fovea.tile_size > 0),
+            (
+                "FOVEA_MAX_CONCURRENT_ENCODES",
+                self.fovea.max_concurrent_encodes > 0,
+            ),
+            ("FOVEA_MAX_BATCH_TILES", self.fovea.max_batch_tiles > 0),
+            (
+                "FOVEA_REQUEST_TIMEOUT_SECS",
+                !self.fovea.request_timeout.is_zero(),
+            ),
+            ("WEBHOOK_MAX_ATTEMPTS", self.webhook.max_attempts > 0),
+            ("WEBHOOK_TIMEOUT_SECS", !self.webhook.timeout.is_zero()),
+        ];
+        for (name, ok) in positive {
+            if !ok {
+                problems.push(ConfigProblem::NotPositive(name));
+            }
+        }
+
+        for (name, path) in [
+            ("SLIDES_DIR", &self.slide.slides_dir),
+            ("OVERLAY_DIR", &self.overlay.overlays_dir),
+        ] {
+            if !is_usable_directory(path) {
+                problems.push(ConfigProblem::BadDirectory {
+                    name,
+                    path: path.clone(),
+                });
+            }
+        }
+
+        if let Some(url) = self
+            .public_base_url
+            .as_deref()
+            .filter(|url| !is_valid_base_url(url))
+        {
+            problems.push(ConfigProblem::InvalidPublicBaseUrl(url.to_string()));
+        }
+
+        if self.demo.enabled {
+            if self.demo.slide_id.is_none() {
+                problems.push(ConfigProblem::MissingDemoSetting("DEMO_SLIDE_ID"));
+            }
+            if self.demo.join_secret.is_none() {
+                problems.push(ConfigProblem::MissingDemoSetting("DEMO_JOIN_SECRET"));
+            }
+        }
+
+        if self.tls.cert_path.is_some() != self.tls.key_path.is_some() {
+            problems.push(ConfigProblem::IncompleteTls);
+        }
+
+        for feature in &self.session.features {
+            if !OPTIONAL_FEATURES.contains(&feature.as_str()) {
+                problems.push(ConfigProblem::UnknownFeature(feature.clone()));
+            }
+        }
+
+        for message_type in &self.session.replay_types {
+            if !REPLAYABLE_MESSAGE_TYPES.contains(&message_type.as_str()) {
+                problems.push(ConfigProblem::UnknownReplayType(message_type.clone()));
+            }
+        }
+
+        for entry in &self.trusted_proxies {
+            if entry.parse::<IpCidr>().is_err() {
+                problems.push(ConfigProblem::InvalidTrustedProxy(entry.clone()));
+            }
+        }
+
+        if let Some(ref template) = self.overlay.path_template
+            && let Err(e) = OverlayPathTemplate::parse(template)
+        {
+            problems.push(ConfigProblem::InvalidOverlayTemplate {
+                template: template.clone(),
+                reason: e.to_string(),
+            });
+        }
+
+        if let Some(ref url) = self.webhook.url {
+            if !is_valid_base_url(url) {
+                problems.push(ConfigProblem::InvalidWebhookUrl(url.clone()));
+            }
+            if self.webhook.secret.is_none() {
+                problems.push(ConfigProblem::MissingWebhookSecret);
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError { problems })
+        }
+    }
+}
+
+/// A directory is usable if it exists, or if its nearest existing ancestor is
+/// a directory (so it can be created at startup)
+fn is_usable_directory(path: &Path) -> bool {
+    if path.exists() {
+        return path.is_dir();
+    }
+    path.ancestors()
+        .skip(1)
+        .find(|p| p.as_os_str().is_empty() || p.exists())
+        .is_none_or(|p| p.as_os_str().is_empty() || p.is_dir())
+}
+
+/// An absolute http(s) URL with a host and no whitespace
+fn is_valid_base_url(url: &str) -> bool {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"));
+    match rest {
+        Some(rest) => {
+            let host = rest.split('/').next().unwrap_or("");
+            !host.is_empty() && !url.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert_eq!(Config::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_invalid_config_reports_every_problem() {
+        let mut config = Config {
+            port: 0,
+            public_base_url: Some("pathcollab.example.com".to_string()),
+            ..Config::default()
+        };
+        config.session.max_followers = 0;
+        config.websocket.ping_interval = Duration::ZERO;
+        config.demo.enabled = true;
+        config.demo.slide_id = Some("demo-slide".to_string());
+        config.tls.cert_path = Some(PathBuf::from("/etc/pathcollab/cert.pem"));
+        config.session.features = vec!["chat".to_string(), "telepathy".to_string()];
+        config.session.replay_types = vec!["chat_message".to_string(), "slide_changed".to_string()];
+        config.trusted_proxies = vec!["10.0.0.0/8".to_string(), "proxy.internal".to_string()];
+        config.overlay.path_template = Some("{overlays_dir}/shared.bin".to_string());
+        config.webhook.url = Some("hooks.example.com/pathcollab".to_string());
+
+        let err = config.validate().unwrap_err();
+        assert_eq!(
+            err.problems,
+            vec![
+                ConfigProblem::InvalidPort,
+                ConfigProblem::NotPositive("MAX_FOLLOWERS"),
+                ConfigProblem::NotPositive("WS_PING_INTERVAL_SECS"),
+                ConfigProblem::InvalidPublicBaseUrl("pathcollab.example.com".to_string()),
+                ConfigProblem::MissingDemoSetting("DEMO_JOIN_SECRET"),
+                ConfigProblem::IncompleteTls,
+                ConfigProblem::UnknownFeature("telepathy".to_string()),
+                ConfigProblem::UnknownReplayType("slide_changed".to_string()),
+                ConfigProblem::InvalidTrustedProxy("proxy.internal".to_string()),
+                ConfigProblem::InvalidOverlayTemplate {
+                    template: "{overlays_dir}/shared.bin".to_string(),
+                    reason: "must contain {slide_id} or {slide_name}".to_string(),
+                },
+                ConfigProblem::InvalidWebhookUrl("hooks.example.com/pathcollab".to_string()),
+                ConfigProblem::MissingWebhookSecret,
+            ]
+        );
+        assert!(
+            err.to_string()
+                .contains("MAX_FOLLOWERS must be greater than zero")
+        );
+    }
+
+    #[test]
+    fn test_slides_dir_that_is_a_file_is_rejected() {
+        let file = std::env::temp_dir().join(format!("pathcollab-config-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&file, b"").unwrap();
+
+        let mut config = Config::default();
+        config.slide.slides_dir = file.clone();
+        // A missing directory under an existing one can be created
+        config.overlay.overlays_dir = std::env::temp_dir().join("pathcollab-missing/overlays");
+
+        let err = config.validate().unwrap_err();
+        assert_eq!(
+            err.problems,
+            vec![ConfigProblem::BadDirectory {
+                name: "SLIDES_DIR",
+                path: file.clone(),
+            }]
+        );
+
+        let _ = std::fs::remove_file(&file);
+    }
+
+    #[test]
+    fn test_public_base_url_forms() {
+        assert!(is_valid_base_url("https://pathcollab.example.com"));
+        assert!(is_valid_base_url("http://localhost:3000/app"));
+        assert!(!is_valid_base_url("ftp://example.com"));
+        assert!(!is_valid_base_url("https://"));
+        assert!(!is_valid_base_url("https://exa mple.com"));
+    }
 }
@@ -0,0 +1,88 @@
+//! Cross-origin access to the HTTP API
+//!
+//! Any origin may call the API, but request headers and methods are listed
+//! explicitly: browsers do not accept a wildcard `Access-Control-Allow-Headers`
+//! for every kind of request, and the session routes depend on custom
+//! credential headers passing preflight. Response headers that clients read
+//! (tile caching validators, timing, the placeholder marker) are exposed.
+
+use crate::fovea::TILE_PLACEHOLDER_HEADER;
+use crate::request_id::REQUEST_ID_HEADER;
+use crate::server::sessions::{JOIN_SECRET_HEADER, PRESENTER_KEY_HEADER};
+use axum::http::{HeaderName, Method, header};
+use tower_http::cors::{Any, CorsLayer};
+
+/// CORS policy for every route
+pub fn cors_layer() -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(Any)
+        .allow_methods([
+            Method::GET,
+            Method::HEAD,
+            Method::POST,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            header::IF_NONE_MATCH,
+            header::IF_MODIFIED_SINCE,
+            HeaderName::from_static(JOIN_SECRET_HEADER),
+            HeaderName::from_static(PRESENTER_KEY_HEADER),
+            HeaderName::from_static(REQUEST_ID_HEADER),
+        ])
+        .expose_headers([
+            header::ETAG,
+            header::LAST_MODIFIED,
+            HeaderName::from_static("server-timing"),
+            HeaderName::from_static(TILE_PLACEHOLDER_HEADER),
+            HeaderName::from_static(REQUEST_ID_HEADER),
+        ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{AppState, session_routes};
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_preflight_allows_presenter_key_header() {
+        let app = Router::new()
+            .nest("/api", session_routes())
+            .with_state(AppState::new())
+            .layer(cors_layer());
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri("/api/session/abc123/participants")
+                    .header(header::ORIGIN, "https://viewer.example.com")
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+                    .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "x-presenter-key")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+        let allowed = headers[header::ACCESS_CONTROL_ALLOW_HEADERS]
+            .to_str()
+            .unwrap();
+        assert!(allowed.split(',').any(|h| h.trim() == PRESENTER_KEY_HEADER));
+        let methods = headers[header::ACCESS_CONTROL_ALLOW_METHODS]
+            .to_str()
+            .unwrap();
+        assert!(methods.contains("GET"));
+    }
+}
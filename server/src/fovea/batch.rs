@@ -0,0 +1,237 @@
+//! Batch slide tile fetching.
+//!
+//! `POST /api/slide/:id/tiles` takes a JSON array of `{ level, x, y }` and
+//! returns the tiles in request order as a length-prefixed stream, so thumbnail
+//! grids and low-bandwidth clients pay for one HTTP round trip instead of one
+//! per tile. Each part is framed as:
+//!
+//! ```text
+//! u16 (big-endian)  HTTP status of this tile
+//! u32 (big-endian)  body length in bytes
+//! [length bytes]    encoded tile on 200, error text otherwise
+//! ```
+//!
+//! Tiles are fetched concurrently through fovea-pack, bounded by the shared
+//! encode limiter, so a batch never bypasses the per-server encode budget.
+
+use axum::{
+    Json,
+    body::{Bytes, to_bytes},
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use fovea_pack::{SlideSources, route_request};
+use futures_util::future::join_all;
+use metrics::histogram;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::{FoveaAppState, Prepared, TileEncoding, prepare_failure_response, within_timeout};
+
+/// Content type of a batch tile response.
+pub const TILE_BATCH_CONTENT_TYPE: &str = "application/x-pathcollab-tile-batch";
+
+/// One requested slide tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TileRef {
+    pub level: u32,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl TileRef {
+    /// fovea-pack path of this tile in the slide pyramid.
+    fn path(&self) -> String {
+        format!(
+            "/slide/images/level_{}/{}_{}.jpg",
+            self.level, self.x, self.y
+        )
+    }
+}
+
+/// POST /api/slide/:id/tiles - Fetch several slide tiles in one response
+pub(super) async fn handle_tile_batch(
+    State(state): State<FoveaAppState>,
+    Path(id): Path<String>,
+    Json(tiles): Json<Vec<TileRef>>,
+) -> Response {
+    let id = state.slide_id(id);
+    let max = state.inner.config.max_batch_tiles;
+    if tiles.is_empty() {
+        return (StatusCode::BAD_REQUEST, "no tiles requested").into_response();
+    }
+    if tiles.len() > max {
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("at most {max} tiles per batch"),
+        )
+            .into_response();
+    }
+
+    let sources = match state.prepare(&id, None, TileEncoding::Jpeg).await {
+        Prepared::Ready(sources) => sources,
+        other => return prepare_failure_response(&id, other),
+    };
+
+    histogram!("pathcollab_tile_batch_size").record(tiles.len() as f64);
+    let parts = join_all(
+        tiles
+            .iter()
+            .map(|tile| fetch_tile(&state, &sources, &id, tile)),
+    )
+    .await;
+
+    (
+        [(header::CONTENT_TYPE, TILE_BATCH_CONTENT_TYPE)],
+        encode_batch(&parts),
+    )
+        .into_response()
+}
+
+/// Fetch one tile through the encode limiter; failures become per-tile errors.
+async fn fetch_tile(
+    state: &FoveaAppState,
+    sources: &SlideSources,
+    id: &str,
+    tile: &TileRef,
+) -> (StatusCode, Bytes) {
+    let path = tile.path();
+    let routed = state
+        .inner
+        .encodes
+        .run(within_timeout(
+            state.inner.config.request_timeout,
+            route_request(sources, &path),
+        ))
+        .await;
+    match routed {
+        Ok(response) => {
+            let status = response.status();
+            match to_bytes(response.into_body(), usize::MAX).await {
+                Ok(body) => (status, body),
+                Err(err) => {
+                    warn!("fovea: failed to read tile {id} {path}: {err}");
+                    internal_error()
+                }
+            }
+        }
+        Err(err) => {
+            warn!("fovea: route_request failed for {id} {path}: {err:#}");
+            internal_error()
+        }
+    }
+}
+
+fn internal_error() -> (StatusCode, Bytes) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Bytes::from_static(b"internal server error"),
+    )
+}
+
+/// Frame tile parts as `status | length | body`, in order.
+fn encode_batch(parts: &[(StatusCode, Bytes)]) -> Vec<u8> {
+    let total: usize = parts.iter().map(|(_, body)| 6 + body.len()).sum();
+    let mut out = Vec::with_capacity(total);
+    for (status, body) in parts {
+        out.extend_from_slice(&status.as_u16().to_be_bytes());
+        out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        out.extend_from_slice(body);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{FoveaConfig, OverlayConfig, SlideConfig};
+    use crate::fovea::fovea_routes;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    const JPEG: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, 0xFF, 0xD9];
+
+    /// Split a batch body back into `(status, body)` parts.
+    fn decode_batch(mut data: &[u8]) -> Vec<(u16, Vec<u8>)> {
+        let mut parts = Vec::new();
+        while !data.is_empty() {
+            let status = u16::from_be_bytes([data[0], data[1]]);
+            let len = u32::from_be_bytes([data[2], data[3], data[4], data[5]]) as usize;
+            parts.push((status, data[6..6 + len].to_vec()));
+            data = &data[6 + len..];
+        }
+        parts
+    }
+
+    #[test]
+    fn test_batch_parts_are_tiles_or_errors() {
+        let parts = vec![
+            (StatusCode::OK, Bytes::from_static(JPEG)),
+            (StatusCode::NOT_FOUND, Bytes::from_static(b"tile not found")),
+            (StatusCode::OK, Bytes::from_static(JPEG)),
+        ];
+
+        let decoded = decode_batch(&encode_batch(&parts));
+        assert_eq!(decoded.len(), 3);
+        for (status, body) in decoded {
+            match status {
+                200 => assert!(body.starts_with(&[0xFF, 0xD8]) && body.ends_with(&[0xFF, 0xD9])),
+                404 => assert_eq!(body, b"tile not found"),
+                other => panic!("unexpected tile status {other}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_tile_path_matches_pyramid_layout() {
+        let tile = TileRef {
+            level: 3,
+            x: 10,
+            y: 7,
+        };
+        assert_eq!(tile.path(), "/slide/images/level_3/10_7.jpg");
+    }
+
+    #[tokio::test]
+    async fn test_batch_size_is_capped() {
+        let root = std::env::temp_dir().join(format!("pathcollab-batch-{}", uuid::Uuid::new_v4()));
+        let state = FoveaAppState::new(
+            &SlideConfig {
+                slides_dir: root.join("slides"),
+                ..Default::default()
+            },
+            &OverlayConfig {
+                overlays_dir: root.join("overlays"),
+                ..Default::default()
+            },
+            FoveaConfig {
+                max_batch_tiles: 2,
+                ..Default::default()
+            },
+        );
+        let tiles = serde_json::to_string(&vec![
+            TileRef {
+                level: 0,
+                x: 0,
+                y: 0
+            };
+            3
+        ])
+        .unwrap();
+
+        let response = fovea_routes(state)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/slide/test/tiles")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(tiles))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
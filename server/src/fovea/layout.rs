@@ -0,0 +1,304 @@
+//! On-disk location of a slide's overlays.
+//!
+//! [`OverlayLayout`] finds a slide's primary and named overlay files. It is
+//! shared by the fovea forwarder, which prepares them, and the slide catalog,
+//! which reports whether a slide has any.
+//!
+//! `OVERLAY_PATH_TEMPLATE` replaces the built-in overlay layout search with a
+//! single path, e.g. `{overlays_dir}/{slide_id}.bin` or
+//! `{slides_dir}/{slide_id}/overlays.bin`. Templates are checked at startup by
+//! `Config::validate`.
+
+use super::{OVERLAY_EXTENSIONS, is_valid_overlay_id};
+use crate::config::{OverlayConfig, SlideConfig};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Values a template can refer to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placeholder {
+    OverlaysDir,
+    SlidesDir,
+    SlideId,
+    SlideName,
+}
+
+/// Placeholder names as written in templates.
+const PLACEHOLDERS: &[(&str, Placeholder)] = &[
+    ("overlays_dir", Placeholder::OverlaysDir),
+    ("slides_dir", Placeholder::SlidesDir),
+    ("slide_id", Placeholder::SlideId),
+    ("slide_name", Placeholder::SlideName),
+];
+
+/// Why an overlay path template was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum OverlayTemplateError {
+    #[error("unknown placeholder {{{0}}}")]
+    UnknownPlaceholder(String),
+
+    #[error("unbalanced brace")]
+    UnbalancedBrace,
+
+    /// Without a per-slide placeholder every slide would share one overlay
+    #[error("must contain {{slide_id}} or {{slide_name}}")]
+    MissingSlidePlaceholder,
+}
+
+/// A piece of a parsed template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Placeholder(Placeholder),
+}
+
+/// Parsed `OVERLAY_PATH_TEMPLATE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlayPathTemplate {
+    segments: Vec<Segment>,
+}
+
+/// Values substituted into an [`OverlayPathTemplate`].
+pub struct OverlayPathVars<'a> {
+    pub overlays_dir: &'a Path,
+    pub slides_dir: &'a Path,
+    /// Sanitized slide id
+    pub slide_id: &'a str,
+    /// Slide file name, extension included
+    pub slide_name: &'a str,
+}
+
+impl OverlayPathTemplate {
+    pub fn parse(template: &str) -> Result<Self, OverlayTemplateError> {
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while let Some(open) = rest.find(['{', '}']) {
+            if rest[open..].starts_with('}') {
+                return Err(OverlayTemplateError::UnbalancedBrace);
+            }
+            if open > 0 {
+                segments.push(Segment::Literal(rest[..open].to_string()));
+            }
+            let after = &rest[open + 1..];
+            let close = after
+                .find('}')
+                .ok_or(OverlayTemplateError::UnbalancedBrace)?;
+            let name = &after[..close];
+            if name.contains('{') {
+                return Err(OverlayTemplateError::UnbalancedBrace);
+            }
+            let (_, placeholder) = PLACEHOLDERS
+                .iter()
+                .find(|(known, _)| *known == name)
+                .ok_or_else(|| OverlayTemplateError::UnknownPlaceholder(name.to_string()))?;
+            segments.push(Segment::Placeholder(*placeholder));
+            rest = &after[close + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+
+        let per_slide = segments.iter().any(|s| {
+            matches!(
+                s,
+                Segment::Placeholder(Placeholder::SlideId | Placeholder::SlideName)
+            )
+        });
+        if !per_slide {
+            return Err(OverlayTemplateError::MissingSlidePlaceholder);
+        }
+        Ok(Self { segments })
+    }
+
+    /// Path of the overlay described by `vars`.
+    pub fn resolve(&self, vars: &OverlayPathVars<'_>) -> PathBuf {
+        let mut path = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => path.push_str(text),
+                Segment::Placeholder(Placeholder::OverlaysDir) => {
+                    path.push_str(&vars.overlays_dir.to_string_lossy())
+                }
+                Segment::Placeholder(Placeholder::SlidesDir) => {
+                    path.push_str(&vars.slides_dir.to_string_lossy())
+                }
+                Segment::Placeholder(Placeholder::SlideId) => path.push_str(vars.slide_id),
+                Segment::Placeholder(Placeholder::SlideName) => path.push_str(vars.slide_name),
+            }
+        }
+        PathBuf::from(path)
+    }
+}
+
+/// Where overlay files live for the configured slide and overlay directories
+#[derive(Debug, Clone)]
+pub struct OverlayLayout {
+    slides_dir: PathBuf,
+    overlays_dir: PathBuf,
+    /// Replaces the built-in primary overlay layouts when set
+    template: Option<OverlayPathTemplate>,
+}
+
+impl OverlayLayout {
+    pub fn new(slide: &SlideConfig, overlay: &OverlayConfig) -> Self {
+        Self {
+            slides_dir: slide.slides_dir.clone(),
+            overlays_dir: overlay.overlays_dir.clone(),
+            // Validated at startup; an invalid template falls back to the
+            // built-in layouts
+            template: overlay
+                .path_template
+                .as_deref()
+                .and_then(|t| OverlayPathTemplate::parse(t).ok()),
+        }
+    }
+
+    /// Candidate subdirectories for a slide's overlays: named after the
+    /// sanitized id and after the raw slide filename.
+    fn subdirs(&self, id: &str, wsi_path: &Path) -> Vec<PathBuf> {
+        let mut subdirs = vec![self.overlays_dir.join(id)];
+        if let Some(name) = wsi_path.file_name().and_then(|n| n.to_str()) {
+            subdirs.push(self.overlays_dir.join(name));
+        }
+        subdirs
+    }
+
+    /// Resolve a slide's primary overlay protobuf path, if one exists. Supports
+    /// several on-disk layouts:
+    ///   - `{overlays_dir}/{id}.bin` / `{id}.pb`
+    ///   - `{overlays_dir}/{id}/cell_masks.bin` / `cell_masks.pb`
+    ///   - `{overlays_dir}/{wsi_file_name}/cell_masks.bin` (subdir named after the
+    ///     full slide filename, e.g. `TCGA-….svs/cell_masks.bin`)
+    ///
+    /// With `OVERLAY_PATH_TEMPLATE` set, only the templated path is checked.
+    pub fn find_primary(&self, id: &str, wsi_path: &Path) -> Option<PathBuf> {
+        if let Some(ref template) = self.template {
+            let path = template.resolve(&OverlayPathVars {
+                overlays_dir: &self.overlays_dir,
+                slides_dir: &self.slides_dir,
+                slide_id: id,
+                slide_name: wsi_path.file_name().and_then(|n| n.to_str()).unwrap_or(id),
+            });
+            return path.is_file().then_some(path);
+        }
+
+        for ext in OVERLAY_EXTENSIONS {
+            let path = self.overlays_dir.join(format!("{id}.{ext}"));
+            if path.exists() {
+                return Some(path);
+            }
+        }
+
+        for subdir in self.subdirs(id, wsi_path) {
+            for ext in OVERLAY_EXTENSIONS {
+                let path = subdir.join(format!("cell_masks.{ext}"));
+                if path.exists() {
+                    return Some(path);
+                }
+            }
+        }
+        None
+    }
+
+    /// Resolve a named overlay at `{subdir}/overlays/{overlay_id}.bin` / `.pb`.
+    pub fn find_named(&self, id: &str, wsi_path: &Path, overlay_id: &str) -> Option<PathBuf> {
+        if !is_valid_overlay_id(overlay_id) {
+            return None;
+        }
+        for subdir in self.subdirs(id, wsi_path) {
+            for ext in OVERLAY_EXTENSIONS {
+                let path = subdir.join("overlays").join(format!("{overlay_id}.{ext}"));
+                if path.is_file() {
+                    return Some(path);
+                }
+            }
+        }
+        None
+    }
+
+    /// Ids of a slide's named overlays, sorted.
+    pub fn named_overlays(&self, id: &str, wsi_path: &Path) -> Vec<String> {
+        let mut named = Vec::new();
+        for subdir in self.subdirs(id, wsi_path) {
+            let Ok(entries) = std::fs::read_dir(subdir.join("overlays")) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let is_overlay = path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| OVERLAY_EXTENSIONS.contains(&e));
+                if !path.is_file() || !is_overlay {
+                    continue;
+                }
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+                    && is_valid_overlay_id(stem)
+                    && !named.iter().any(|n| n == stem)
+                {
+                    named.push(stem.to_string());
+                }
+            }
+        }
+        named.sort();
+        named
+    }
+
+    /// Whether the slide has any overlay to show: a primary or a named one.
+    pub fn has_overlay(&self, id: &str, wsi_path: &Path) -> bool {
+        self.find_primary(id, wsi_path).is_some() || !self.named_overlays(id, wsi_path).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_template() {
+        let vars = OverlayPathVars {
+            overlays_dir: Path::new("/data/overlays"),
+            slides_dir: Path::new("/data/slides"),
+            slide_id: "case_1",
+            slide_name: "case 1.svs",
+        };
+
+        let flat = OverlayPathTemplate::parse("{overlays_dir}/{slide_id}.bin").unwrap();
+        assert_eq!(
+            flat.resolve(&vars),
+            PathBuf::from("/data/overlays/case_1.bin")
+        );
+
+        let beside = OverlayPathTemplate::parse("{slides_dir}/{slide_name}/overlays.bin").unwrap();
+        assert_eq!(
+            beside.resolve(&vars),
+            PathBuf::from("/data/slides/case 1.svs/overlays.bin")
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_templates() {
+        assert_eq!(
+            OverlayPathTemplate::parse("{overlays_dir}/{slide}.bin"),
+            Err(OverlayTemplateError::UnknownPlaceholder(
+                "slide".to_string()
+            ))
+        );
+        assert_eq!(
+            OverlayPathTemplate::parse("{overlays_dir/{slide_id}.bin"),
+            Err(OverlayTemplateError::UnbalancedBrace)
+        );
+        assert_eq!(
+            OverlayPathTemplate::parse("{slide_id}}.bin"),
+            Err(OverlayTemplateError::UnbalancedBrace)
+        );
+        assert_eq!(
+            OverlayPathTemplate::parse("{slide_id"),
+            Err(OverlayTemplateError::UnbalancedBrace)
+        );
+        assert_eq!(
+            OverlayPathTemplate::parse("{overlays_dir}/shared.bin"),
+            Err(OverlayTemplateError::MissingSlidePlaceholder)
+        );
+    }
+}
@@ -4,33 +4,116 @@
 //! `/api/fovea/:id/*` to fovea-pack's router, which serves the slide tile
 //! pyramid, cell chunks, and density heatmap directly from a WSI + an overlay
 //! protobuf. This module owns only:
-//!   1. resolving a slide id to its WSI path + (optional) overlay protobuf path, and
+//!   1. resolving a slide id (+ optional overlay id) to its WSI path and
+//!      overlay protobuf path, and
 //!   2. the per-slide lifecycle: lazily preparing `SlideSources` once (deduped
 //!      across concurrent requests) and caching them.
 //!
+//! A slide may carry several overlays (different models, timepoints). Named
+//! overlays live at `{overlays_dir}/{id}/overlays/{overlay_id}.bin`; the legacy
+//! single-overlay layouts are exposed as the `primary` overlay. Requests pick
+//! one with `?overlay=`, defaulting to the primary. `OVERLAY_PATH_TEMPLATE`
+//! pins the primary overlay to a single path instead (see [`layout`]).
+//!
+//! Overlay manifests (`cells/manifest.json`, `heatmap/manifest.json`) carry an
+//! `ETag` version and `Last-Modified` derived from the overlay file, and honor
+//! `If-None-Match` / `If-Modified-Since` with 304 so polling viewers stay cheap.
+//!
+//! Responses carry a `Server-Timing` header splitting time spent waiting for an
+//! encode slot (`queue`) from time spent in fovea-pack (`render`), so slow
+//! tiles can be attributed to saturation vs. slide reads/encodes.
+//!
+//! A fovea-pack request that runs longer than `FOVEA_REQUEST_TIMEOUT_SECS` is
+//! dropped (cancelling the read/encode at its next await point) and answered
+//! with 504 Gateway Timeout, so a hung slide read doesn't pin a connection.
+//!
+//! With `FOVEA_PLACEHOLDER_TILES` set, a slide tile whose read fails is served
+//! as a non-cacheable placeholder JPEG marked `X-Tile-Placeholder: true`
+//! instead of an error, so viewers don't show broken images mid-session.
+//!
+//! With `FOVEA_LOSSLESS_TILES` set, `?lossless=true` serves slide tiles as PNG
+//! instead of JPEG for close review without compression artifacts. Lossless
+//! sources are prepared and cached separately from the JPEG ones, so normal
+//! browsing is unaffected. Otherwise lossless requests get 403 Forbidden.
+//!
+//! `POST /api/slide/:id/tiles` fetches many slide tiles in one request (see
+//! [`batch`]).
+//!
 //! All tiling, manifest building, cell-chunk encoding, heatmap building, path
 //! parsing, and tile caching live in fovea-pack — never duplicated here.
 
+mod batch;
+mod layout;
+
+pub use batch::{TILE_BATCH_CONTENT_TYPE, TileRef};
+pub use layout::{OverlayLayout, OverlayPathTemplate, OverlayPathVars, OverlayTemplateError};
+
+use std::future::Future;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use axum::{
-    Router,
-    extract::{Path, State},
-    http::StatusCode,
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware,
     response::{IntoResponse, Response},
-    routing::get,
+    routing::{get, post},
 };
 use dashmap::DashMap;
 use fovea_pack::{ImageFormat, SlideSources, SourceOptions, prepare_sources, route_request};
-use tokio::sync::OnceCell;
+use metrics::{counter, gauge};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OnceCell, Semaphore};
 use tracing::{info, warn};
 
 use crate::config::{FoveaConfig, OverlayConfig, SlideConfig};
+use crate::http_metrics::track_http_metrics;
+use crate::slide::normalize_slide_id;
 
 /// Slide file extensions OpenSlide (via fovea-pack) can read.
 const SLIDE_EXTENSIONS: &[&str] = &["svs", "ndpi", "tiff", "tif", "vms", "vmu", "scn", "mrxs"];
 
+/// Overlay id under which the legacy single-overlay layouts are exposed.
+pub const PRIMARY_OVERLAY_ID: &str = "primary";
+
+/// Solid light-gray JPEG served in place of slide tiles that fail to read.
+const PLACEHOLDER_TILE: &[u8] = include_bytes!("../../assets/placeholder_tile.jpg");
+
+/// Response header marking a placeholder tile.
+pub const TILE_PLACEHOLDER_HEADER: &str = "x-tile-placeholder";
+
+/// Overlay protobuf extensions, in lookup priority order.
+const OVERLAY_EXTENSIONS: &[&str] = &["bin", "pb"];
+
+/// Image encoding of slide tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+enum TileEncoding {
+    /// Lossy JPEG, used for normal browsing
+    #[default]
+    Jpeg,
+    /// Lossless PNG, for review at high zoom
+    Lossless,
+}
+
+impl TileEncoding {
+    fn image_format(self) -> ImageFormat {
+        match self {
+            Self::Jpeg => ImageFormat::Jpeg,
+            Self::Lossless => ImageFormat::Png,
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Lossless => "image/png",
+        }
+    }
+}
+
 /// Per-slide preparation slot. A `OnceCell` dedups concurrent first requests
 /// (slide + cells + heatmap manifests arrive together): preparation runs once
 /// via `get_or_try_init`, and a failed attempt leaves the cell uninitialized so
@@ -44,29 +127,261 @@ pub struct FoveaAppState {
 
 struct FoveaInner {
     slides_dir: PathBuf,
-    overlays_dir: PathBuf,
+    layout: OverlayLayout,
     config: FoveaConfig,
-    sources: DashMap<String, SourceSlot>,
+    /// Prepared sources keyed by (slide id, resolved overlay id, tile encoding).
+    sources: DashMap<(String, Option<String>, TileEncoding), SourceSlot>,
+    /// Bounds concurrent fovea-pack request handling across all slides.
+    encodes: EncodeLimiter,
+    /// Normalize slide ids the same way the slide catalog does.
+    normalize_ids: bool,
+}
+
+/// Bounds concurrent tile/cell encoding. OpenSlide reads + JPEG encodes are
+/// CPU-bound, so requests beyond the limit queue instead of oversubscribing
+/// cores. Queue depth is exported as `pathcollab_tile_encode_queue_depth`.
+struct EncodeLimiter {
+    permits: Semaphore,
+    queued: AtomicUsize,
+}
+
+impl EncodeLimiter {
+    fn new(max_concurrent: usize) -> Self {
+        Self {
+            permits: Semaphore::new(max_concurrent.max(1)),
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// Run `work` once a permit is available.
+    async fn run<F: Future>(&self, work: F) -> F::Output {
+        self.run_timed(work).await.0
+    }
+
+    /// Like [`run`](Self::run), also reporting time queued and time working.
+    async fn run_timed<F: Future>(&self, work: F) -> (F::Output, ServerTiming) {
+        let queued_at = Instant::now();
+        let _permit = {
+            let _queued = QueueSlot::enter(&self.queued);
+            self.permits
+                .acquire()
+                .await
+                .expect("encode semaphore is never closed")
+        };
+        let started_at = Instant::now();
+        let output = work.await;
+        let timing = ServerTiming {
+            queue: started_at - queued_at,
+            render: started_at.elapsed(),
+        };
+        (output, timing)
+    }
+}
+
+/// Per-request timing exposed via the `Server-Timing` header.
+#[derive(Debug, Clone, Copy)]
+struct ServerTiming {
+    /// Waiting for an encode permit
+    queue: Duration,
+    /// Inside fovea-pack (cache lookup, slide read, encode)
+    render: Duration,
+}
+
+impl ServerTiming {
+    fn header_value(&self) -> String {
+        format!(
+            "queue;dur={:.3}, render;dur={:.3}",
+            self.queue.as_secs_f64() * 1000.0,
+            self.render.as_secs_f64() * 1000.0
+        )
+    }
+
+    fn apply(&self, headers: &mut HeaderMap) {
+        if let Ok(value) = HeaderValue::from_str(&self.header_value()) {
+            headers.insert("server-timing", value);
+        }
+    }
+}
+
+/// Counts a request as queued until dropped (also on cancellation).
+struct QueueSlot<'a>(&'a AtomicUsize);
+
+impl<'a> QueueSlot<'a> {
+    fn enter(queued: &'a AtomicUsize) -> Self {
+        let depth = queued.fetch_add(1, Ordering::Relaxed) + 1;
+        gauge!("pathcollab_tile_encode_queue_depth").set(depth as f64);
+        Self(queued)
+    }
+}
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        let depth = self.0.fetch_sub(1, Ordering::Relaxed) - 1;
+        gauge!("pathcollab_tile_encode_queue_depth").set(depth as f64);
+    }
 }
 
 enum Prepared {
     Ready(Arc<SlideSources>),
     NotFound,
+    OverlayNotFound,
     Failed(String),
 }
 
+/// Cache validators for an overlay, derived from its file metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OverlayVersion {
+    /// Strong ETag: overlay id, mtime and size
+    etag: String,
+    /// File mtime truncated to whole seconds (HTTP date precision)
+    last_modified: SystemTime,
+}
+
+impl OverlayVersion {
+    fn from_file(overlay_id: &str, path: &std::path::Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let secs = metadata
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        Some(Self {
+            etag: format!("\"{}-{:x}-{:x}\"", overlay_id, secs, metadata.len()),
+            last_modified: UNIX_EPOCH + std::time::Duration::from_secs(secs),
+        })
+    }
+
+    /// Whether the request's conditional headers show the client is up to date.
+    /// `If-None-Match` takes precedence over `If-Modified-Since`.
+    fn is_fresh(&self, headers: &HeaderMap) -> bool {
+        if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+            let Ok(tags) = if_none_match.to_str() else {
+                return false;
+            };
+            return tags
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == self.etag);
+        }
+        headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| httpdate::parse_http_date(v).ok())
+            .is_some_and(|since| self.last_modified <= since)
+    }
+
+    fn apply(&self, headers: &mut HeaderMap) {
+        if let Ok(etag) = HeaderValue::from_str(&self.etag) {
+            headers.insert(header::ETAG, etag);
+        }
+        if let Ok(date) = HeaderValue::from_str(&httpdate::fmt_http_date(self.last_modified)) {
+            headers.insert(header::LAST_MODIFIED, date);
+        }
+    }
+}
+
+/// Run a fovea-pack request, abandoning it after `limit` with a 504.
+async fn within_timeout<F>(limit: Duration, work: F) -> anyhow::Result<Response>
+where
+    F: Future<Output = anyhow::Result<Response>>,
+{
+    match tokio::time::timeout(limit, work).await {
+        Ok(routed) => routed,
+        Err(_) => {
+            counter!("pathcollab_tile_timeouts_total").increment(1);
+            Ok((StatusCode::GATEWAY_TIMEOUT, "fovea request timed out").into_response())
+        }
+    }
+}
+
+/// Whether a fovea path is a slide pyramid tile.
+fn is_slide_tile(rest: &str) -> bool {
+    rest.starts_with("slide/images/")
+}
+
+/// Replace a failed tile read with the placeholder tile. Client errors
+/// (unknown level, out-of-range coordinates) pass through unchanged.
+fn with_placeholder(routed: anyhow::Result<Response>, path: &str) -> anyhow::Result<Response> {
+    match routed {
+        Ok(response) if !response.status().is_server_error() => Ok(response),
+        Ok(response) => {
+            warn!(
+                "fovea: tile read failed for {path} ({}), serving placeholder",
+                response.status()
+            );
+            Ok(placeholder_tile_response())
+        }
+        Err(err) => {
+            warn!("fovea: tile read failed for {path}: {err:#}, serving placeholder");
+            Ok(placeholder_tile_response())
+        }
+    }
+}
+
+fn placeholder_tile_response() -> Response {
+    counter!("pathcollab_tile_placeholders_total").increment(1);
+    (
+        [
+            (header::CONTENT_TYPE, "image/jpeg"),
+            (header::CACHE_CONTROL, "no-store"),
+            (
+                header::HeaderName::from_static(TILE_PLACEHOLDER_HEADER),
+                "true",
+            ),
+        ],
+        PLACEHOLDER_TILE,
+    )
+        .into_response()
+}
+
+/// Whether a fovea path is an overlay-derived manifest.
+fn is_overlay_manifest(rest: &str) -> bool {
+    matches!(rest, "cells/manifest.json" | "heatmap/manifest.json")
+}
+
+/// Result of resolving a slide's overlay selection.
+enum OverlaySelection {
+    /// The slide has no overlay at all (only valid when none was requested).
+    None,
+    /// The chosen overlay id and its protobuf path.
+    Found(String, PathBuf),
+    /// An explicitly requested overlay id does not exist.
+    Missing,
+}
+
 impl FoveaAppState {
     pub fn new(slide: &SlideConfig, overlay: &OverlayConfig, config: FoveaConfig) -> Self {
         Self {
             inner: Arc::new(FoveaInner {
                 slides_dir: slide.slides_dir.clone(),
-                overlays_dir: overlay.overlays_dir.clone(),
+                layout: OverlayLayout::new(slide, overlay),
+                encodes: EncodeLimiter::new(config.max_concurrent_encodes),
                 config,
                 sources: DashMap::new(),
+                normalize_ids: slide.normalize_ids,
             }),
         }
     }
 
+    /// Drop a slide's prepared sources (and their tile caches) for every
+    /// overlay and encoding, e.g. after the slide file is deleted.
+    pub fn evict_slide(&self, id: &str) {
+        let id = self.slide_id(id.to_string());
+        self.inner
+            .sources
+            .retain(|(slide_id, _, _), _| *slide_id != id);
+    }
+
+    /// The catalog id for a requested slide id (see `SlideConfig::normalize_ids`).
+    fn slide_id(&self, id: String) -> String {
+        if self.inner.normalize_ids {
+            normalize_slide_id(&id)
+        } else {
+            id
+        }
+    }
+
     /// Resolve a slide id to its WSI path by scanning the slides directory.
     fn find_slide_path(&self, id: &str) -> Option<PathBuf> {
         let entries = std::fs::read_dir(&self.inner.slides_dir).ok()?;
@@ -83,7 +398,10 @@ impl FoveaAppState {
             if !SLIDE_EXTENSIONS.contains(&ext.as_str()) {
                 continue;
             }
-            let stem = path.file_stem().and_then(|s| s.to_str()).map(sanitize_id);
+            let stem = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(|s| self.slide_id(sanitize_id(s)));
             if stem.as_deref() == Some(id) {
                 return Some(path);
             }
@@ -91,43 +409,84 @@ impl FoveaAppState {
         None
     }
 
-    /// Resolve a slide's overlay protobuf path, if one exists. Supports several
-    /// on-disk layouts:
-    ///   - `{overlays_dir}/{id}.bin` / `{id}.pb`
-    ///   - `{overlays_dir}/{id}/cell_masks.bin` / `cell_masks.pb`
-    ///   - `{overlays_dir}/{wsi_file_name}/cell_masks.bin` (subdir named after the
-    ///     full slide filename, e.g. `TCGA-….svs/cell_masks.bin`)
-    fn find_overlay_path(&self, id: &str, wsi_path: &std::path::Path) -> Option<PathBuf> {
-        for ext in &["bin", "pb"] {
-            let path = self.inner.overlays_dir.join(format!("{id}.{ext}"));
-            if path.exists() {
-                return Some(path);
-            }
+    /// List a slide's overlay ids: `primary` first (if a legacy layout exists),
+    /// then named overlays sorted by id. `None` if the slide does not exist.
+    pub fn list_overlays(&self, id: &str) -> Option<Vec<String>> {
+        let wsi_path = self.find_slide_path(id)?;
+        let named = self.inner.layout.named_overlays(id, &wsi_path);
+
+        let mut overlays = Vec::with_capacity(named.len() + 1);
+        if self.inner.layout.find_primary(id, &wsi_path).is_some() {
+            overlays.push(PRIMARY_OVERLAY_ID.to_string());
         }
+        overlays.extend(named.into_iter().filter(|n| n != PRIMARY_OVERLAY_ID));
+        Some(overlays)
+    }
+
+    /// Resolve the overlay to serve. With no selector this is the default
+    /// overlay: the primary one, else the first named overlay.
+    fn select_overlay(
+        &self,
+        id: &str,
+        wsi_path: &std::path::Path,
+        overlay: Option<&str>,
+    ) -> OverlaySelection {
+        let primary = || {
+            self.inner
+                .layout
+                .find_primary(id, wsi_path)
+                .map(|path| OverlaySelection::Found(PRIMARY_OVERLAY_ID.to_string(), path))
+        };
+        let named = |overlay_id: &str| {
+            self.inner
+                .layout
+                .find_named(id, wsi_path, overlay_id)
+                .map(|path| OverlaySelection::Found(overlay_id.to_string(), path))
+        };
 
-        // Candidate subdirectory names: the sanitized id and the raw slide filename.
-        let mut subdirs: Vec<String> = vec![id.to_string()];
-        if let Some(name) = wsi_path.file_name().and_then(|n| n.to_str()) {
-            subdirs.push(name.to_string());
+        match overlay {
+            Some(PRIMARY_OVERLAY_ID) => primary()
+                .or_else(|| named(PRIMARY_OVERLAY_ID))
+                .unwrap_or(OverlaySelection::Missing),
+            Some(overlay_id) => named(overlay_id).unwrap_or(OverlaySelection::Missing),
+            None => primary()
+                .or_else(|| {
+                    self.list_overlays(id)
+                        .and_then(|ids| ids.into_iter().next())
+                        .and_then(|first| named(&first))
+                })
+                .unwrap_or(OverlaySelection::None),
         }
-        for subdir in subdirs {
-            for filename in &["cell_masks.bin", "cell_masks.pb"] {
-                let path = self.inner.overlays_dir.join(&subdir).join(filename);
-                if path.exists() {
-                    return Some(path);
-                }
+    }
+
+    /// Cache validators for the overlay a request resolves to, if any.
+    fn overlay_version(&self, id: &str, overlay: Option<&str>) -> Option<OverlayVersion> {
+        let wsi_path = self.find_slide_path(id)?;
+        match self.select_overlay(id, &wsi_path, overlay) {
+            OverlaySelection::Found(overlay_id, path) => {
+                OverlayVersion::from_file(&overlay_id, &path)
             }
+            OverlaySelection::None | OverlaySelection::Missing => None,
         }
-        None
     }
 
-    fn source_options(&self, id: &str) -> Option<SourceOptions> {
-        let wsi_path = self.find_slide_path(id)?;
-        let cells_protobuf_path = self.find_overlay_path(id, &wsi_path);
+    /// Build source options for a slide and overlay selection. Returns the
+    /// resolved overlay id alongside so preparation is cached per overlay.
+    fn source_options(
+        &self,
+        id: &str,
+        overlay: Option<&str>,
+    ) -> Result<(Option<String>, SourceOptions), Prepared> {
+        let wsi_path = self.find_slide_path(id).ok_or(Prepared::NotFound)?;
+        let (overlay_id, cells_protobuf_path) = match self.select_overlay(id, &wsi_path, overlay) {
+            OverlaySelection::None => (None, None),
+            OverlaySelection::Found(overlay_id, path) => (Some(overlay_id), Some(path)),
+            OverlaySelection::Missing => return Err(Prepared::OverlayNotFound),
+        };
         let c = &self.inner.config;
         // Only build a heatmap when cells exist to derive it from.
         let heatmap = c.heatmap && cells_protobuf_path.is_some();
-        Some(SourceOptions {
+        let options = SourceOptions {
             wsi_path,
             cells_protobuf_path,
             tile_size: c.tile_size,
@@ -138,20 +497,23 @@ impl FoveaAppState {
             heatmap_bin_size: c.heatmap_bin_size,
             heatmap_tile_size: c.heatmap_tile_size,
             tile_cache_mb: c.tile_cache_mb,
-        })
+        };
+        Ok((overlay_id, options))
     }
 
     /// Get prepared sources for a slide, preparing them once on first use.
     /// Blocks (awaits) until preparation completes; subsequent calls are instant.
-    async fn prepare(&self, id: &str) -> Prepared {
-        let Some(options) = self.source_options(id) else {
-            return Prepared::NotFound;
+    async fn prepare(&self, id: &str, overlay: Option<&str>, encoding: TileEncoding) -> Prepared {
+        let (overlay_id, mut options) = match self.source_options(id, overlay) {
+            Ok(resolved) => resolved,
+            Err(prepared) => return prepared,
         };
+        options.image_format = encoding.image_format();
 
         let slot = self
             .inner
             .sources
-            .entry(id.to_string())
+            .entry((id.to_string(), overlay_id, encoding))
             .or_insert_with(|| Arc::new(OnceCell::new()))
             .clone();
 
@@ -178,31 +540,141 @@ impl FoveaAppState {
 pub fn fovea_routes(state: FoveaAppState) -> Router {
     Router::new()
         .route("/fovea/:id/*rest", get(handle_fovea))
+        .route("/slide/:id/overlays", get(list_overlays))
+        .route("/slide/:id/tiles", post(batch::handle_tile_batch))
+        .route_layer(middleware::from_fn(track_http_metrics))
         .with_state(state)
 }
 
+/// Query parameters accepted by the fovea forwarding route
+#[derive(Debug, Default, Deserialize)]
+pub struct FoveaQuery {
+    /// Overlay id to serve cells/heatmap from (defaults to the primary overlay)
+    pub overlay: Option<String>,
+    /// Serve slide tiles losslessly (PNG) instead of JPEG
+    #[serde(default)]
+    pub lossless: bool,
+}
+
+impl FoveaQuery {
+    fn tile_encoding(&self) -> TileEncoding {
+        if self.lossless {
+            TileEncoding::Lossless
+        } else {
+            TileEncoding::Jpeg
+        }
+    }
+}
+
+/// Response for GET /api/slide/:id/overlays
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverlayListResponse {
+    pub slide_id: String,
+    /// Overlay served when no `?overlay=` selector is given
+    pub default: Option<String>,
+    pub overlays: Vec<String>,
+}
+
+/// GET /api/slide/:id/overlays - List the overlays available for a slide
+async fn list_overlays(State(state): State<FoveaAppState>, Path(id): Path<String>) -> Response {
+    let id = state.slide_id(id);
+    match state.list_overlays(&id) {
+        Some(overlays) => Json(OverlayListResponse {
+            slide_id: id,
+            default: overlays.first().cloned(),
+            overlays,
+        })
+        .into_response(),
+        None => (StatusCode::NOT_FOUND, "slide not found").into_response(),
+    }
+}
+
 async fn handle_fovea(
     State(state): State<FoveaAppState>,
     Path((id, rest)): Path<(String, String)>,
+    Query(query): Query<FoveaQuery>,
+    headers: HeaderMap,
 ) -> Response {
-    match state.prepare(&id).await {
+    let id = state.slide_id(id);
+    // Overlay manifests are revalidated against the overlay file before any
+    // (potentially slow) preparation happens.
+    let version = if is_overlay_manifest(&rest) {
+        state.overlay_version(&id, query.overlay.as_deref())
+    } else {
+        None
+    };
+    if let Some(ref version) = version
+        && version.is_fresh(&headers)
+    {
+        let mut response = StatusCode::NOT_MODIFIED.into_response();
+        version.apply(response.headers_mut());
+        return response;
+    }
+
+    let encoding = query.tile_encoding();
+    if encoding == TileEncoding::Lossless && !state.inner.config.lossless_tiles {
+        return (StatusCode::FORBIDDEN, "lossless tiles are disabled").into_response();
+    }
+    match state.prepare(&id, query.overlay.as_deref(), encoding).await {
         Prepared::Ready(sources) => {
             // `rest` is the slide-relative path fovea-pack expects, e.g.
             // "slide/images/level_0/0_0.jpg" -> "/slide/images/level_0/0_0.jpg".
             let path = format!("/{rest}");
-            match route_request(&sources, &path).await {
-                Ok(response) => response,
+            let (routed, timing) = state
+                .inner
+                .encodes
+                .run_timed(within_timeout(
+                    state.inner.config.request_timeout,
+                    route_request(&sources, &path),
+                ))
+                .await;
+            let routed = if state.inner.config.placeholder_tiles && is_slide_tile(&rest) {
+                with_placeholder(routed, &path)
+            } else {
+                routed
+            };
+            match routed {
+                Ok(mut response) => {
+                    timing.apply(response.headers_mut());
+                    if encoding == TileEncoding::Lossless
+                        && is_slide_tile(&rest)
+                        && response.status().is_success()
+                        && !response.headers().contains_key(TILE_PLACEHOLDER_HEADER)
+                    {
+                        response.headers_mut().insert(
+                            header::CONTENT_TYPE,
+                            HeaderValue::from_static(encoding.content_type()),
+                        );
+                    }
+                    if let Some(ref version) = version
+                        && response.status().is_success()
+                    {
+                        version.apply(response.headers_mut());
+                    }
+                    response
+                }
                 Err(err) => {
                     warn!("fovea: route_request failed for {id} {path}: {err:#}");
                     (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
                 }
             }
         }
+        other => prepare_failure_response(&id, other),
+    }
+}
+
+/// Error response for a slide whose sources could not be prepared.
+fn prepare_failure_response(id: &str, prepared: Prepared) -> Response {
+    match prepared {
         Prepared::NotFound => (StatusCode::NOT_FOUND, "slide not found").into_response(),
+        Prepared::OverlayNotFound => (StatusCode::NOT_FOUND, "overlay not found").into_response(),
         Prepared::Failed(err) => {
             warn!("fovea: preparation failed for {id}: {err}");
             (StatusCode::INTERNAL_SERVER_ERROR, err).into_response()
         }
+        Prepared::Ready(_) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
+        }
     }
 }
 
@@ -219,3 +691,389 @@ fn sanitize_id(s: &str) -> String {
         })
         .collect()
 }
+
+/// Overlay ids map to file stems, so only allow id-safe characters and no
+/// leading dot (rules out `..` and hidden files).
+fn is_valid_overlay_id(overlay_id: &str) -> bool {
+    !overlay_id.is_empty() && !overlay_id.starts_with('.') && sanitize_id(overlay_id) == overlay_id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Temporary slides + overlays directories, removed on drop
+    struct Fixture {
+        root: PathBuf,
+        state: FoveaAppState,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            Self::with_config(FoveaConfig::default())
+        }
+
+        fn with_config(config: FoveaConfig) -> Self {
+            Self::build(config, None)
+        }
+
+        fn with_overlay_template(template: &str) -> Self {
+            Self::build(FoveaConfig::default(), Some(template.to_string()))
+        }
+
+        fn build(config: FoveaConfig, path_template: Option<String>) -> Self {
+            let root =
+                std::env::temp_dir().join(format!("pathcollab-fovea-{}", uuid::Uuid::new_v4()));
+            let slide = SlideConfig {
+                slides_dir: root.join("slides"),
+                ..Default::default()
+            };
+            let overlay = OverlayConfig {
+                overlays_dir: root.join("overlays"),
+                path_template,
+            };
+            std::fs::create_dir_all(&slide.slides_dir).unwrap();
+            std::fs::create_dir_all(&overlay.overlays_dir).unwrap();
+            let state = FoveaAppState::new(&slide, &overlay, config);
+            Self { root, state }
+        }
+
+        fn touch(&self, relative: &str) {
+            let path = self.root.join(relative);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn test_list_multiple_overlays() {
+        let fixture = Fixture::new();
+        fixture.touch("slides/slide-a.svs");
+        fixture.touch("overlays/slide-a.bin");
+        fixture.touch("overlays/slide-a/overlays/model-b.bin");
+        fixture.touch("overlays/slide-a/overlays/model-a.pb");
+        fixture.touch("overlays/slide-a/overlays/notes.txt");
+
+        let overlays = fixture.state.list_overlays("slide-a").unwrap();
+        assert_eq!(overlays, vec!["primary", "model-a", "model-b"]);
+        assert!(fixture.state.list_overlays("missing").is_none());
+    }
+
+    #[test]
+    fn test_select_non_default_overlay() {
+        let fixture = Fixture::new();
+        fixture.touch("slides/slide-a.svs");
+        fixture.touch("overlays/slide-a/cell_masks.bin");
+        fixture.touch("overlays/slide-a/overlays/model-b.bin");
+
+        let Ok((overlay_id, options)) = fixture.state.source_options("slide-a", None) else {
+            panic!("default overlay should resolve");
+        };
+        assert_eq!(overlay_id.as_deref(), Some(PRIMARY_OVERLAY_ID));
+        assert!(
+            options
+                .cells_protobuf_path
+                .unwrap()
+                .ends_with("slide-a/cell_masks.bin")
+        );
+
+        let Ok((overlay_id, options)) = fixture.state.source_options("slide-a", Some("model-b"))
+        else {
+            panic!("named overlay should resolve");
+        };
+        assert_eq!(overlay_id.as_deref(), Some("model-b"));
+        assert!(
+            options
+                .cells_protobuf_path
+                .unwrap()
+                .ends_with("overlays/model-b.bin")
+        );
+
+        assert!(matches!(
+            fixture.state.source_options("slide-a", Some("../slide-a")),
+            Err(Prepared::OverlayNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_overlay_manifest_conditional_requests() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let fixture = Fixture::new();
+        fixture.touch("slides/slide-a.svs");
+        fixture.touch("overlays/slide-a.bin");
+        let version = fixture.state.overlay_version("slide-a", None).unwrap();
+        let last_modified = httpdate::fmt_http_date(version.last_modified);
+        let app = fovea_routes(fixture.state.clone());
+
+        let request = |name: &str, value: &str| {
+            Request::builder()
+                .uri("/fovea/slide-a/cells/manifest.json")
+                .header(name, value)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // Unchanged since Last-Modified
+        let response = app
+            .clone()
+            .oneshot(request("if-modified-since", &last_modified))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], version.etag.as_str());
+        assert_eq!(
+            response.headers()[header::LAST_MODIFIED],
+            last_modified.as_str()
+        );
+
+        // Matching ETag
+        let response = app
+            .clone()
+            .oneshot(request("if-none-match", &version.etag))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        // Stale validators are not fresh
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"other\""));
+        assert!(!version.is_fresh(&headers));
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::IF_MODIFIED_SINCE,
+            HeaderValue::from_static("Thu, 01 Jan 1970 00:00:00 GMT"),
+        );
+        assert!(!version.is_fresh(&headers));
+    }
+
+    #[tokio::test]
+    async fn test_encode_limiter_bounds_concurrency() {
+        let limiter = Arc::new(EncodeLimiter::new(2));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..8)
+            .map(|i| {
+                let limiter = limiter.clone();
+                let in_flight = in_flight.clone();
+                let max_in_flight = max_in_flight.clone();
+                tokio::spawn(async move {
+                    limiter
+                        .run(async {
+                            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                            max_in_flight.fetch_max(now, Ordering::SeqCst);
+                            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                            in_flight.fetch_sub(1, Ordering::SeqCst);
+                            i
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        for (i, task) in tasks.into_iter().enumerate() {
+            assert_eq!(task.await.unwrap(), i);
+        }
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+        assert_eq!(limiter.queued.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_server_timing_reports_queue_and_render() {
+        let limiter = Arc::new(EncodeLimiter::new(1));
+
+        // Hold the only permit so the timed request has to queue
+        let holder = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move {
+                limiter
+                    .run(tokio::time::sleep(Duration::from_millis(30)))
+                    .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let ((), timing) = limiter
+            .run_timed(async { tokio::time::sleep(Duration::from_millis(10)).await })
+            .await;
+        holder.await.unwrap();
+
+        assert!(timing.queue >= Duration::from_millis(15));
+        assert!(timing.render >= Duration::from_millis(10));
+
+        let mut headers = HeaderMap::new();
+        timing.apply(&mut headers);
+        let value = headers["server-timing"].to_str().unwrap();
+        assert!(value.starts_with("queue;dur="));
+        assert!(value.contains(", render;dur="));
+    }
+
+    #[tokio::test]
+    async fn test_slow_request_times_out_with_504() {
+        /// Sets its flag when dropped, i.e. when the work is cancelled
+        struct DropFlag(Arc<std::sync::atomic::AtomicBool>);
+        impl Drop for DropFlag {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let cancelled = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let flag = DropFlag(cancelled.clone());
+        let slow_read = async move {
+            let _flag = flag;
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(StatusCode::OK.into_response())
+        };
+
+        let response = within_timeout(Duration::from_millis(20), slow_read)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert!(
+            cancelled.load(Ordering::SeqCst),
+            "slow work should be dropped"
+        );
+
+        let fast = within_timeout(Duration::from_secs(5), async {
+            Ok(StatusCode::OK.into_response())
+        })
+        .await
+        .unwrap();
+        assert_eq!(fast.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_failed_tile_read_serves_placeholder() {
+        let failed = with_placeholder(
+            Err(anyhow::anyhow!("openslide read error")),
+            "/slide/images/level_0/0_0.jpg",
+        )
+        .unwrap();
+
+        assert_eq!(failed.status(), StatusCode::OK);
+        assert_eq!(failed.headers()[TILE_PLACEHOLDER_HEADER], "true");
+        assert_eq!(failed.headers()[header::CACHE_CONTROL], "no-store");
+        assert_eq!(failed.headers()[header::CONTENT_TYPE], "image/jpeg");
+        assert!(PLACEHOLDER_TILE.starts_with(&[0xFF, 0xD8]));
+        assert!(PLACEHOLDER_TILE.ends_with(&[0xFF, 0xD9]));
+
+        let server_error = with_placeholder(
+            Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response()),
+            "/slide/images/level_0/0_0.jpg",
+        )
+        .unwrap();
+        assert!(server_error.headers().contains_key(TILE_PLACEHOLDER_HEADER));
+
+        // Invalid coordinates keep their client error
+        let bad_request = with_placeholder(
+            Ok(StatusCode::BAD_REQUEST.into_response()),
+            "/slide/images/level_99/0_0.jpg",
+        )
+        .unwrap();
+        assert_eq!(bad_request.status(), StatusCode::BAD_REQUEST);
+        assert!(!bad_request.headers().contains_key(TILE_PLACEHOLDER_HEADER));
+
+        assert!(is_slide_tile("slide/images/level_0/0_0.jpg"));
+        assert!(!is_slide_tile("cells/manifest.json"));
+    }
+
+    #[tokio::test]
+    async fn test_lossless_tiles_rejected_unless_enabled() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let fixture = Fixture::new();
+        fixture.touch("slides/slide-a.svs");
+
+        let response = fovea_routes(fixture.state.clone())
+            .oneshot(
+                Request::builder()
+                    .uri("/fovea/slide-a/slide/images/level_0/0_0.jpg?lossless=true")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        // Nothing was prepared for the rejected request
+        assert!(fixture.state.inner.sources.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lossless_tiles_prepared_separately() {
+        let fixture = Fixture::with_config(FoveaConfig {
+            lossless_tiles: true,
+            ..Default::default()
+        });
+        fixture.touch("slides/slide-a.svs");
+
+        let uri: axum::http::Uri = "/fovea/slide-a/slide/images/level_0/0_0.jpg?lossless=true"
+            .parse()
+            .unwrap();
+        let Query(query) = Query::<FoveaQuery>::try_from_uri(&uri).unwrap();
+        let lossless = query.tile_encoding();
+        assert_eq!(lossless, TileEncoding::Lossless);
+        assert_eq!(FoveaQuery::default().tile_encoding(), TileEncoding::Jpeg);
+        assert!(matches!(lossless.image_format(), ImageFormat::Png));
+
+        // JPEG and lossless sources never share a cache slot
+        fixture
+            .state
+            .prepare("slide-a", None, TileEncoding::Jpeg)
+            .await;
+        fixture.state.prepare("slide-a", None, lossless).await;
+        assert_eq!(fixture.state.inner.sources.len(), 2);
+
+        fixture.state.evict_slide("slide-b");
+        assert_eq!(fixture.state.inner.sources.len(), 2);
+        fixture.state.evict_slide("slide-a");
+        assert!(fixture.state.inner.sources.is_empty());
+    }
+
+    #[test]
+    fn test_primary_overlay_under_custom_template() {
+        let fixture = Fixture::with_overlay_template("{slides_dir}/{slide_id}/overlays.bin");
+        fixture.touch("slides/slide-a.svs");
+        fixture.touch("slides/slide-a/overlays.bin");
+        fixture.touch("slides/slide-b.svs");
+        // Built-in layouts are no longer searched
+        fixture.touch("overlays/slide-b.bin");
+
+        assert_eq!(
+            fixture.state.list_overlays("slide-a"),
+            Some(vec![PRIMARY_OVERLAY_ID.to_string()])
+        );
+        let Ok((_, options)) = fixture.state.source_options("slide-a", None) else {
+            panic!("slide-a should resolve");
+        };
+        assert_eq!(
+            options.cells_protobuf_path,
+            Some(fixture.root.join("slides/slide-a/overlays.bin"))
+        );
+        assert_eq!(fixture.state.list_overlays("slide-b"), Some(vec![]));
+    }
+
+    #[test]
+    fn test_single_named_overlay_is_default() {
+        let fixture = Fixture::new();
+        fixture.touch("slides/slide-a.svs");
+        fixture.touch("overlays/slide-a/overlays/only.bin");
+
+        let Ok((overlay_id, options)) = fixture.state.source_options("slide-a", None) else {
+            panic!("single named overlay should be the default");
+        };
+        assert_eq!(overlay_id.as_deref(), Some("only"));
+        assert!(options.cells_protobuf_path.is_some());
+    }
+}
@@ -0,0 +1,151 @@
+//! Per-route HTTP request metrics
+//!
+//! Records `pathcollab_http_requests_total` and
+//! `pathcollab_http_request_duration_seconds`, labelled by `route`, `method`
+//! and `status`. The route label is the matched route template (e.g.
+//! `/api/slide/:id`), never the raw path, so slide ids and tile coordinates
+//! don't blow up label cardinality. Apply with `route_layer` so only matched
+//! routes are measured.
+
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use metrics::{counter, histogram};
+use std::time::Instant;
+
+/// Middleware recording request count and latency for the matched route
+pub async fn track_http_metrics(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let method = request.method().as_str().to_string();
+    let start = Instant::now();
+
+    let response = next.run(request).await;
+
+    let labels = [
+        ("route", route),
+        ("method", method),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    counter!("pathcollab_http_requests_total", &labels).increment(1);
+    histogram!("pathcollab_http_request_duration_seconds", &labels)
+        .record(start.elapsed().as_secs_f64());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{FoveaConfig, OverlayConfig, SlideConfig};
+    use crate::fovea::{FoveaAppState, fovea_routes};
+    use crate::server::harness::FixtureSlides;
+    use crate::slide::{SlideAppState, slide_routes};
+    use axum::{Router, body::Body, http::Request};
+    use metrics::{
+        Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tower::ServiceExt;
+
+    /// Recorder capturing request counters by their (route, method, status) labels
+    #[derive(Default)]
+    struct RouteRecorder {
+        requests: Mutex<HashMap<(String, String, String), Arc<AtomicU64>>>,
+    }
+
+    impl RouteRecorder {
+        fn count(&self, route: &str, method: &str, status: &str) -> u64 {
+            let key = (route.to_string(), method.to_string(), status.to_string());
+            self.requests
+                .lock()
+                .unwrap()
+                .get(&key)
+                .map_or(0, |c| c.load(Ordering::Relaxed))
+        }
+    }
+
+    impl Recorder for RouteRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            if key.name() != "pathcollab_http_requests_total" {
+                return Counter::noop();
+            }
+            let label = |name: &str| {
+                key.labels()
+                    .find(|l| l.key() == name)
+                    .map(|l| l.value().to_string())
+                    .unwrap_or_default()
+            };
+            let counter = self
+                .requests
+                .lock()
+                .unwrap()
+                .entry((label("route"), label("method"), label("status")))
+                .or_default()
+                .clone();
+            Counter::from_arc(counter)
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_routes_labelled_by_template() {
+        let root = std::env::temp_dir().join(format!("pathcollab-http-{}", uuid::Uuid::new_v4()));
+        let slide = SlideConfig {
+            slides_dir: root.join("slides"),
+            ..Default::default()
+        };
+        let overlay = OverlayConfig {
+            overlays_dir: root.join("overlays"),
+            ..Default::default()
+        };
+        std::fs::create_dir_all(&slide.slides_dir).unwrap();
+        std::fs::create_dir_all(&overlay.overlays_dir).unwrap();
+
+        let app = Router::new()
+            .nest(
+                "/api",
+                slide_routes(SlideAppState {
+                    slide_service: Arc::new(FixtureSlides),
+                }),
+            )
+            .nest(
+                "/api",
+                fovea_routes(FoveaAppState::new(&slide, &overlay, FoveaConfig::default())),
+            );
+
+        let recorder = RouteRecorder::default();
+        let _guard = metrics::set_default_local_recorder(&recorder);
+        for uri in [
+            "/api/slide/test-slide",
+            "/api/slide/other-slide",
+            "/api/fovea/missing/slide/images/level_0/0_0.jpg",
+            "/api/fovea/missing/slide/images/level_0/1_0.jpg",
+        ] {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        assert_eq!(recorder.count("/api/slide/:id", "GET", "200"), 1);
+        assert_eq!(recorder.count("/api/slide/:id", "GET", "404"), 1);
+        assert_eq!(recorder.count("/api/fovea/:id/*rest", "GET", "404"), 2);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -3,12 +3,21 @@
 //! This module exports the server components for use in integration tests
 //! and external tooling.
 
+pub mod audit;
 pub mod config;
+pub mod cors;
 pub mod fovea;
+pub mod http_metrics;
+pub mod logging;
+pub mod openapi;
+pub mod prometheus;
 pub mod protocol;
+pub mod request_id;
 pub mod server;
 pub mod session;
 pub mod slide;
+pub mod tls;
+pub mod webhook;
 
 // Re-export commonly used types
 pub use config::Config;
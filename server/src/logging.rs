@@ -0,0 +1,112 @@
+//! Log output setup
+//!
+//! Logs are human-readable lines by default. `LOG_FORMAT=json` writes one JSON
+//! object per line instead, for log aggregators. Fields of the enclosing spans
+//! (such as the `connection` span's `connection_id`, `participant_id` and
+//! `session_id`) are then emitted as structured fields under `span` and `spans`
+//! rather than being flattened into the message text.
+
+use crate::config::LogFormat;
+use tracing::Subscriber;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Install the global subscriber, writing to stdout in `format`
+pub fn init_tracing(format: LogFormat) {
+    tracing_subscriber::registry()
+        .with(
+            EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "pathcollab=debug,tower_http=debug".into()),
+        )
+        .with(fmt_layer(format, std::io::stdout))
+        .init();
+}
+
+/// Formatting layer writing each event to `writer`
+fn fmt_layer<S, W>(format: LogFormat, writer: W) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer);
+    match format {
+        LogFormat::Pretty => layer.boxed(),
+        LogFormat::Json => layer.json().boxed(),
+    }
+}
+
+/// Formatted log output captured by a test subscriber
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub(crate) struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+#[cfg(test)]
+impl CapturedLogs {
+    pub(crate) fn lines(&self) -> Vec<String> {
+        String::from_utf8_lossy(&self.0.lock().unwrap())
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+impl std::io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl<'a> MakeWriter<'a> for CapturedLogs {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::websocket::connection_span;
+    use tracing::{field, info};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_json_format_writes_parseable_lines() {
+        let logs = CapturedLogs::default();
+        let subscriber =
+            tracing_subscriber::registry().with(fmt_layer(LogFormat::Json, logs.clone()));
+
+        let connection_id = Uuid::new_v4();
+        tracing::subscriber::with_default(subscriber, || {
+            let span = connection_span(connection_id);
+            let _entered = span.enter();
+            info!("before join");
+            span.record("session_id", field::display("abc123"));
+            info!(rev = 3, "after join");
+        });
+
+        let events: Vec<serde_json::Value> = logs
+            .lines()
+            .iter()
+            .map(|line| serde_json::from_str(line).expect("Each line should be JSON"))
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["fields"]["message"], "before join");
+        assert_eq!(
+            events[0]["span"]["connection_id"],
+            connection_id.to_string()
+        );
+        assert!(events[0]["span"]["session_id"].is_null());
+        assert_eq!(events[1]["fields"]["rev"], 3);
+        assert_eq!(events[1]["span"]["session_id"], "abc123");
+    }
+}
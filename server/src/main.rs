@@ -1,11 +1,23 @@
-use axum::{Json, Router, extract::State, response::IntoResponse, routing::get};
-use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use axum::{Json, Router, extract::State, middleware, routing::get};
 use pathcollab_server::SessionManager;
-use pathcollab_server::config::{Config, SlideSourceMode};
-use pathcollab_server::fovea::{FoveaAppState, fovea_routes};
-use pathcollab_server::server::{AppState, ws_handler};
-use pathcollab_server::session::state::SessionConfig as SessionStateConfig;
+use pathcollab_server::audit::FileAuditSink;
+use pathcollab_server::config::{Config, SlidePreload, SlideSourceMode};
+use pathcollab_server::cors::cors_layer;
+use pathcollab_server::fovea::{FoveaAppState, OverlayLayout, fovea_routes};
+use pathcollab_server::logging::init_tracing;
+use pathcollab_server::openapi::openapi_routes;
+use pathcollab_server::prometheus::{prometheus_metrics, setup_prometheus_metrics};
+use pathcollab_server::request_id::assign_request_id;
+use pathcollab_server::server::presence::{PresenceBatcher, ViewportBatcher};
+use pathcollab_server::server::recording::SessionRecorder;
+use pathcollab_server::server::replay::EventReplay;
+use pathcollab_server::server::{
+    AppState, WsConfig, admin_routes, ready, session_routes, ws_handler,
+};
+use pathcollab_server::session::state::{DEMO_SESSION_ID, SessionConfig as SessionStateConfig};
 use pathcollab_server::slide::{LocalSlideService, SlideAppState, slide_routes};
+use pathcollab_server::tls::{load_rustls_config, serve_tls};
+use pathcollab_server::webhook::WebhookNotifier;
 use serde::Serialize;
 use std::net::SocketAddr;
 use std::path::Path;
@@ -13,11 +25,9 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tower::ServiceBuilder;
 use tower_http::compression::CompressionLayer;
-use tower_http::cors::{Any, CorsLayer};
 use tower_http::services::{ServeDir, ServeFile};
 use tower_http::trace::TraceLayer;
 use tracing::{info, warn};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 /// Application start time for uptime calculation
 static START_TIME: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
@@ -46,6 +56,9 @@ struct HealthResponse {
     version: &'static str,
     slide_service: &'static str,
     websocket: &'static str,
+    session_cleanup: &'static str,
+    /// Optional features enabled for new sessions
+    features: Vec<String>,
     uptime_seconds: u64,
 }
 
@@ -59,9 +72,18 @@ async fn health(State(state): State<AppState>) -> (axum::http::StatusCode, Json<
         false
     };
 
-    let status = if slide_ready { "healthy" } else { "degraded" };
+    // A stalled cleanup task means sessions have stopped expiring
+    let cleanup_running = !state.cleanup_stalled();
+
+    let healthy = slide_ready && cleanup_running;
+    let status = if healthy { "healthy" } else { "degraded" };
     let slide_status = if slide_ready { "ready" } else { "unavailable" };
-    let http_status = if slide_ready {
+    let cleanup_status = if cleanup_running {
+        "running"
+    } else {
+        "stalled"
+    };
+    let http_status = if healthy {
         axum::http::StatusCode::OK
     } else {
         axum::http::StatusCode::SERVICE_UNAVAILABLE
@@ -74,6 +96,8 @@ async fn health(State(state): State<AppState>) -> (axum::http::StatusCode, Json<
             version: env!("CARGO_PKG_VERSION"),
             slide_service: slide_status,
             websocket: "ready", // WebSocket is always ready if server is running
+            session_cleanup: cleanup_status,
+            features: state.session_manager.features().to_vec(),
             uptime_seconds: uptime,
         }),
     )
@@ -104,24 +128,6 @@ async fn metrics(State(state): State<AppState>) -> Json<MetricsResponse> {
     })
 }
 
-/// Prometheus metrics handle for exposing metrics in Prometheus format
-static PROMETHEUS_HANDLE: std::sync::OnceLock<PrometheusHandle> = std::sync::OnceLock::new();
-
-/// Initialize the Prometheus metrics recorder
-fn setup_prometheus_metrics() -> PrometheusHandle {
-    PrometheusBuilder::new()
-        .install_recorder()
-        .expect("Failed to install Prometheus recorder")
-}
-
-/// Endpoint to expose metrics in Prometheus format
-async fn prometheus_metrics() -> impl IntoResponse {
-    let handle = PROMETHEUS_HANDLE
-        .get()
-        .expect("Prometheus handle not initialized");
-    handle.render()
-}
-
 /// Update gauge metrics for sessions and connections (called periodically)
 async fn update_gauge_metrics(state: &AppState) {
     let (sessions, connections) = state.get_stats().await;
@@ -140,21 +146,18 @@ async fn main() -> anyhow::Result<()> {
     // Record server start time
     START_TIME.set(Instant::now()).ok();
 
-    // Initialize Prometheus metrics recorder (must be done before any metrics are recorded)
-    let prometheus_handle = setup_prometheus_metrics();
-    PROMETHEUS_HANDLE.set(prometheus_handle).ok();
+    // Load configuration from environment
+    let config = Config::from_env();
 
     // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "pathcollab=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    init_tracing(config.log_format);
 
-    // Load configuration from environment
-    let config = Config::from_env();
+    // Initialize Prometheus metrics recorder (must be done before any metrics are recorded)
+    if let Err(e) = setup_prometheus_metrics() {
+        warn!("Prometheus metrics disabled: {}", e);
+    }
+
+    config.validate()?;
     info!(
         "Loaded configuration: host={}, port={}",
         config.host, config.port
@@ -180,23 +183,35 @@ async fn main() -> anyhow::Result<()> {
     }
 
     // Initialize slide service based on configuration
-    let slide_service: Arc<dyn pathcollab_server::SlideService> = match config.slide.source_mode {
+    let local_slides = match config.slide.source_mode {
         SlideSourceMode::Local => {
             info!("Using local slide source: {:?}", config.slide.slides_dir);
-            let service = LocalSlideService::new(&config.slide)
-                .expect("Failed to initialize local slide service");
-            Arc::new(service)
+            LocalSlideService::new(&config.slide)
+                .expect("Failed to initialize local slide service")
+                .with_overlay_layout(OverlayLayout::new(&config.slide, &config.overlay))
         }
         SlideSourceMode::WsiStreamer => {
             info!("Using WSIStreamer at: {}", config.wsistreamer_url);
             // For now, fall back to local if WsiStreamer is configured
             // TODO: Implement WsiStreamerSlideService
             info!("WsiStreamer mode not yet implemented, falling back to local");
-            let service = LocalSlideService::new(&config.slide)
-                .expect("Failed to initialize local slide service");
-            Arc::new(service)
+            LocalSlideService::new(&config.slide)
+                .expect("Failed to initialize local slide service")
+                .with_overlay_layout(OverlayLayout::new(&config.slide, &config.overlay))
         }
     };
+    let local_slides = Arc::new(local_slides);
+
+    // Open slides ahead of their first session (optional)
+    if config.slide.preload != SlidePreload::Disabled {
+        let service = local_slides.clone();
+        let preload = config.slide.preload.clone();
+        let keep_open = config.slide.preload_keep_open;
+        tokio::spawn(async move {
+            service.preload(&preload, keep_open).await;
+        });
+    }
+    let slide_service: Arc<dyn pathcollab_server::SlideService> = local_slides;
 
     // Create slide app state for HTTP routes (slide catalog: list + metadata)
     let slide_app_state = SlideAppState {
@@ -212,23 +227,133 @@ async fn main() -> anyhow::Result<()> {
     // Create shared application state with session config, slide service, and public base URL
     let session_config = SessionStateConfig {
         max_duration: config.session.max_duration,
+        expiry_warning: config.session.expiry_warning,
+        extension: config.session.extension,
+        max_total_duration: config.session.max_total_duration,
         presenter_grace_period: config.session.presenter_grace_period,
         max_followers: config.session.max_followers,
+        max_followers_cap: config.session.max_followers_cap,
+        max_sessions: config.session.max_concurrent_sessions,
+        viewport_history_len: config.session.viewport_history_len,
+        features: config.session.features.clone(),
     };
     let session_manager = Arc::new(SessionManager::with_config(session_config));
 
-    let app_state = AppState::new()
+    let mut app_state = AppState::new()
         .with_session_manager(session_manager)
         .with_slide_service(slide_service)
-        .with_public_base_url(config.public_base_url.clone());
+        .with_public_base_url(config.public_base_url.clone())
+        .with_admin_token(config.admin_token.clone())
+        .with_trusted_proxies(config.trusted_proxy_networks())
+        .with_fovea(fovea_app_state.clone())
+        .with_slide_change_delay(config.session.slide_change_delay)
+        .with_cleanup_interval(config.session.cleanup_interval)
+        .with_cleanup_jitter(config.session.cleanup_jitter)
+        .with_ws_config(WsConfig {
+            ping_interval: config.websocket.ping_interval,
+            ping_timeout: config.websocket.ping_timeout,
+            strict_subprotocol: config.websocket.strict_subprotocol,
+            report_unknown_fields: config.websocket.report_unknown_fields,
+            allow_client_slides: config.websocket.allow_client_slides,
+            max_connections: config.websocket.max_connections,
+            max_cursor_hz: config.presence.cursor_max_hz,
+            ..WsConfig::default()
+        });
+
+    // Per-tick presence batching (optional; cursors are broadcast immediately otherwise)
+    if config.presence.batch_cursors {
+        info!(
+            "Batching cursor updates at {} Hz",
+            config.presence.cursor_broadcast_hz
+        );
+        app_state = app_state.with_presence_batcher(Arc::new(PresenceBatcher::new()));
+    }
+    app_state = app_state.with_viewport_batcher(Arc::new(ViewportBatcher::new()));
+
+    // Recent-event replay for late joiners (optional)
+    if config.session.replay_len > 0 {
+        app_state = app_state.with_event_replay(Arc::new(EventReplay::new(
+            config.session.replay_len,
+            config.session.replay_types.clone(),
+        )));
+    }
 
-    // Periodic cleanup for expired sessions
-    let cleanup_state = app_state.clone();
+    // Presenter action audit log (optional)
+    if let Some(ref path) = config.audit.log_path {
+        let sink = FileAuditSink::open(path).expect("Failed to open audit log");
+        info!("Writing presenter audit log to: {:?}", path);
+        app_state = app_state.with_audit_sink(Arc::new(sink));
+    }
+
+    // Session lifecycle webhooks (optional)
+    if let Some(notifier) = WebhookNotifier::from_config(&config.webhook) {
+        info!(
+            "Sending session webhooks to: {}",
+            config.webhook.url.as_deref().unwrap_or_default()
+        );
+        app_state = app_state.with_webhooks(Arc::new(notifier));
+    }
+
+    // Session transcript recording (optional)
+    if let Some(ref dir) = config.recording.dir {
+        let recorder = SessionRecorder::new(dir).expect("Failed to create recording directory");
+        info!("Recording session transcripts to: {:?}", dir);
+        app_state = app_state.with_session_recorder(Arc::new(recorder));
+    }
+
+    // Demo session (optional); validate() guarantees slide and secret are set
+    let demo = config
+        .demo
+        .slide_id
+        .clone()
+        .zip(config.demo.join_secret.clone())
+        .filter(|_| config.demo.enabled);
+    if let Some((ref slide_id, _)) = demo {
+        info!(
+            "Demo mode enabled: session {} on slide {}",
+            DEMO_SESSION_ID, slide_id
+        );
+    }
+
+    // Periodic cleanup for expired sessions; also (re-)creates the demo session
+    tokio::spawn(app_state.clone().run_cleanup(demo));
+
+    // Presence tick: flush batched cursors
+    if app_state.presence_batcher.is_some() {
+        let presence_state = app_state.clone();
+        let tick = Duration::from_secs(1) / config.presence.cursor_broadcast_hz;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tick);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                presence_state.flush_presence().await;
+            }
+        });
+    }
+
+    // Viewport tick: broadcast the newest presenter viewport of each session
+    let viewport_state = app_state.clone();
+    let viewport_tick = Duration::from_secs(1) / config.presence.viewport_broadcast_hz;
     tokio::spawn(async move {
-        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        let mut interval = tokio::time::interval(viewport_tick);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
         loop {
             interval.tick().await;
-            cleanup_state.session_manager.cleanup_expired().await;
+            viewport_state.flush_viewports().await;
+        }
+    });
+
+    // Periodic sweep for idle cursors so clients can fade them out
+    let presence_state = app_state.clone();
+    let cursor_stale_after = config.presence.cursor_stale_after;
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            presence_state
+                .broadcast_stale_cursors(cursor_stale_after)
+                .await;
         }
     });
 
@@ -242,12 +367,6 @@ async fn main() -> anyhow::Result<()> {
         }
     });
 
-    // Build CORS layer
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
-
     // Build slide API routes (separate state, merged as nested service)
     let slide_api = slide_routes(slide_app_state);
 
@@ -258,16 +377,25 @@ async fn main() -> anyhow::Result<()> {
     // The slide routes have their own state, so we nest them before adding AppState
     let app = Router::new()
         .route("/health", get(health))
+        .route("/ready", get(ready))
         .route("/metrics", get(metrics))
         .route("/metrics/prometheus", get(prometheus_metrics))
         .route("/ws", get(ws_handler))
+        // Admin controls (drain for zero-downtime deploys)
+        .nest("/api", admin_routes())
+        // Session inspection for members without a WebSocket
+        .nest("/api", session_routes())
         .with_state(app_state)
         // Merge slide catalog routes (list + metadata) after setting AppState
         .merge(Router::new().nest("/api", slide_api))
         // Merge fovea rendering-data routes (replaces DZI tiles + overlay serving)
         .merge(Router::new().nest("/api", fovea_api))
+        // OpenAPI document describing the HTTP routes above
+        .merge(Router::new().nest("/api", openapi_routes()))
         .layer(TraceLayer::new_for_http())
-        .layer(cors);
+        // Outside the trace layer, so its logs carry the request id
+        .layer(middleware::from_fn(assign_request_id))
+        .layer(cors_layer());
 
     // Add static file serving if configured (for unified Docker image)
     let app = if let Some(ref static_dir) = config.static_files.dir {
@@ -301,8 +429,21 @@ async fn main() -> anyhow::Result<()> {
     let addr: SocketAddr = format!("{}:{}", config.host, config.port).parse()?;
     info!("PathCollab server listening on {}", addr);
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    if let Some((cert_path, key_path)) = config.tls.paths() {
+        let tls_config = load_rustls_config(cert_path, key_path)
+            .await
+            .expect("Failed to load TLS certificate and key");
+        info!("Serving HTTPS/WSS with certificate {:?}", cert_path);
+        let listener = std::net::TcpListener::bind(addr)?;
+        serve_tls(listener, app, tls_config).await?;
+    } else {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await?;
+    }
 
     Ok(())
 }
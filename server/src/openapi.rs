@@ -0,0 +1,502 @@
+//! OpenAPI document for the HTTP API
+//!
+//! Served at `GET /api/openapi.json` so the frontend and third-party
+//! integrators can generate client types instead of hand-maintaining them.
+//! Schemas mirror the serde shape of the response types; tests check that the
+//! documented properties match what the types actually serialize.
+
+use crate::fovea::TILE_BATCH_CONTENT_TYPE;
+use axum::{Json, Router, routing::get};
+use serde_json::{Value, json};
+
+/// Build the OpenAPI 3.0 document
+pub fn openapi_spec() -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "PathCollab API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": {
+            "/health": {
+                "get": {
+                    "summary": "Liveness and slide service status",
+                    "responses": {
+                        "200": { "description": "Healthy" },
+                        "503": { "description": "Slide service unavailable or session cleanup stalled" },
+                    },
+                },
+            },
+            "/ready": {
+                "get": {
+                    "summary": "Whether this instance accepts new sessions",
+                    "responses": {
+                        "200": json_response("Ready", schema_ref("ReadyResponse")),
+                        "503": json_response("Draining or session cleanup stalled", schema_ref("ReadyResponse")),
+                    },
+                },
+            },
+            "/api/slides": {
+                "get": {
+                    "summary": "List available slides",
+                    "responses": {
+                        "200": json_response("Slide list", json!({
+                            "type": "array",
+                            "items": schema_ref("SlideListItem"),
+                        })),
+                        "500": error_response(),
+                    },
+                },
+            },
+            "/api/slides/default": {
+                "get": {
+                    "summary": "Default slide to display",
+                    "responses": {
+                        "200": json_response("Default slide", schema_ref("DefaultSlideResponse")),
+                        "404": error_response(),
+                    },
+                },
+            },
+            "/api/slide/{id}": {
+                "get": {
+                    "summary": "Slide metadata",
+                    "parameters": [slide_id_param()],
+                    "responses": {
+                        "200": json_response("Slide metadata", schema_ref("SlideMetadata")),
+                        "404": error_response(),
+                    },
+                },
+            },
+            "/api/slide/{id}/overlays": {
+                "get": {
+                    "summary": "List a slide's overlays",
+                    "parameters": [slide_id_param()],
+                    "responses": {
+                        "200": json_response("Overlay list", schema_ref("OverlayListResponse")),
+                        "404": { "description": "Slide not found" },
+                    },
+                },
+            },
+            "/api/slide/{id}/tiles": {
+                "post": {
+                    "summary": "Fetch several slide tiles in one response",
+                    "parameters": [slide_id_param()],
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {
+                                "schema": { "type": "array", "items": schema_ref("TileRef") },
+                            },
+                        },
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Length-prefixed tile parts (u16 status, u32 length, body)",
+                            "content": {
+                                TILE_BATCH_CONTENT_TYPE: {
+                                    "schema": { "type": "string", "format": "binary" },
+                                },
+                            },
+                        },
+                        "400": { "description": "No tiles requested" },
+                        "413": { "description": "Too many tiles in one batch" },
+                    },
+                },
+            },
+            "/api/fovea/{id}/{path}": {
+                "get": {
+                    "summary": "Slide tiles, cell chunks and heatmap in the fovea contract",
+                    "description": "Paths include slide/manifest.json, slide/images/level_{level}/{x}_{y}.jpg, cells/manifest.json and heatmap/manifest.json.",
+                    "parameters": [
+                        slide_id_param(),
+                        {
+                            "name": "path",
+                            "in": "path",
+                            "required": true,
+                            "schema": { "type": "string" },
+                        },
+                        {
+                            "name": "overlay",
+                            "in": "query",
+                            "required": false,
+                            "schema": { "type": "string" },
+                        },
+                        {
+                            "name": "lossless",
+                            "in": "query",
+                            "required": false,
+                            "description": "Serve slide tiles as lossless PNG instead of JPEG (403 unless FOVEA_LOSSLESS_TILES is enabled)",
+                            "schema": { "type": "boolean", "default": false },
+                        },
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "Tile image or manifest. With FOVEA_PLACEHOLDER_TILES enabled, a tile that fails to read is served as a placeholder marked X-Tile-Placeholder: true.",
+                            "content": {
+                                "image/jpeg": { "schema": { "type": "string", "format": "binary" } },
+                                "image/png": { "schema": { "type": "string", "format": "binary" } },
+                                "application/json": { "schema": { "type": "object" } },
+                            },
+                        },
+                        "304": { "description": "Overlay manifest not modified" },
+                        "404": { "description": "Slide, overlay or tile not found" },
+                        "504": { "description": "fovea-pack did not answer within FOVEA_REQUEST_TIMEOUT_SECS" },
+                    },
+                },
+            },
+            "/api/features": {
+                "get": {
+                    "summary": "Optional features enabled for new sessions (SESSION_FEATURES)",
+                    "responses": {
+                        "200": json_response("Enabled features", schema_ref("FeaturesResponse")),
+                    },
+                },
+            },
+            "/api/session/{id}/participants": {
+                "get": {
+                    "summary": "Participants of a session (members only)",
+                    "parameters": [
+                        {
+                            "name": "id",
+                            "in": "path",
+                            "required": true,
+                            "schema": { "type": "string" },
+                        },
+                        {
+                            "name": "X-Join-Secret",
+                            "in": "header",
+                            "required": false,
+                            "schema": { "type": "string" },
+                        },
+                        {
+                            "name": "X-Presenter-Key",
+                            "in": "header",
+                            "required": false,
+                            "schema": { "type": "string" },
+                        },
+                    ],
+                    "responses": {
+                        "200": json_response("Participants", schema_ref("ParticipantListResponse")),
+                        "401": { "description": "Missing or invalid session credentials" },
+                        "404": { "description": "Session not found" },
+                    },
+                },
+            },
+            "/api/admin/drain": {
+                "post": {
+                    "summary": "Stop accepting new sessions",
+                    "security": [{ "adminToken": [] }],
+                    "responses": {
+                        "200": json_response("Draining", schema_ref("ReadyResponse")),
+                        "401": { "description": "Invalid admin token" },
+                        "403": { "description": "Admin API disabled" },
+                    },
+                },
+            },
+            "/api/admin/slides/reload": {
+                "post": {
+                    "summary": "Rescan the slides directory",
+                    "security": [{ "adminToken": [] }],
+                    "responses": {
+                        "200": json_response("Reloaded", schema_ref("ReloadSlidesResponse")),
+                        "401": { "description": "Invalid admin token" },
+                        "403": { "description": "Admin API disabled" },
+                        "503": { "description": "Slide service unavailable" },
+                    },
+                },
+            },
+            "/api/admin/slides/{id}": {
+                "delete": {
+                    "summary": "Delete a slide file",
+                    "description": "Refused with 409 while active sessions show the slide, unless force=true, which ends them first.",
+                    "security": [{ "adminToken": [] }],
+                    "parameters": [
+                        slide_id_param(),
+                        {
+                            "name": "force",
+                            "in": "query",
+                            "required": false,
+                            "schema": { "type": "boolean" },
+                        },
+                    ],
+                    "responses": {
+                        "200": json_response("Deleted", schema_ref("DeleteSlideResponse")),
+                        "401": { "description": "Invalid admin token" },
+                        "403": { "description": "Admin API disabled" },
+                        "404": error_response(),
+                        "409": json_response("Slide in use", schema_ref("SlideInUseResponse")),
+                        "503": { "description": "Slide service unavailable" },
+                    },
+                },
+            },
+            "/api/admin/sessions/{id}": {
+                "get": {
+                    "summary": "Internal state of one session",
+                    "security": [{ "adminToken": [] }],
+                    "parameters": [
+                        {
+                            "name": "id",
+                            "in": "path",
+                            "required": true,
+                            "schema": { "type": "string" },
+                        },
+                    ],
+                    "responses": {
+                        "200": json_response("Session", schema_ref("AdminSessionResponse")),
+                        "401": { "description": "Invalid admin token" },
+                        "403": { "description": "Admin API disabled" },
+                        "404": { "description": "Session not found" },
+                    },
+                },
+            },
+            "/api/admin/stats": {
+                "get": {
+                    "summary": "Session lifecycle summary",
+                    "security": [{ "adminToken": [] }],
+                    "responses": {
+                        "200": json_response("Stats", schema_ref("AdminStatsResponse")),
+                        "401": { "description": "Invalid admin token" },
+                        "403": { "description": "Admin API disabled" },
+                    },
+                },
+            },
+        },
+        "components": {
+            "securitySchemes": {
+                "adminToken": { "type": "http", "scheme": "bearer" },
+            },
+            "schemas": {
+                "SlideListItem": object(&[
+                    ("id", string()),
+                    ("name", string()),
+                    ("width", integer()),
+                    ("height", integer()),
+                    ("format", string()),
+                ], &["id", "name", "width", "height", "format"]),
+                "SlideMetadata": object(&[
+                    ("id", string()),
+                    ("name", string()),
+                    ("width", integer()),
+                    ("height", integer()),
+                    ("tile_size", integer()),
+                    ("num_levels", integer()),
+                    ("format", string()),
+                    ("vendor", string()),
+                    ("mpp_x", number()),
+                    ("mpp_y", number()),
+                    ("objective_power", number()),
+                    ("has_overlay", json!({ "type": "boolean" })),
+                ], &["id", "name", "width", "height", "tile_size", "num_levels", "format", "has_overlay"]),
+                "SlideErrorResponse": object(&[
+                    ("error", string()),
+                    ("code", string()),
+                ], &["error", "code"]),
+                "DefaultSlideResponse": object(&[
+                    ("slide_id", string()),
+                    ("source", string()),
+                    ("name", string()),
+                    ("width", integer()),
+                    ("height", integer()),
+                ], &["slide_id", "source", "name", "width", "height"]),
+                "OverlayListResponse": object(&[
+                    ("slide_id", string()),
+                    ("default", json!({ "type": "string", "nullable": true })),
+                    ("overlays", json!({ "type": "array", "items": string() })),
+                ], &["slide_id", "default", "overlays"]),
+                "TileRef": object(&[
+                    ("level", integer()),
+                    ("x", integer()),
+                    ("y", integer()),
+                ], &["level", "x", "y"]),
+                "FeaturesResponse": object(&[
+                    ("features", json!({ "type": "array", "items": string() })),
+                ], &["features"]),
+                "Participant": object(&[
+                    ("id", json!({ "type": "string", "format": "uuid" })),
+                    ("name", string()),
+                    ("color", string()),
+                    ("role", json!({ "type": "string", "enum": ["presenter", "follower"] })),
+                    ("connected_at", integer()),
+                ], &["id", "name", "color", "role", "connected_at"]),
+                "ParticipantListResponse": object(&[
+                    ("session_id", string()),
+                    ("participants", json!({ "type": "array", "items": schema_ref("Participant") })),
+                ], &["session_id", "participants"]),
+                "ReadyResponse": object(&[
+                    ("ready", json!({ "type": "boolean" })),
+                    ("draining", json!({ "type": "boolean" })),
+                    ("cleanup_stalled", json!({ "type": "boolean" })),
+                ], &["ready", "draining", "cleanup_stalled"]),
+                "ReloadSlidesResponse": object(&[
+                    ("slides", integer()),
+                ], &["slides"]),
+                "DeleteSlideResponse": object(&[
+                    ("slide_id", string()),
+                    ("ended_sessions", json!({ "type": "array", "items": string() })),
+                ], &["slide_id", "ended_sessions"]),
+                "SlideInUseResponse": object(&[
+                    ("error", string()),
+                    ("sessions", json!({ "type": "array", "items": string() })),
+                ], &["error", "sessions"]),
+                "AdminStatsResponse": object(&[
+                    ("total_connections", integer()),
+                    ("active_sessions", integer()),
+                    ("completed_sessions", integer()),
+                    ("session_lifetime_secs", json!({
+                        "type": "object",
+                        "nullable": true,
+                        "properties": { "avg": number(), "p50": number(), "p99": number() },
+                    })),
+                    ("avg_followers_per_session", number()),
+                ], &["total_connections", "active_sessions", "completed_sessions", "avg_followers_per_session"]),
+                "AdminParticipant": object(&[
+                    ("id", json!({ "type": "string", "format": "uuid" })),
+                    ("name", string()),
+                    ("role", json!({ "type": "string", "enum": ["presenter", "follower"] })),
+                    ("connected_at", integer()),
+                    ("last_seen_at", integer()),
+                ], &["id", "name", "role", "connected_at", "last_seen_at"]),
+                "AdminSessionResponse": object(&[
+                    ("id", string()),
+                    ("rev", integer()),
+                    ("content_rev", integer()),
+                    ("state", json!({ "type": "string", "enum": ["active", "presenter_disconnected", "expired"] })),
+                    ("presenter_disconnected_at", integer()),
+                    ("locked", json!({ "type": "boolean" })),
+                    ("created_at", integer()),
+                    ("expires_at", integer()),
+                    ("last_activity_at", integer()),
+                    ("slide_id", string()),
+                    ("presenter_id", json!({ "type": "string", "format": "uuid" })),
+                    ("participants", json!({ "type": "array", "items": schema_ref("AdminParticipant") })),
+                    ("max_followers", integer()),
+                    ("features", json!({ "type": "array", "items": string() })),
+                    ("connections", integer()),
+                    ("broadcast_receivers", integer()),
+                ], &[
+                    "id", "rev", "content_rev", "state", "locked", "created_at", "expires_at",
+                    "last_activity_at", "slide_id", "presenter_id", "participants", "max_followers",
+                    "features", "connections", "broadcast_receivers",
+                ]),
+            },
+        },
+    })
+}
+
+/// Routes serving the OpenAPI document, to be nested under `/api`
+pub fn openapi_routes() -> Router {
+    Router::new().route("/openapi.json", get(|| async { Json(openapi_spec()) }))
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+fn json_response(description: &str, schema: Value) -> Value {
+    json!({
+        "description": description,
+        "content": { "application/json": { "schema": schema } },
+    })
+}
+
+fn error_response() -> Value {
+    json_response("Error", schema_ref("SlideErrorResponse"))
+}
+
+fn slide_id_param() -> Value {
+    json!({
+        "name": "id",
+        "in": "path",
+        "required": true,
+        "schema": { "type": "string" },
+    })
+}
+
+fn object(properties: &[(&str, Value)], required: &[&str]) -> Value {
+    let properties: serde_json::Map<String, Value> = properties
+        .iter()
+        .map(|(name, schema)| (name.to_string(), schema.clone()))
+        .collect();
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+fn integer() -> Value {
+    json!({ "type": "integer", "minimum": 0 })
+}
+
+fn number() -> Value {
+    json!({ "type": "number" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slide::{SlideListItem, SlideMetadata};
+
+    fn schema_properties(spec: &Value, name: &str) -> Vec<String> {
+        let mut keys: Vec<String> = spec["components"]["schemas"][name]["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    fn serialized_keys(value: impl serde::Serialize) -> Vec<String> {
+        let mut keys: Vec<String> = serde_json::to_value(value)
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_spec_lists_slide_and_tile_endpoints() {
+        let spec = openapi_spec();
+        let paths = &spec["paths"];
+
+        assert!(paths["/api/slides"]["get"].is_object());
+        assert!(paths["/api/slide/{id}"]["get"].is_object());
+        assert!(paths["/api/fovea/{id}/{path}"]["get"].is_object());
+        assert!(paths["/api/slide/{id}/tiles"]["post"].is_object());
+        assert!(paths["/api/slide/{id}/tiles"]["get"].is_null());
+    }
+
+    #[test]
+    fn test_schemas_match_serialized_types() {
+        let spec = openapi_spec();
+        let metadata = SlideMetadata {
+            id: "a".to_string(),
+            name: "a".to_string(),
+            width: 1,
+            height: 1,
+            tile_size: 256,
+            num_levels: 1,
+            format: "svs".to_string(),
+            vendor: Some("aperio".to_string()),
+            mpp_x: Some(0.25),
+            mpp_y: Some(0.25),
+            objective_power: Some(40.0),
+            has_overlay: true,
+        };
+
+        assert_eq!(
+            schema_properties(&spec, "SlideListItem"),
+            serialized_keys(SlideListItem::from(metadata.clone()))
+        );
+        assert_eq!(
+            schema_properties(&spec, "SlideMetadata"),
+            serialized_keys(metadata)
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! Prometheus exporter setup
+//!
+//! Installing the global metrics recorder fails if another one is already
+//! installed, e.g. when the server is embedded in a test or another process.
+//! That isn't fatal: the server runs without Prometheus and
+//! `/metrics/prometheus` answers 503.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use metrics_exporter_prometheus::{BuildError, PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+
+/// Handle for rendering metrics, set once the recorder is installed
+static PROMETHEUS_HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Install the Prometheus metrics recorder (must be done before any metrics
+/// are recorded)
+pub fn setup_prometheus_metrics() -> Result<(), BuildError> {
+    let handle = PrometheusBuilder::new().install_recorder()?;
+    let _ = PROMETHEUS_HANDLE.set(handle);
+    Ok(())
+}
+
+/// Endpoint to expose metrics in Prometheus format
+pub async fn prometheus_metrics() -> Response {
+    match PROMETHEUS_HANDLE.get() {
+        Some(handle) => handle.render().into_response(),
+        None => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Prometheus metrics unavailable",
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_second_install_fails_without_panicking() {
+        let first = setup_prometheus_metrics();
+        assert!(setup_prometheus_metrics().is_err());
+
+        let status = prometheus_metrics().await.status();
+        if first.is_ok() {
+            assert_eq!(status, StatusCode::OK);
+        } else {
+            assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        }
+    }
+}
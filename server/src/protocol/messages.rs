@@ -1,37 +1,126 @@
+use crate::slide::{SlideListItem, SlideMetadata};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// WebSocket protocol version spoken by this server (`major.minor`). Clients
+/// with a different major version are rejected; minor versions are compatible.
+pub const PROTOCOL_VERSION: &str = "1.0";
+
+/// Major component of a `major.minor` protocol version
+pub fn protocol_major(version: &str) -> Option<u32> {
+    version.split('.').next()?.parse().ok()
+}
+
+/// Whether a client's protocol version can talk to this server
+pub fn is_protocol_compatible(version: &str) -> bool {
+    protocol_major(version).is_some() && protocol_major(version) == protocol_major(PROTOCOL_VERSION)
+}
+
+/// Optional feature: participants can post chat messages to the session
+pub const FEATURE_CHAT: &str = "chat";
+
+/// Optional features this server implements. Each session enables a subset.
+pub const OPTIONAL_FEATURES: &[&str] = &[FEATURE_CHAT];
+
+/// Interpolation window followers use for a presenter viewport when the
+/// presenter gives no hint: one 10Hz update interval
+pub const DEFAULT_VIEWPORT_TRANSITION_MS: u32 = 100;
+
+/// Broadcast message types that can be replayed to late joiners. State changes
+/// are left out: the join snapshot already reflects them.
+pub const REPLAYABLE_MESSAGE_TYPES: &[&str] = &[
+    "chat_message",
+    "participant_joined",
+    "participant_left",
+    "participant_renamed",
+];
+
 /// Client to Server messages
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ClientMessage {
+    /// Announce the client's protocol version (expected first; clients that
+    /// skip it are assumed to speak the current version)
+    Hello {
+        protocol_version: String,
+        /// Optional features the client supports (all, if omitted)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        features: Option<Vec<String>>,
+        seq: u64,
+    },
     /// Join an existing session
     JoinSession {
         session_id: String,
         join_secret: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         last_seen_rev: Option<u64>,
+        /// Token from this client's previous `SessionJoined` or
+        /// `SessionUnchanged`, marking the join as a reconnect
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reconnect_token: Option<String>,
         seq: u64,
     },
+    /// Leave the current session, keeping the connection open for another
+    /// create or join
+    LeaveSession { seq: u64 },
     /// Create a new session
-    CreateSession { slide_id: String, seq: u64 },
+    CreateSession {
+        slide_id: String,
+        /// Follower limit for this session (clamped to the server cap)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_followers: Option<usize>,
+        seq: u64,
+    },
+    /// Create a new session on a slide described by the client rather than
+    /// looked up in the slide service (trusted deployments only)
+    CreateSessionWithSlide {
+        slide: SlideInfo,
+        /// Follower limit for this session (clamped to the server cap)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_followers: Option<usize>,
+        seq: u64,
+    },
     /// Authenticate as presenter
     PresenterAuth { presenter_key: String, seq: u64 },
     /// Update cursor position
-    CursorUpdate { x: f64, y: f64, seq: u64 },
+    CursorUpdate {
+        x: f64,
+        y: f64,
+        /// Space `x`/`y` are given in (slide pixels if omitted)
+        #[serde(default)]
+        space: CoordinateSpace,
+        seq: u64,
+    },
     /// Update viewport (presenter: 10Hz, follower: 2Hz)
     ViewportUpdate {
         center_x: f64,
         center_y: f64,
         zoom: f64,
+        /// How long followers should animate towards this viewport; `0` asks
+        /// for an instant jump. Defaults to [`DEFAULT_VIEWPORT_TRANSITION_MS`].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        transition_ms: Option<u32>,
         seq: u64,
     },
     /// Snap to presenter viewport
     SnapToPresenter { seq: u64 },
+    /// Step the presenter viewport back through its recent history (presenter only)
+    RewindViewport { steps: u32, seq: u64 },
+    /// Request the full current session state (e.g. to recover after missed broadcasts)
+    RequestSnapshot { seq: u64 },
     /// Change slide (presenter only)
     ChangeSlide { slide_id: String, seq: u64 },
-    /// Ping for keepalive
-    Ping { seq: u64 },
+    /// Cancel a slide change announced with `SlideChangePending` (presenter only)
+    CancelSlideChange { seq: u64 },
+    /// Push the session's expiry back (presenter only)
+    ExtendSession { seq: u64 },
+    /// Ping for keepalive. `client_ts` is echoed back in the `Pong` so the
+    /// client can measure round-trip time.
+    Ping {
+        seq: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client_ts: Option<u64>,
+    },
     /// Update cell overlay state (presenter only, broadcast to followers)
     CellOverlayUpdate {
         enabled: bool,
@@ -46,23 +135,68 @@ pub enum ClientMessage {
         visible_tissue_types: Vec<i32>,
         seq: u64,
     },
+    /// Change only some overlay fields (presenter only, broadcast as a delta)
+    LayerPatch { patch: LayerPatch, seq: u64 },
+    /// Show or hide follower cursors for everyone (presenter only)
+    SetFollowerCursors { visible: bool, seq: u64 },
+    /// Change the sender's display name
+    SetName { name: String, seq: u64 },
+    /// Post a chat message to the session (requires the `chat` feature)
+    ChatMessage { text: String, seq: u64 },
+    /// Fetch a slide's metadata, as `GET /api/slide/:id`
+    GetSlide { slide_id: String, seq: u64 },
+    /// List available slides, as `GET /api/slides`
+    ListSlides { seq: u64 },
 }
 
 /// Server to Client messages
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ServerMessage {
+    /// Reply to a compatible `Hello`
+    Welcome {
+        protocol_version: String,
+        server_version: String,
+        /// Optional features enabled on this server that the client supports
+        #[serde(default)]
+        features: Vec<String>,
+    },
     /// Session was created successfully (includes secrets for presenter)
     SessionCreated {
         session: SessionSnapshot,
         join_secret: String,
         presenter_key: String,
+        /// Link followers use to join (absolute when a public base URL is configured)
+        join_url: String,
+        /// Link to the session without credentials
+        viewer_url: String,
+        /// Link that also reclaims the presenter role
+        presenter_url: String,
     },
     /// Successfully joined a session
     SessionJoined {
         session: SessionSnapshot,
         you: Participant,
+        /// Optional features enabled for this session
+        #[serde(default)]
+        features: Vec<String>,
+        /// Single-use token to present when reconnecting to this session
+        #[serde(default)]
+        reconnect_token: String,
     },
+    /// Rejoined a session whose content hasn't changed since the client's
+    /// `last_seen_rev`; only the participants are resent
+    SessionUnchanged {
+        rev: u64,
+        you: Participant,
+        presenter: Participant,
+        followers: Vec<Participant>,
+        /// Single-use token to present when reconnecting to this session
+        #[serde(default)]
+        reconnect_token: String,
+    },
+    /// Full current session state, sent only to the client that requested it
+    SessionSnapshot { session: SessionSnapshot },
     /// QoS profile for this client
     QosProfile { profile: QosProfileData },
     /// Acknowledgment of client action
@@ -76,10 +210,33 @@ pub enum ServerMessage {
     SessionError { code: ErrorCode, message: String },
     /// Session has ended
     SessionEnded { reason: SessionEndReason },
+    /// The session expires in `in_secs` unless the presenter extends it
+    SessionExpiring { in_secs: u64 },
+    /// The presenter extended the session; it now expires at `expires_at`
+    SessionExtended { expires_at: u64 },
     /// A participant joined
     ParticipantJoined { participant: Participant },
     /// A participant left
     ParticipantLeft { participant_id: Uuid },
+    /// A participant changed their display name
+    ParticipantRenamed { participant_id: Uuid, name: String },
+    /// Reply to `GetSlide`, sent only to the requesting client
+    SlideMetadata {
+        request_seq: u64,
+        slide: SlideMetadata,
+    },
+    /// Reply to `ListSlides`, sent only to the requesting client
+    SlideList {
+        request_seq: u64,
+        slides: Vec<SlideListItem>,
+    },
+    /// Chat message posted by a participant (broadcast to all participants)
+    ChatMessage {
+        participant_id: Uuid,
+        name: String,
+        text: String,
+        sent_at: u64,
+    },
     /// Presence update (cursor positions)
     PresenceDelta {
         changed: Vec<CursorWithParticipant>,
@@ -87,19 +244,46 @@ pub enum ServerMessage {
         server_ts: u64,
     },
     /// Presenter viewport update
-    PresenterViewport { viewport: Viewport },
-    /// Slide changed notification (broadcast to all participants)
-    SlideChanged { slide: SlideInfo },
+    PresenterViewport {
+        viewport: Viewport,
+        /// How long to animate towards `viewport`; `0` means jump
+        #[serde(default = "default_transition_ms")]
+        transition_ms: u32,
+    },
+    /// Slide changed notification (broadcast to all participants). Carries the
+    /// reset overlay state so clients drop layers from the previous slide.
+    SlideChanged {
+        slide: SlideInfo,
+        rev: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cell_overlay: Option<CellOverlayState>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tissue_overlay: Option<TissueOverlayState>,
+    },
+    /// The presenter changed slides; `SlideChanged` follows in `in_secs`
+    /// seconds (rounded up) unless the change is cancelled
+    SlideChangePending { slide: SlideInfo, in_secs: u64 },
+    /// The pending slide change was cancelled by the presenter
+    SlideChangeCancelled,
     /// Ping for keepalive (server to client)
     Ping,
-    /// Pong response (to client's Ping)
-    Pong,
+    /// Pong response (to client's Ping), with the ping's `client_ts` echoed
+    /// verbatim and the server clock in Unix milliseconds
+    Pong {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client_ts: Option<u64>,
+        server_ts: u64,
+    },
     /// Presenter cell overlay state update (broadcast to all participants)
     PresenterCellOverlay {
         enabled: bool,
         opacity: f64,
         visible_cell_types: Vec<String>,
     },
+    /// Presenter showed or hid follower cursors (broadcast to all participants)
+    FollowerCursorsToggled { visible: bool },
+    /// Overlay fields changed by a presenter `LayerPatch` (broadcast to all participants)
+    LayerPatched { patch: LayerPatch, rev: u64 },
     /// Presenter tissue overlay state update (broadcast to all participants)
     PresenterTissueOverlay {
         enabled: bool,
@@ -108,6 +292,34 @@ pub enum ServerMessage {
     },
 }
 
+/// Coordinate space of a cursor position
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CoordinateSpace {
+    /// Fraction of the slide width/height, 0–1
+    Normalized,
+    /// Level-0 slide pixels; the canonical space for broadcast cursors
+    #[default]
+    SlidePixels,
+}
+
+impl CoordinateSpace {
+    /// Convert a position in this space to slide pixels on a `width` x `height`
+    /// slide. Returns None for non-finite or out-of-range normalized positions.
+    pub fn to_slide_pixels(self, x: f64, y: f64, width: u64, height: u64) -> Option<(f64, f64)> {
+        if !x.is_finite() || !y.is_finite() {
+            return None;
+        }
+        match self {
+            Self::SlidePixels => Some((x, y)),
+            Self::Normalized if (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y) => {
+                Some((x * width as f64, y * height as f64))
+            }
+            Self::Normalized => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum AckStatus {
@@ -124,6 +336,38 @@ pub enum ErrorCode {
     InvalidSlide,
     InvalidMessage,
     Unauthorized,
+    /// Server is draining for a deploy and accepts no new sessions or joins
+    ServerDraining,
+    /// Client's protocol major version differs from the server's
+    UnsupportedProtocol,
+    /// Server has reached its session limit
+    ServerBusy,
+    /// Connection already created or joined a session
+    AlreadyInSession,
+    /// Client message exceeded the server's size limit
+    MessageTooLarge,
+    /// Message needs an optional feature that is disabled for the session
+    FeatureDisabled,
+    /// The session's presenter is disconnected
+    NoPresenter,
+}
+
+impl ErrorCode {
+    /// WebSocket close code for errors that end the connection
+    ///
+    /// Fatal errors are followed by a close frame carrying this code, so
+    /// clients can tell them from recoverable errors and skip reconnecting
+    /// with the same input. Returns None for recoverable errors, which leave
+    /// the socket open.
+    pub fn close_code(self) -> Option<u16> {
+        match self {
+            // 1002: protocol error
+            ErrorCode::UnsupportedProtocol => Some(1002),
+            // 1009: message too big
+            ErrorCode::MessageTooLarge => Some(1009),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -131,6 +375,8 @@ pub enum ErrorCode {
 pub enum SessionEndReason {
     Expired,
     PresenterLeft,
+    /// An operator deleted the session's slide
+    SlideDeleted,
 }
 
 /// Cell overlay state
@@ -149,6 +395,62 @@ pub struct TissueOverlayState {
     pub visible_tissue_types: Vec<i32>,
 }
 
+/// Defaults match the web client's initial overlay controls
+impl Default for CellOverlayState {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            opacity: 0.9,
+            visible_cell_types: Vec::new(),
+        }
+    }
+}
+
+impl Default for TissueOverlayState {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            opacity: 0.7,
+            visible_tissue_types: Vec::new(),
+        }
+    }
+}
+
+/// Partial overlay update: only the `Some` fields change
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LayerPatch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell_opacity: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visible_cell_types: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tissue_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tissue_opacity: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visible_tissue_types: Option<Vec<i32>>,
+}
+
+impl LayerPatch {
+    pub fn touches_cell_overlay(&self) -> bool {
+        self.cell_enabled.is_some()
+            || self.cell_opacity.is_some()
+            || self.visible_cell_types.is_some()
+    }
+
+    pub fn touches_tissue_overlay(&self) -> bool {
+        self.tissue_enabled.is_some()
+            || self.tissue_opacity.is_some()
+            || self.visible_tissue_types.is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.touches_cell_overlay() && !self.touches_tissue_overlay()
+    }
+}
+
 /// Session snapshot for state transfer
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionSnapshot {
@@ -162,6 +464,19 @@ pub struct SessionSnapshot {
     pub cell_overlay: Option<CellOverlayState>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tissue_overlay: Option<TissueOverlayState>,
+    /// Whether follower cursors are shown (presenter can hide them)
+    #[serde(default = "default_true")]
+    pub follower_cursors_visible: bool,
+    /// Followers allowed in this session
+    pub max_followers: usize,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_transition_ms() -> u32 {
+    DEFAULT_VIEWPORT_TRANSITION_MS
 }
 
 /// Participant info
@@ -191,6 +506,38 @@ pub struct SlideInfo {
     pub tile_size: u32,
     pub num_levels: u32,
     pub tile_url_template: String,
+    /// Microns per pixel X, for scale bars (if known)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mpp_x: Option<f64>,
+    /// Microns per pixel Y, for scale bars (if known)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mpp_y: Option<f64>,
+    /// Scanner objective magnification (if known)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objective_power: Option<f64>,
+    /// Whether the slide has an overlay, so clients know to offer overlay controls
+    #[serde(default)]
+    pub has_overlay: bool,
+}
+
+impl SlideInfo {
+    /// Check that client-supplied slide metadata describes a usable slide
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("Slide id must not be empty".to_string());
+        }
+        if self.width == 0 || self.height == 0 {
+            return Err("Slide dimensions must be non-zero".to_string());
+        }
+        if self.tile_size == 0 || self.num_levels == 0 {
+            return Err("Tile size and level count must be non-zero".to_string());
+        }
+        let valid_mpp = |mpp: Option<f64>| mpp.is_none_or(|v| v.is_finite() && v > 0.0);
+        if !valid_mpp(self.mpp_x) || !valid_mpp(self.mpp_y) {
+            return Err("Microns per pixel must be positive".to_string());
+        }
+        Ok(())
+    }
 }
 
 /// Viewport state
@@ -211,6 +558,9 @@ pub struct CursorWithParticipant {
     pub is_presenter: bool,
     pub x: f64,
     pub y: f64,
+    /// Always `SlidePixels`: the server converts incoming cursors to it
+    #[serde(default)]
+    pub space: CoordinateSpace,
 }
 
 /// QoS profile data
@@ -233,16 +583,58 @@ impl ClientMessage {
     /// Get the message type name for metrics
     pub fn message_type(&self) -> &'static str {
         match self {
+            ClientMessage::Hello { .. } => "hello",
             ClientMessage::JoinSession { .. } => "join_session",
+            ClientMessage::LeaveSession { .. } => "leave_session",
             ClientMessage::CreateSession { .. } => "create_session",
+            ClientMessage::CreateSessionWithSlide { .. } => "create_session_with_slide",
             ClientMessage::PresenterAuth { .. } => "presenter_auth",
             ClientMessage::CursorUpdate { .. } => "cursor_update",
             ClientMessage::ViewportUpdate { .. } => "viewport_update",
             ClientMessage::SnapToPresenter { .. } => "snap_to_presenter",
+            ClientMessage::RewindViewport { .. } => "rewind_viewport",
+            ClientMessage::RequestSnapshot { .. } => "request_snapshot",
             ClientMessage::ChangeSlide { .. } => "change_slide",
+            ClientMessage::CancelSlideChange { .. } => "cancel_slide_change",
+            ClientMessage::ExtendSession { .. } => "extend_session",
             ClientMessage::Ping { .. } => "ping",
             ClientMessage::CellOverlayUpdate { .. } => "cell_overlay_update",
             ClientMessage::TissueOverlayUpdate { .. } => "tissue_overlay_update",
+            ClientMessage::LayerPatch { .. } => "layer_patch",
+            ClientMessage::SetFollowerCursors { .. } => "set_follower_cursors",
+            ClientMessage::SetName { .. } => "set_name",
+            ClientMessage::ChatMessage { .. } => "chat_message",
+            ClientMessage::GetSlide { .. } => "get_slide",
+            ClientMessage::ListSlides { .. } => "list_slides",
+        }
+    }
+
+    /// Client sequence number, echoed back in the `Ack`
+    pub fn seq(&self) -> u64 {
+        match self {
+            ClientMessage::Hello { seq, .. }
+            | ClientMessage::JoinSession { seq, .. }
+            | ClientMessage::LeaveSession { seq }
+            | ClientMessage::CreateSession { seq, .. }
+            | ClientMessage::CreateSessionWithSlide { seq, .. }
+            | ClientMessage::PresenterAuth { seq, .. }
+            | ClientMessage::CursorUpdate { seq, .. }
+            | ClientMessage::ViewportUpdate { seq, .. }
+            | ClientMessage::SnapToPresenter { seq }
+            | ClientMessage::RewindViewport { seq, .. }
+            | ClientMessage::RequestSnapshot { seq }
+            | ClientMessage::ChangeSlide { seq, .. }
+            | ClientMessage::CancelSlideChange { seq }
+            | ClientMessage::ExtendSession { seq }
+            | ClientMessage::Ping { seq, .. }
+            | ClientMessage::CellOverlayUpdate { seq, .. }
+            | ClientMessage::TissueOverlayUpdate { seq, .. }
+            | ClientMessage::LayerPatch { seq, .. }
+            | ClientMessage::SetFollowerCursors { seq, .. }
+            | ClientMessage::SetName { seq, .. }
+            | ClientMessage::ChatMessage { seq, .. }
+            | ClientMessage::GetSlide { seq, .. }
+            | ClientMessage::ListSlides { seq } => *seq,
         }
     }
 }
@@ -251,21 +643,34 @@ impl ServerMessage {
     /// Get the message type name for metrics
     pub fn message_type(&self) -> &'static str {
         match self {
+            ServerMessage::Welcome { .. } => "welcome",
             ServerMessage::SessionCreated { .. } => "session_created",
             ServerMessage::SessionJoined { .. } => "session_joined",
+            ServerMessage::SessionUnchanged { .. } => "session_unchanged",
+            ServerMessage::SessionSnapshot { .. } => "session_snapshot",
             ServerMessage::QosProfile { .. } => "qos_profile",
             ServerMessage::Ack { .. } => "ack",
             ServerMessage::SessionError { .. } => "session_error",
             ServerMessage::SessionEnded { .. } => "session_ended",
+            ServerMessage::SessionExpiring { .. } => "session_expiring",
+            ServerMessage::SessionExtended { .. } => "session_extended",
             ServerMessage::ParticipantJoined { .. } => "participant_joined",
             ServerMessage::ParticipantLeft { .. } => "participant_left",
+            ServerMessage::ParticipantRenamed { .. } => "participant_renamed",
+            ServerMessage::ChatMessage { .. } => "chat_message",
+            ServerMessage::SlideMetadata { .. } => "slide_metadata",
+            ServerMessage::SlideList { .. } => "slide_list",
             ServerMessage::PresenceDelta { .. } => "presence_delta",
             ServerMessage::PresenterViewport { .. } => "presenter_viewport",
             ServerMessage::SlideChanged { .. } => "slide_changed",
+            ServerMessage::SlideChangePending { .. } => "slide_change_pending",
+            ServerMessage::SlideChangeCancelled => "slide_change_cancelled",
             ServerMessage::Ping => "ping",
-            ServerMessage::Pong => "pong",
+            ServerMessage::Pong { .. } => "pong",
             ServerMessage::PresenterCellOverlay { .. } => "presenter_cell_overlay",
             ServerMessage::PresenterTissueOverlay { .. } => "presenter_tissue_overlay",
+            ServerMessage::LayerPatched { .. } => "layer_patched",
+            ServerMessage::FollowerCursorsToggled { .. } => "follower_cursors_toggled",
         }
     }
 }
@@ -0,0 +1,104 @@
+//! Request ids for correlating client reports with server logs
+//!
+//! Every HTTP request gets an id: the client's `X-Request-Id` if it sent a
+//! usable one, otherwise a fresh UUID. The id is stored in the request
+//! extensions as [`RequestId`], recorded on a `request` span around the
+//! request's handling (so every log line inside carries it) and echoed in
+//! the response's `X-Request-Id` header. WebSocket connections outlive their
+//! upgrade request, so the connection span records the id separately.
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{Instrument, info_span};
+use uuid::Uuid;
+
+/// Header carrying the request id, both ways
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied id that is kept; longer ones are replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Id of the request being handled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// Client ids are logged verbatim, so only short printable ASCII ones are kept
+fn is_usable(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Middleware assigning, logging and echoing the request id
+pub async fn assign_request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_usable(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    request.extensions_mut().insert(RequestId(id.clone()));
+
+    let span = info_span!("request", request_id = %id);
+    let mut response = next.run(request).instrument(span).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Extension, Router, body::Body, middleware, routing::get};
+    use tower::ServiceExt;
+
+    async fn request_id_of(header: Option<&str>) -> (String, String) {
+        let app = Router::new()
+            .route(
+                "/echo",
+                get(|Extension(RequestId(id)): Extension<RequestId>| async move { id }),
+            )
+            .layer(middleware::from_fn(assign_request_id));
+        let mut request = Request::get("/echo");
+        if let Some(id) = header {
+            request = request.header(REQUEST_ID_HEADER, id);
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let echoed = response.headers()[REQUEST_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (echoed, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_provided_request_id_is_echoed() {
+        let (echoed, seen_by_handler) = request_id_of(Some("client-42")).await;
+        assert_eq!(echoed, "client-42");
+        assert_eq!(seen_by_handler, "client-42");
+    }
+
+    #[tokio::test]
+    async fn test_missing_request_id_is_generated() {
+        let (echoed, seen_by_handler) = request_id_of(None).await;
+        assert!(Uuid::parse_str(&echoed).is_ok());
+        assert_eq!(seen_by_handler, echoed);
+
+        // Unusable ids are replaced too
+        let long = "x".repeat(MAX_REQUEST_ID_LEN + 1);
+        let (echoed, _) = request_id_of(Some(&long)).await;
+        assert!(Uuid::parse_str(&echoed).is_ok());
+    }
+}
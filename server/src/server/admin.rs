@@ -0,0 +1,478 @@
+//! Readiness and admin HTTP endpoints
+//!
+//! `/ready` tells the load balancer whether to route new traffic here.
+//! `/api/admin/*` endpoints require `Authorization: Bearer <ADMIN_TOKEN>` and
+//! are disabled when no admin token is configured.
+
+use super::AppState;
+use crate::protocol::{ParticipantRole, SessionEndReason};
+use crate::session::state::{SessionId, SessionState};
+use crate::session::stats::SessionLifecycleStats;
+use crate::slide::routes::SlideErrorResponse;
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReadyResponse {
+    pub ready: bool,
+    pub draining: bool,
+    /// Session cleanup hasn't run within twice its interval
+    #[serde(default)]
+    pub cleanup_stalled: bool,
+}
+
+/// GET /ready - 503 while draining, or when session cleanup has stalled, so the
+/// load balancer stops routing new traffic
+pub async fn ready(State(state): State<AppState>) -> (StatusCode, Json<ReadyResponse>) {
+    let draining = state.is_draining();
+    let cleanup_stalled = state.cleanup_stalled();
+    let ready = !draining && !cleanup_stalled;
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(ReadyResponse {
+            ready,
+            draining,
+            cleanup_stalled,
+        }),
+    )
+}
+
+/// Admin routes, to be nested under `/api`
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/admin/drain", post(drain))
+        .route("/admin/stats", get(stats))
+        .route("/admin/sessions/:id", get(session_detail))
+        .route("/admin/slides/reload", post(reload_slides))
+        .route("/admin/slides/:id", delete(delete_slide))
+}
+
+/// Check the bearer token against the configured admin token
+fn authorize(state: &AppState, headers: &HeaderMap) -> Result<(), (StatusCode, &'static str)> {
+    let Some(ref expected) = state.admin_token else {
+        return Err((StatusCode::FORBIDDEN, "admin API disabled"));
+    };
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    // Constant-time, so response timing doesn't reveal how much of a guess matched
+    let matches =
+        provided.is_some_and(|token| bool::from(token.as_bytes().ct_eq(expected.as_bytes())));
+    if matches {
+        Ok(())
+    } else {
+        Err((StatusCode::UNAUTHORIZED, "invalid admin token"))
+    }
+}
+
+/// POST /api/admin/drain - Stop accepting new sessions; existing ones continue
+async fn drain(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Err(rejection) = authorize(&state, &headers) {
+        warn!("Rejected unauthorized drain request");
+        return rejection.into_response();
+    }
+
+    state.set_draining(true);
+    info!("Server draining: new sessions and joins are rejected");
+    Json(ReadyResponse {
+        ready: false,
+        draining: true,
+        cleanup_stalled: state.cleanup_stalled(),
+    })
+    .into_response()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminStatsResponse {
+    pub total_connections: usize,
+    #[serde(flatten)]
+    pub sessions: SessionLifecycleStats,
+}
+
+/// GET /api/admin/stats - JSON summary of sessions for dashboards without Prometheus
+async fn stats(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Err(rejection) = authorize(&state, &headers) {
+        return rejection.into_response();
+    }
+
+    Json(AdminStatsResponse {
+        total_connections: state.connections.len(),
+        sessions: state.session_manager.lifecycle_stats(),
+    })
+    .into_response()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminParticipant {
+    pub id: Uuid,
+    pub name: String,
+    pub role: ParticipantRole,
+    pub connected_at: u64,
+    pub last_seen_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminSessionResponse {
+    pub id: SessionId,
+    pub rev: u64,
+    pub content_rev: u64,
+    /// `active`, `presenter_disconnected` or `expired`
+    pub state: String,
+    /// When the presenter disconnected, while `presenter_disconnected`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presenter_disconnected_at: Option<u64>,
+    pub locked: bool,
+    pub created_at: u64,
+    pub expires_at: u64,
+    /// Most recent time any participant was seen
+    pub last_activity_at: u64,
+    pub slide_id: String,
+    pub presenter_id: Uuid,
+    pub participants: Vec<AdminParticipant>,
+    pub max_followers: usize,
+    pub features: Vec<String>,
+    /// WebSocket connections attached to the session
+    pub connections: usize,
+    /// Receivers subscribed to the session broadcaster
+    pub broadcast_receivers: usize,
+}
+
+/// GET /api/admin/sessions/:id - Internal state of one session, for debugging.
+/// Join secret and presenter key hashes are left out.
+async fn session_detail(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(session_id): Path<SessionId>,
+) -> Response {
+    if let Err(rejection) = authorize(&state, &headers) {
+        return rejection.into_response();
+    }
+    let Ok(session) = state.session_manager.session_internals(&session_id) else {
+        return (StatusCode::NOT_FOUND, "session not found").into_response();
+    };
+
+    let (state_name, presenter_disconnected_at) = match session.state {
+        SessionState::Active => ("active", None),
+        SessionState::PresenterDisconnected { disconnect_at } => {
+            ("presenter_disconnected", Some(disconnect_at))
+        }
+        SessionState::Expired => ("expired", None),
+    };
+    let mut participants: Vec<AdminParticipant> = session
+        .participants
+        .values()
+        .map(|p| AdminParticipant {
+            id: p.id,
+            name: p.name.clone(),
+            role: p.role,
+            connected_at: p.connected_at,
+            last_seen_at: p.last_seen_at,
+        })
+        .collect();
+    participants.sort_by_key(|p| p.connected_at);
+    let last_activity_at = participants
+        .iter()
+        .map(|p| p.last_seen_at)
+        .fold(session.created_at, u64::max);
+    let connections = state
+        .connections
+        .iter()
+        .filter(|conn| conn.session_id.as_deref() == Some(session_id.as_str()))
+        .count();
+    let broadcast_receivers = state
+        .session_broadcasters
+        .get(&session_id)
+        .map_or(0, |sender| sender.receiver_count());
+
+    Json(AdminSessionResponse {
+        id: session.id,
+        rev: session.rev,
+        content_rev: session.content_rev,
+        state: state_name.to_string(),
+        presenter_disconnected_at,
+        locked: session.locked,
+        created_at: session.created_at,
+        expires_at: session.expires_at,
+        last_activity_at,
+        slide_id: session.slide.id,
+        presenter_id: session.presenter_id,
+        participants,
+        max_followers: session.max_followers,
+        features: session.features,
+        connections,
+        broadcast_receivers,
+    })
+    .into_response()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReloadSlidesResponse {
+    /// Number of slides in the catalog after the rescan
+    pub slides: usize,
+}
+
+/// POST /api/admin/slides/reload - Rescan the slides directory without a restart
+async fn reload_slides(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Err(rejection) = authorize(&state, &headers) {
+        warn!("Rejected unauthorized slide reload request");
+        return rejection.into_response();
+    }
+    let Some(ref service) = state.slide_service else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "slide service unavailable").into_response();
+    };
+
+    match service.reload().await {
+        Ok(slides) => {
+            info!("Slide catalog reloaded: {} slides", slides);
+            Json(ReloadSlidesResponse { slides }).into_response()
+        }
+        Err(e) => {
+            warn!("Slide catalog reload failed: {}", e);
+            SlideErrorResponse::from(e).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DeleteSlideQuery {
+    /// End sessions showing the slide instead of refusing the deletion
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteSlideResponse {
+    pub slide_id: String,
+    /// Sessions ended because they were showing the slide
+    pub ended_sessions: Vec<SessionId>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlideInUseResponse {
+    pub error: String,
+    /// Active sessions showing the slide
+    pub sessions: Vec<SessionId>,
+}
+
+/// DELETE /api/admin/slides/:id - Remove a slide file
+///
+/// Refused with 409 while active sessions show the slide, unless `?force=true`,
+/// which ends those sessions with `SessionEnded { reason: slide_deleted }`.
+async fn delete_slide(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(slide_id): Path<String>,
+    Query(query): Query<DeleteSlideQuery>,
+) -> Response {
+    if let Err(rejection) = authorize(&state, &headers) {
+        warn!("Rejected unauthorized slide deletion request");
+        return rejection.into_response();
+    }
+    let Some(ref service) = state.slide_service else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "slide service unavailable").into_response();
+    };
+
+    let sessions = state.session_manager.sessions_using_slide(&slide_id);
+    if !sessions.is_empty() && !query.force {
+        return (
+            StatusCode::CONFLICT,
+            Json(SlideInUseResponse {
+                error: format!(
+                    "Slide {} is in use by {} active session(s)",
+                    slide_id,
+                    sessions.len()
+                ),
+                sessions,
+            }),
+        )
+            .into_response();
+    }
+
+    if let Err(e) = service.delete_slide(&slide_id).await {
+        warn!("Failed to delete slide {}: {}", slide_id, e);
+        return SlideErrorResponse::from(e).into_response();
+    }
+
+    if let Some(ref fovea) = state.fovea {
+        fovea.evict_slide(&slide_id);
+    }
+
+    // Look again: a session may have been created on the slide while it was
+    // being deleted, and one may have ended on its own since the first lookup
+    let mut ended_sessions = Vec::new();
+    for session_id in state.session_manager.sessions_using_slide(&slide_id) {
+        if state
+            .end_session(&session_id, SessionEndReason::SlideDeleted)
+            .await
+            .is_ok()
+        {
+            ended_sessions.push(session_id);
+        }
+    }
+    info!(
+        "Deleted slide {} (ended {} sessions)",
+        slide_id,
+        ended_sessions.len()
+    );
+    Json(DeleteSlideResponse {
+        slide_id,
+        ended_sessions,
+    })
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ServerMessage;
+    use crate::server::harness::{FIXTURE_SLIDE_ID, FakeConnection, fixture_state};
+    use axum::body::Body;
+    use axum::http::Request;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    const ADMIN_TOKEN: &str = "admin-secret";
+
+    async fn delete_fixture_slide(state: &AppState, query: &str) -> (StatusCode, Vec<u8>) {
+        let app = Router::new()
+            .nest("/api", admin_routes())
+            .with_state(state.clone());
+        let request = Request::delete(format!("/api/admin/slides/{FIXTURE_SLIDE_ID}{query}"))
+            .header(header::AUTHORIZATION, format!("Bearer {ADMIN_TOKEN}"))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, body.to_vec())
+    }
+
+    /// A state with one session showing the fixture slide, and its presenter
+    async fn state_with_session() -> (AppState, FakeConnection) {
+        let state = fixture_state().with_admin_token(Some(ADMIN_TOKEN.to_string()));
+        let (presenter, _) = FakeConnection::create_session(&state).await;
+        (state, presenter)
+    }
+
+    async fn get_session_detail(state: &AppState, session_id: &str) -> (StatusCode, Vec<u8>) {
+        let app = Router::new()
+            .nest("/api", admin_routes())
+            .with_state(state.clone());
+        let request = Request::get(format!("/api/admin/sessions/{session_id}"))
+            .header(header::AUTHORIZATION, format!("Bearer {ADMIN_TOKEN}"))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_session_detail() {
+        let (state, presenter) = state_with_session().await;
+        let session_id = presenter.session_id().unwrap();
+        let snapshot = state
+            .session_manager
+            .get_session(&session_id)
+            .await
+            .unwrap();
+
+        let (status, body) = get_session_detail(&state, &session_id).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!String::from_utf8_lossy(&body).contains("hash"));
+        let detail: AdminSessionResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(detail.id, session_id);
+        assert_eq!(detail.state, "active");
+        assert_eq!(detail.rev, snapshot.rev);
+        assert_eq!(detail.slide_id, FIXTURE_SLIDE_ID);
+        assert_eq!(detail.participants.len(), 1);
+        assert_eq!(detail.participants[0].role, ParticipantRole::Presenter);
+        assert_eq!(detail.connections, 1);
+        assert!(detail.expires_at > detail.created_at);
+
+        let (status, _) = get_session_detail(&state, "unknown").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_delete_slide_refused_while_in_use() {
+        let (state, presenter) = state_with_session().await;
+        let session_id = presenter.session_id().unwrap();
+
+        let (status, body) = delete_fixture_slide(&state, "").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let body: SlideInUseResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.sessions, vec![session_id.clone()]);
+        assert!(state.session_manager.get_session(&session_id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_forced_delete_ends_sessions() {
+        let (state, mut presenter) = state_with_session().await;
+        let session_id = presenter.session_id().unwrap();
+
+        let (status, body) = delete_fixture_slide(&state, "?force=true").await;
+        assert_eq!(status, StatusCode::OK);
+        let body: DeleteSlideResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.ended_sessions, vec![session_id.clone()]);
+
+        assert!(presenter.drain().iter().any(|m| matches!(
+            m,
+            ServerMessage::SessionEnded {
+                reason: SessionEndReason::SlideDeleted
+            }
+        )));
+        assert!(
+            state
+                .session_manager
+                .get_session(&session_id)
+                .await
+                .is_err()
+        );
+        assert_eq!(presenter.session_id(), None);
+    }
+
+    #[tokio::test]
+    async fn test_ready_degrades_when_cleanup_stalls() {
+        let state = AppState::new().with_cleanup_interval(Duration::from_secs(60));
+
+        let (status, Json(body)) = ready(State(state.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!body.cleanup_stalled);
+
+        // Last pass 3 intervals ago: the cleanup task has stopped
+        let stalled_at = crate::session::state::now_millis() - 180_000;
+        state.last_cleanup_at.store(stalled_at, Ordering::Relaxed);
+        let (status, Json(body)) = ready(State(state.clone())).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body.cleanup_stalled);
+        assert!(!body.ready);
+        assert!(!body.draining);
+
+        // A fresh heartbeat recovers
+        state.record_cleanup();
+        let (status, _) = ready(State(state)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
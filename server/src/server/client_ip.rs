@@ -0,0 +1,195 @@
+//! Client IP resolution behind trusted reverse proxies
+//!
+//! Behind a proxy every request arrives from the proxy's address. When the
+//! peer is in `TRUSTED_PROXIES`, the client address is taken from
+//! `X-Forwarded-For` (or the standard `Forwarded` header), walking the chain
+//! from the right and skipping further trusted hops. Headers from untrusted
+//! peers are ignored, so clients cannot spoof their address.
+
+use axum::http::HeaderMap;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// An IP network in CIDR notation, e.g. `10.0.0.0/8` or `::1/128`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                prefix_matches(&net.octets(), &ip.octets(), self.prefix_len)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(&net.octets(), &ip.octets(), self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = String;
+
+    /// Parse `addr/len`; a bare address is a single-host network
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match s.trim().split_once('/') {
+            Some((addr, len)) => (addr, Some(len)),
+            None => (s.trim(), None),
+        };
+        let network: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("invalid address {addr:?}"))?;
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(len) => len
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| format!("invalid prefix length {len:?}"))?,
+            None => max_len,
+        };
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+}
+
+fn prefix_matches(network: &[u8], ip: &[u8], prefix_len: u8) -> bool {
+    let full_bytes = usize::from(prefix_len / 8);
+    let rest_bits = prefix_len % 8;
+    if network[..full_bytes] != ip[..full_bytes] {
+        return false;
+    }
+    if rest_bits == 0 {
+        return true;
+    }
+    let mask = 0xffu8 << (8 - rest_bits);
+    network[full_bytes] & mask == ip[full_bytes] & mask
+}
+
+/// Address of the client behind `peer`. Forwarding headers are only trusted
+/// when `peer` is one of `trusted_proxies`.
+pub fn client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpCidr]) -> IpAddr {
+    let is_trusted = |ip: IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    if !is_trusted(peer) {
+        return peer;
+    }
+
+    // Nearest hop last: the first untrusted address from the right is the client
+    let chain = forwarded_chain(headers);
+    chain
+        .iter()
+        .rev()
+        .find(|ip| !is_trusted(**ip))
+        .or(chain.first())
+        .copied()
+        .unwrap_or(peer)
+}
+
+/// Addresses listed in `X-Forwarded-For`, or else in `Forwarded: for=...`
+fn forwarded_chain(headers: &HeaderMap) -> Vec<IpAddr> {
+    let values = |name: &str| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    let xff: Vec<IpAddr> = values("x-forwarded-for")
+        .iter()
+        .filter_map(|v| parse_node(v))
+        .collect();
+    if !xff.is_empty() {
+        return xff;
+    }
+
+    values("forwarded")
+        .iter()
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.trim().split_once('=')?;
+                key.eq_ignore_ascii_case("for")
+                    .then(|| parse_node(value.trim_matches('"')))
+                    .flatten()
+            })
+        })
+        .collect()
+}
+
+/// Parse a forwarded node: `1.2.3.4`, `1.2.3.4:5678`, `[::1]` or `[::1]:5678`
+fn parse_node(node: &str) -> Option<IpAddr> {
+    if let Ok(ip) = node.parse() {
+        return Some(ip);
+    }
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+    node.rsplit_once(':')?.0.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_forwarded_ip_used_only_from_trusted_proxy() {
+        let trusted: Vec<IpCidr> = ["10.0.0.0/8", "::1"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("198.51.100.7, 10.1.2.3"),
+        );
+
+        // Through the proxy chain: the first hop that isn't a trusted proxy
+        assert_eq!(
+            client_ip(ip("10.0.0.5"), &headers, &trusted),
+            ip("198.51.100.7")
+        );
+        assert_eq!(client_ip(ip("::1"), &headers, &trusted), ip("198.51.100.7"));
+
+        // Spoofed header straight from a client is ignored
+        assert_eq!(
+            client_ip(ip("203.0.113.9"), &headers, &trusted),
+            ip("203.0.113.9")
+        );
+        assert_eq!(client_ip(ip("10.0.0.5"), &headers, &[]), ip("10.0.0.5"));
+
+        // RFC 7239 Forwarded header when there is no X-Forwarded-For
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "forwarded",
+            HeaderValue::from_static(r#"for="[2001:db8::1]:4711";proto=https, for=10.0.0.9"#),
+        );
+        assert_eq!(
+            client_ip(ip("10.0.0.5"), &headers, &trusted),
+            ip("2001:db8::1")
+        );
+    }
+
+    #[test]
+    fn test_cidr_parsing() {
+        let net: IpCidr = "192.168.0.0/20".parse().unwrap();
+        assert!(net.contains(ip("192.168.15.255")));
+        assert!(!net.contains(ip("192.168.16.0")));
+        assert!(net.contains(ip("::ffff:192.168.1.1")));
+        assert!("10.0.0.0/33".parse::<IpCidr>().is_err());
+        assert!("proxy.internal".parse::<IpCidr>().is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! In-process WebSocket test harness
+//!
+//! Drives `handle_client_message` directly against an `AppState`, with an
+//! in-memory channel standing in for the socket. Protocol flows can be tested
+//! deterministically, without a TCP server, tungstenite, or sleeps: every
+//! message the handler produces is already queued when `send` returns.
+
+use super::outbound::{ConnectionSender, connection_channel};
+use super::rate_limit::TokenBucket;
+use super::websocket::{AppState, Connection, handle_client_message};
+use crate::protocol::{AckStatus, ClientMessage, ServerMessage};
+use crate::slide::{SlideError, SlideMetadata, SlideService};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::Instant;
+use uuid::Uuid;
+
+/// Id of the slide served by [`FixtureSlides`]
+pub const FIXTURE_SLIDE_ID: &str = "test-slide";
+
+/// Slide service with a single fixture slide
+pub struct FixtureSlides;
+
+#[async_trait]
+impl SlideService for FixtureSlides {
+    async fn list_slides(&self) -> Result<Vec<SlideMetadata>, SlideError> {
+        Ok(vec![fixture_slide()])
+    }
+
+    async fn get_slide(&self, id: &str) -> Result<SlideMetadata, SlideError> {
+        if id == FIXTURE_SLIDE_ID {
+            Ok(fixture_slide())
+        } else {
+            Err(SlideError::NotFound(id.to_string()))
+        }
+    }
+
+    /// Pretends to delete the fixture slide; it is still listed afterwards
+    async fn delete_slide(&self, id: &str) -> Result<(), SlideError> {
+        self.get_slide(id).await.map(|_| ())
+    }
+}
+
+fn fixture_slide() -> SlideMetadata {
+    SlideMetadata {
+        id: FIXTURE_SLIDE_ID.to_string(),
+        name: "Test Slide".to_string(),
+        width: 10000,
+        height: 10000,
+        tile_size: 256,
+        num_levels: 14,
+        format: "mock".to_string(),
+        vendor: None,
+        mpp_x: Some(0.25),
+        mpp_y: Some(0.25),
+        objective_power: Some(40.0),
+        has_overlay: false,
+    }
+}
+
+/// App state serving the fixture slide
+pub fn fixture_state() -> AppState {
+    AppState::new().with_slide_service(Arc::new(FixtureSlides))
+}
+
+/// A connection registered with an `AppState` but backed by channels
+pub struct FakeConnection {
+    pub id: Uuid,
+    state: AppState,
+    tx: ConnectionSender,
+    rx: mpsc::Receiver<ServerMessage>,
+    /// Session broadcasts, subscribed once the connection is in a session
+    broadcasts: Option<(String, broadcast::Receiver<ServerMessage>)>,
+}
+
+impl FakeConnection {
+    /// Register a new connection, as `handle_socket` does on upgrade
+    pub fn connect(state: &AppState) -> Self {
+        let id = Uuid::new_v4();
+        let (tx, rx) = connection_channel(id, 256);
+        state.connections.insert(
+            id,
+            Connection {
+                id,
+                session_id: None,
+                participant_id: None,
+                is_presenter: false,
+                last_ping: Instant::now(),
+                sender: tx.clone(),
+                name: None,
+                color: None,
+                protocol_supported: true,
+                cursor_bucket: TokenBucket::per_second(state.ws_config.max_cursor_hz),
+            },
+        );
+        Self {
+            id,
+            state: state.clone(),
+            tx,
+            rx,
+            broadcasts: None,
+        }
+    }
+
+    /// Connect and create a session on the fixture slide, returning the
+    /// presenter and the session's join secret. Creation replies are consumed.
+    pub async fn create_session(state: &AppState) -> (Self, String) {
+        let mut presenter = Self::connect(state);
+        presenter
+            .send(ClientMessage::CreateSession {
+                slide_id: FIXTURE_SLIDE_ID.to_string(),
+                max_followers: None,
+                seq: 1,
+            })
+            .await;
+        let join_secret = presenter.drain().into_iter().find_map(|m| match m {
+            ServerMessage::SessionCreated { join_secret, .. } => Some(join_secret),
+            _ => None,
+        });
+        (presenter, join_secret.expect("Session should be created"))
+    }
+
+    /// Connect and join `session_id`; the join replies are left queued
+    pub async fn join(state: &AppState, session_id: &str, join_secret: &str) -> Self {
+        let mut follower = Self::connect(state);
+        follower
+            .send(ClientMessage::JoinSession {
+                session_id: session_id.to_string(),
+                join_secret: join_secret.to_string(),
+                last_seen_rev: None,
+                reconnect_token: None,
+                seq: 1,
+            })
+            .await;
+        follower
+    }
+
+    /// Handle one client message to completion
+    pub async fn send(&mut self, msg: ClientMessage) {
+        handle_client_message(msg, self.id, &self.state, &self.tx).await;
+        self.follow_session().await;
+    }
+
+    /// Session the connection is currently in
+    pub fn session_id(&self) -> Option<String> {
+        self.state
+            .connections
+            .get(&self.id)
+            .and_then(|conn| conn.session_id.clone())
+    }
+
+    /// Messages sent to this connection so far: direct replies first, then
+    /// session broadcasts received since joining
+    pub fn drain(&mut self) -> Vec<ServerMessage> {
+        let mut messages = Vec::new();
+        while let Ok(msg) = self.rx.try_recv() {
+            messages.push(msg);
+        }
+        if let Some((_, ref mut broadcasts)) = self.broadcasts {
+            while let Ok(msg) = broadcasts.try_recv() {
+                messages.push(msg);
+            }
+        }
+        messages
+    }
+
+    /// Subscribe to the broadcaster of the session the connection is in
+    async fn follow_session(&mut self) {
+        let session_id = self.session_id();
+        let current = self.broadcasts.as_ref().map(|(id, _)| id.clone());
+        if session_id == current {
+            return;
+        }
+        self.broadcasts = match session_id {
+            Some(id) => {
+                let broadcaster = self.state.get_session_broadcaster(&id).await;
+                Some((id, broadcaster.subscribe()))
+            }
+            None => None,
+        };
+    }
+}
+
+/// A new session on the fixture slide with one follower, returned as
+/// (presenter, follower). The follower's join replies are left queued.
+pub async fn session_with_follower(state: &AppState) -> (FakeConnection, FakeConnection) {
+    let (presenter, join_secret) = FakeConnection::create_session(state).await;
+    let session_id = presenter
+        .session_id()
+        .expect("Presenter should be in the session");
+    let follower = FakeConnection::join(state, &session_id, &join_secret).await;
+    (presenter, follower)
+}
+
+/// Status of the ack for `seq` among `messages`
+pub fn ack_status(messages: &[ServerMessage], seq: u64) -> Option<AckStatus> {
+    messages.iter().find_map(|msg| match msg {
+        ServerMessage::Ack {
+            ack_seq, status, ..
+        } if *ack_seq == seq => Some(*status),
+        _ => None,
+    })
+}
@@ -1,3 +1,15 @@
+pub mod admin;
+pub mod client_ip;
+#[cfg(test)]
+pub(crate) mod harness;
+pub mod outbound;
+pub mod presence;
+pub mod rate_limit;
+pub mod recording;
+pub mod replay;
+pub mod sessions;
 pub mod websocket;
 
+pub use admin::{admin_routes, ready};
+pub use sessions::session_routes;
 pub use websocket::*;
@@ -0,0 +1,164 @@
+//! Per-connection outgoing message queues
+//!
+//! Each connection's messages pass through a bounded channel drained by its
+//! socket writer. A slow client lets that queue fill up until senders block,
+//! so every enqueue samples the queue depth into
+//! `pathcollab_ws_send_queue_depth`, and a warning is logged when a queue
+//! crosses its high-water mark.
+
+use crate::protocol::ServerMessage;
+use metrics::histogram;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Capacity of a WebSocket connection's outgoing queue
+pub const SEND_QUEUE_CAPACITY: usize = 32;
+
+/// Sending half of a connection's outgoing queue
+#[derive(Clone)]
+pub struct ConnectionSender {
+    inner: mpsc::Sender<ServerMessage>,
+    connection_id: Uuid,
+    /// Set while the queue is above its high-water mark, so a backed-up
+    /// connection warns once rather than on every message
+    above_high_water: Arc<AtomicBool>,
+}
+
+/// Create an outgoing queue for `connection_id`
+pub fn connection_channel(
+    connection_id: Uuid,
+    capacity: usize,
+) -> (ConnectionSender, mpsc::Receiver<ServerMessage>) {
+    let (inner, rx) = mpsc::channel(capacity);
+    let sender = ConnectionSender {
+        inner,
+        connection_id,
+        above_high_water: Arc::new(AtomicBool::new(false)),
+    };
+    (sender, rx)
+}
+
+impl ConnectionSender {
+    /// Queue a message, waiting for room if the queue is full
+    pub async fn send(
+        &self,
+        msg: ServerMessage,
+    ) -> Result<(), mpsc::error::SendError<ServerMessage>> {
+        self.inner.send(msg).await?;
+        self.record_depth();
+        Ok(())
+    }
+
+    /// Messages queued but not yet written to the socket
+    pub fn depth(&self) -> usize {
+        self.inner.max_capacity() - self.inner.capacity()
+    }
+
+    fn high_water_mark(&self) -> usize {
+        self.inner.max_capacity() * 3 / 4
+    }
+
+    fn record_depth(&self) {
+        let depth = self.depth();
+        histogram!("pathcollab_ws_send_queue_depth").record(depth as f64);
+
+        let above = depth >= self.high_water_mark();
+        if above != self.above_high_water.swap(above, Ordering::Relaxed) {
+            if above {
+                warn!(
+                    "Send queue for connection {} at {}/{} messages; client is not keeping up",
+                    self.connection_id,
+                    depth,
+                    self.inner.max_capacity()
+                );
+            } else {
+                debug!("Send queue for connection {} drained", self.connection_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics::{
+        Counter, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString,
+        Unit,
+    };
+    use std::sync::Mutex;
+
+    /// Histogram capturing every recorded value
+    #[derive(Default)]
+    struct Samples(Mutex<Vec<f64>>);
+
+    impl HistogramFn for Samples {
+        fn record(&self, value: f64) {
+            self.0.lock().unwrap().push(value);
+        }
+    }
+
+    /// Recorder capturing `pathcollab_ws_send_queue_depth` samples
+    #[derive(Default)]
+    struct DepthRecorder {
+        samples: Arc<Samples>,
+    }
+
+    impl Recorder for DepthRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, _: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::noop()
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            if key.name() == "pathcollab_ws_send_queue_depth" {
+                Histogram::from_arc(self.samples.clone())
+            } else {
+                Histogram::noop()
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stalled_receiver_records_growing_depth() {
+        let recorder = DepthRecorder::default();
+        let _guard = metrics::set_default_local_recorder(&recorder);
+
+        // Nothing reads from the queue, as with a stalled client
+        let (tx, mut rx) = connection_channel(Uuid::new_v4(), 8);
+        for _ in 0..6 {
+            tx.send(ServerMessage::Pong {
+                client_ts: None,
+                server_ts: 0,
+            })
+            .await
+            .unwrap();
+        }
+        assert_eq!(tx.depth(), 6);
+        assert_eq!(
+            *recorder.samples.0.lock().unwrap(),
+            vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+        assert!(tx.above_high_water.load(Ordering::Relaxed));
+
+        // Once the client catches up the queue is back under the mark
+        while rx.try_recv().is_ok() {}
+        tx.send(ServerMessage::Pong {
+            client_ts: None,
+            server_ts: 0,
+        })
+        .await
+        .unwrap();
+        assert_eq!(recorder.samples.0.lock().unwrap().last(), Some(&1.0));
+        assert!(!tx.above_high_water.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,77 @@
+//! Batched presence broadcasts
+//!
+//! In immediate mode every cursor update is broadcast as its own
+//! `PresenceDelta`. With a [`PresenceBatcher`] installed, cursor updates are
+//! collected instead, keeping only the latest position per participant, and
+//! [`AppState::flush_presence`](super::AppState::flush_presence) sends one
+//! delta per session on each tick. Broadcasts then scale with the tick rate
+//! rather than the number of moving cursors.
+//!
+//! Presenter viewports are coalesced the same way by a [`ViewportBatcher`]:
+//! only the newest viewport of a tick is stored and broadcast, by
+//! [`AppState::flush_viewports`](super::AppState::flush_viewports), so the
+//! session `rev` moves at most once per tick however fast the presenter pans.
+
+use crate::protocol::{CursorWithParticipant, Viewport};
+use crate::session::state::SessionId;
+use dashmap::DashMap;
+use indexmap::IndexMap;
+use uuid::Uuid;
+
+/// Latest cursor per participant since the last tick, per session
+#[derive(Default)]
+pub struct PresenceBatcher {
+    pending: DashMap<SessionId, IndexMap<Uuid, CursorWithParticipant>>,
+}
+
+impl PresenceBatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a cursor, replacing any earlier position from the same participant
+    pub fn push(&self, session_id: &str, cursor: CursorWithParticipant) {
+        self.pending
+            .entry(session_id.to_string())
+            .or_default()
+            .insert(cursor.participant_id, cursor);
+    }
+
+    /// Take everything queued since the last call
+    pub fn take(&self) -> Vec<(SessionId, Vec<CursorWithParticipant>)> {
+        let session_ids: Vec<SessionId> = self.pending.iter().map(|e| e.key().clone()).collect();
+        session_ids
+            .into_iter()
+            .filter_map(|id| self.pending.remove(&id))
+            .map(|(id, cursors)| (id, cursors.into_values().collect()))
+            .collect()
+    }
+}
+
+/// Latest presenter viewport since the last tick, per session, with its
+/// transition hint in milliseconds
+#[derive(Default)]
+pub struct ViewportBatcher {
+    pending: DashMap<SessionId, (Viewport, u32)>,
+}
+
+impl ViewportBatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a viewport, replacing any earlier one from the same tick
+    pub fn push(&self, session_id: &str, viewport: Viewport, transition_ms: u32) {
+        self.pending
+            .insert(session_id.to_string(), (viewport, transition_ms));
+    }
+
+    /// Take everything queued since the last call
+    pub fn take(&self) -> Vec<(SessionId, (Viewport, u32))> {
+        let session_ids: Vec<SessionId> = self.pending.iter().map(|e| e.key().clone()).collect();
+        session_ids
+            .into_iter()
+            .filter_map(|id| self.pending.remove(&id))
+            .collect()
+    }
+}
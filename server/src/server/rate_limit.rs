@@ -0,0 +1,67 @@
+//! Token bucket rate limiting for high-frequency client messages
+
+use tokio::time::Instant;
+
+/// Token bucket allowing `rate` events per second on average, with bursts of
+/// up to `burst` events
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket; `burst` is raised to at least one event
+    pub fn new(rate: u32, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            rate: f64::from(rate),
+            burst,
+            tokens: burst,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    /// Bucket for a per-second rate, allowing a fifth of a second of burst
+    pub fn per_second(rate: u32) -> Self {
+        Self::new(rate, rate / 5)
+    }
+
+    /// Take a token at `now`, returning false if the bucket is empty
+    pub fn try_take(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.burst);
+        self.refilled_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_flood_capped_to_rate() {
+        let mut bucket = TokenBucket::new(30, 6);
+        let start = Instant::now();
+
+        // 1000 Hz for two seconds: the burst, then 30 per second
+        let allowed = (0..2000)
+            .filter(|ms| bucket.try_take(start + Duration::from_millis(*ms)))
+            .count();
+        assert!((64..=66).contains(&allowed), "allowed {allowed}");
+
+        // Idle time refills only up to the burst size
+        let later = start + Duration::from_secs(60);
+        let allowed = (0..100).filter(|_| bucket.try_take(later)).count();
+        assert_eq!(allowed, 6);
+    }
+}
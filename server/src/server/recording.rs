@@ -0,0 +1,551 @@
+//! Session recording and replay
+//!
+//! With `SESSION_RECORDING_DIR` set, every session gets a transcript at
+//! `{dir}/{session_id}.jsonl`: one JSON object per line holding either an
+//! inbound `ClientMessage` (with the connection it arrived on) or an outbound
+//! session broadcast, stamped with the Unix time in milliseconds. Join secrets
+//! and presenter keys are redacted before they are written. Transcripts are
+//! written by a background task; once a session ends its transcript is closed
+//! and late messages for it are dropped. A session id that is reused, like the
+//! demo session's, is recorded again once [`SessionRecorder::session_started`]
+//! is called for it.
+//!
+//! [`SessionReplayer`] reads a transcript back and re-injects the inbound
+//! messages into an `AppState` (typically with a fresh `SessionManager`),
+//! preserving their relative timing. Recorded connections are replayed as
+//! in-memory connections, and the credentials of the replayed session are
+//! substituted into `JoinSession` / `PresenterAuth`.
+
+use super::outbound::{ConnectionSender, connection_channel};
+use super::rate_limit::TokenBucket;
+use super::websocket::{AppState, Connection, handle_client_message};
+use crate::protocol::{ClientMessage, ServerMessage};
+use crate::session::state::now_millis;
+use dashmap::DashMap;
+use metrics::counter;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::sync::oneshot;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Direction and payload of a recorded message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "direction", rename_all = "snake_case")]
+pub enum RecordedMessage {
+    /// Sent by a client on `connection_id`
+    Inbound {
+        connection_id: Uuid,
+        message: ClientMessage,
+    },
+    /// Broadcast to everyone in the session
+    Outbound { message: Box<ServerMessage> },
+}
+
+/// One line of a session transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordEntry {
+    /// Unix timestamp in milliseconds
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub message: RecordedMessage,
+}
+
+/// Transcript lines queued for the writer task before new ones are dropped
+const RECORDER_QUEUE_CAPACITY: usize = 4096;
+
+/// How long messages for an ended session are dropped as stragglers
+const ENDED_SESSION_TTL: Duration = Duration::from_secs(60);
+
+/// Work for the transcript writer task
+enum WriterOp {
+    /// Append `line` to a session's transcript, closing it afterwards if
+    /// `close` is set
+    Append {
+        session_id: String,
+        line: String,
+        close: bool,
+    },
+    /// Reply once every earlier operation has been written
+    Flush(oneshot::Sender<()>),
+}
+
+/// Appends session transcripts as JSON lines, one file per session
+///
+/// Lines are queued on a bounded channel and written by a single task. When
+/// the writer falls behind and the queue fills up, new lines are dropped and
+/// counted in `pathcollab_session_records_dropped_total`.
+pub struct SessionRecorder {
+    dir: PathBuf,
+    tx: mpsc::Sender<WriterOp>,
+    /// Sessions whose transcript has been closed, and when
+    ended: DashMap<String, Instant>,
+}
+
+impl SessionRecorder {
+    /// Record transcripts into `dir`, creating it if needed. Must be called
+    /// from within a Tokio runtime.
+    pub fn new(dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        let (tx, rx) = mpsc::channel(RECORDER_QUEUE_CAPACITY);
+        tokio::spawn(write_transcripts(dir.clone(), rx));
+        Ok(Self {
+            dir,
+            tx,
+            ended: DashMap::new(),
+        })
+    }
+
+    /// Path of a session's transcript
+    pub fn transcript_path(&self, session_id: &str) -> PathBuf {
+        transcript_path(&self.dir, session_id)
+    }
+
+    /// Record a message received from a client in `session_id`
+    pub fn record_inbound(&self, session_id: &str, connection_id: Uuid, message: &ClientMessage) {
+        self.append(
+            session_id,
+            RecordedMessage::Inbound {
+                connection_id,
+                message: redacted(message),
+            },
+            false,
+        );
+    }
+
+    /// Record a broadcast to `session_id`. The transcript is closed once the
+    /// session ends.
+    pub fn record_outbound(&self, session_id: &str, message: &ServerMessage) {
+        self.append(
+            session_id,
+            RecordedMessage::Outbound {
+                message: Box::new(message.clone()),
+            },
+            matches!(message, ServerMessage::SessionEnded { .. }),
+        );
+    }
+
+    /// Record `session_id` again, even if a session with the same id ended
+    /// recently
+    pub fn session_started(&self, session_id: &str) {
+        self.ended.remove(session_id);
+    }
+
+    /// Wait until everything recorded so far has been written
+    pub async fn flush(&self) {
+        let (done_tx, done_rx) = oneshot::channel();
+        if self.tx.send(WriterOp::Flush(done_tx)).await.is_ok() {
+            let _ = done_rx.await;
+        }
+    }
+
+    fn append(&self, session_id: &str, message: RecordedMessage, close: bool) {
+        if self.ended.contains_key(session_id) {
+            return;
+        }
+        let entry = RecordEntry {
+            at_ms: now_millis(),
+            message,
+        };
+        let mut line = match serde_json::to_string(&entry) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Failed to serialize session record: {}", e);
+                return;
+            }
+        };
+        line.push('\n');
+
+        let op = WriterOp::Append {
+            session_id: session_id.to_string(),
+            line,
+            close,
+        };
+        if close {
+            self.ended
+                .retain(|_, ended_at| ended_at.elapsed() < ENDED_SESSION_TTL);
+            self.ended.insert(session_id.to_string(), Instant::now());
+        }
+        match self.tx.try_send(op) {
+            Ok(()) => {}
+            Err(TrySendError::Full(op)) if close => {
+                // Never drop the closing line, or the writer would keep the file open
+                let tx = self.tx.clone();
+                tokio::spawn(async move {
+                    let _ = tx.send(op).await;
+                });
+            }
+            Err(TrySendError::Full(_)) => {
+                counter!("pathcollab_session_records_dropped_total").increment(1);
+                warn!(
+                    "Recording queue is full, dropping message for session {}",
+                    session_id
+                );
+            }
+            Err(TrySendError::Closed(_)) => {
+                counter!("pathcollab_session_record_errors_total").increment(1);
+                warn!("Recording writer has stopped, dropping message");
+            }
+        }
+    }
+}
+
+fn transcript_path(dir: &Path, session_id: &str) -> PathBuf {
+    dir.join(format!("{session_id}.jsonl"))
+}
+
+/// Write queued transcript lines until every sender is gone
+async fn write_transcripts(dir: PathBuf, mut rx: mpsc::Receiver<WriterOp>) {
+    let mut files: HashMap<String, tokio::fs::File> = HashMap::new();
+    while let Some(op) = rx.recv().await {
+        match op {
+            WriterOp::Append {
+                session_id,
+                line,
+                close,
+            } => {
+                match write_line(&dir, &mut files, &session_id, &line).await {
+                    Ok(()) => counter!("pathcollab_session_records_total").increment(1),
+                    Err(e) => {
+                        counter!("pathcollab_session_record_errors_total").increment(1);
+                        warn!("Failed to record message for session {}: {}", session_id, e);
+                    }
+                }
+                if close {
+                    files.remove(&session_id);
+                }
+            }
+            WriterOp::Flush(done) => {
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+async fn write_line(
+    dir: &Path,
+    files: &mut HashMap<String, tokio::fs::File>,
+    session_id: &str,
+    line: &str,
+) -> std::io::Result<()> {
+    let file = match files.entry(session_id.to_string()) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => {
+            let file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(transcript_path(dir, session_id))
+                .await?;
+            entry.insert(file)
+        }
+    };
+    file.write_all(line.as_bytes()).await?;
+    file.flush().await
+}
+
+/// Copy of `message` with session credentials blanked out
+fn redacted(message: &ClientMessage) -> ClientMessage {
+    let mut message = message.clone();
+    match &mut message {
+        ClientMessage::JoinSession { join_secret, .. } => join_secret.clear(),
+        ClientMessage::PresenterAuth { presenter_key, .. } => presenter_key.clear(),
+        _ => {}
+    }
+    message
+}
+
+/// Replays a recorded session transcript
+pub struct SessionReplayer {
+    entries: Vec<RecordEntry>,
+    speed: f64,
+}
+
+impl SessionReplayer {
+    /// Read a transcript written by [`SessionRecorder`]
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            entries.push(entry);
+        }
+        Ok(Self {
+            entries,
+            speed: 1.0,
+        })
+    }
+
+    /// Play back `speed` times faster than recorded (`f64::INFINITY` for no delays)
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn entries(&self) -> &[RecordEntry] {
+        &self.entries
+    }
+
+    /// Re-inject the recorded client messages into `state`. Returns the id of
+    /// the replayed session, if one was created.
+    pub async fn replay(&self, state: &AppState) -> Option<String> {
+        let mut connections: HashMap<Uuid, ReplayConnection> = HashMap::new();
+        let mut credentials: Option<(String, String, String)> = None;
+        let mut previous_at: Option<u64> = None;
+
+        for entry in &self.entries {
+            let RecordedMessage::Inbound {
+                connection_id,
+                message,
+            } = &entry.message
+            else {
+                continue;
+            };
+
+            if let Some(previous) = previous_at {
+                let delay = self.delay(entry.at_ms.saturating_sub(previous));
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            previous_at = Some(entry.at_ms);
+
+            let message = match (message.clone(), &credentials) {
+                (
+                    ClientMessage::JoinSession {
+                        last_seen_rev, seq, ..
+                    },
+                    Some((id, secret, _)),
+                ) => ClientMessage::JoinSession {
+                    session_id: id.clone(),
+                    join_secret: secret.clone(),
+                    last_seen_rev,
+                    reconnect_token: None,
+                    seq,
+                },
+                (ClientMessage::PresenterAuth { seq, .. }, Some((_, _, key))) => {
+                    ClientMessage::PresenterAuth {
+                        presenter_key: key.clone(),
+                        seq,
+                    }
+                }
+                (message, _) => message,
+            };
+
+            let connection = connections
+                .entry(*connection_id)
+                .or_insert_with(|| ReplayConnection::register(state));
+            handle_client_message(message, connection.id, state, &connection.tx).await;
+
+            while let Ok(reply) = connection.rx.try_recv() {
+                if let ServerMessage::SessionCreated {
+                    session,
+                    join_secret,
+                    presenter_key,
+                    ..
+                } = reply
+                {
+                    credentials = Some((session.id, join_secret, presenter_key));
+                }
+            }
+        }
+
+        for connection in connections.values() {
+            state.connections.remove(&connection.id);
+        }
+        credentials.map(|(id, _, _)| id)
+    }
+
+    fn delay(&self, recorded_ms: u64) -> Duration {
+        let secs = recorded_ms as f64 / 1000.0 / self.speed;
+        if secs.is_finite() && secs > 0.0 {
+            Duration::from_secs_f64(secs)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+/// In-memory stand-in for a recorded client's socket
+struct ReplayConnection {
+    id: Uuid,
+    tx: ConnectionSender,
+    rx: mpsc::Receiver<ServerMessage>,
+}
+
+impl ReplayConnection {
+    fn register(state: &AppState) -> Self {
+        let id = Uuid::new_v4();
+        let (tx, rx) = connection_channel(id, 256);
+        state.connections.insert(
+            id,
+            Connection {
+                id,
+                session_id: None,
+                participant_id: None,
+                is_presenter: false,
+                last_ping: tokio::time::Instant::now(),
+                sender: tx.clone(),
+                name: None,
+                color: None,
+                protocol_supported: true,
+                cursor_bucket: TokenBucket::per_second(state.ws_config.max_cursor_hz),
+            },
+        );
+        Self { id, tx, rx }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{CoordinateSpace, SessionEndReason};
+    use crate::server::harness::{FakeConnection, fixture_state};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_record_and_replay_session() {
+        let dir = std::env::temp_dir().join(format!("pathcollab-rec-{}", Uuid::new_v4()));
+        let recorder = Arc::new(SessionRecorder::new(&dir).unwrap());
+        let state = fixture_state().with_session_recorder(recorder.clone());
+
+        let (mut presenter, join_secret) = FakeConnection::create_session(&state).await;
+        let session_id = presenter.session_id().unwrap();
+        let mut follower = FakeConnection::join(&state, &session_id, &join_secret).await;
+        follower
+            .send(ClientMessage::CursorUpdate {
+                x: 120.0,
+                y: 80.0,
+                space: CoordinateSpace::SlidePixels,
+                seq: 2,
+            })
+            .await;
+        presenter
+            .send(ClientMessage::ViewportUpdate {
+                center_x: 0.3,
+                center_y: 0.6,
+                zoom: 4.0,
+                transition_ms: None,
+                seq: 2,
+            })
+            .await;
+        let original = state
+            .session_manager
+            .get_session(&session_id)
+            .await
+            .unwrap();
+
+        // Credentials never reach the transcript
+        recorder.flush().await;
+        let path = recorder.transcript_path(&session_id);
+        let transcript = std::fs::read_to_string(&path).unwrap();
+        assert!(!transcript.contains(&join_secret));
+
+        let replayer = SessionReplayer::open(&path)
+            .unwrap()
+            .with_speed(f64::INFINITY);
+        let inbound = replayer
+            .entries()
+            .iter()
+            .filter(|e| matches!(e.message, RecordedMessage::Inbound { .. }))
+            .count();
+        assert_eq!(inbound, 4);
+
+        let replay_state = fixture_state();
+        let replayed_id = replayer.replay(&replay_state).await.unwrap();
+        let replayed = replay_state
+            .session_manager
+            .get_session(&replayed_id)
+            .await
+            .unwrap();
+
+        assert_ne!(replayed.id, original.id);
+        assert_eq!(replayed.rev, original.rev);
+        assert_eq!(replayed.slide.id, original.slide.id);
+        assert_eq!(replayed.followers.len(), 1);
+        assert_eq!(
+            (
+                replayed.presenter_viewport.center_x,
+                replayed.presenter_viewport.center_y,
+                replayed.presenter_viewport.zoom
+            ),
+            (
+                original.presenter_viewport.center_x,
+                original.presenter_viewport.center_y,
+                original.presenter_viewport.zoom
+            )
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_ended_session_transcript_is_not_reopened() {
+        let dir = std::env::temp_dir().join(format!("pathcollab-rec-{}", Uuid::new_v4()));
+        let recorder = SessionRecorder::new(&dir).unwrap();
+        let ended = ServerMessage::SessionEnded {
+            reason: SessionEndReason::PresenterLeft,
+        };
+
+        recorder.record_outbound("s1", &ended);
+        recorder.flush().await;
+        let path = recorder.transcript_path("s1");
+        std::fs::remove_file(&path).unwrap();
+
+        // A straggler arriving after the session ended is dropped
+        recorder.record_inbound(
+            "s1",
+            Uuid::new_v4(),
+            &ClientMessage::Ping {
+                seq: 1,
+                client_ts: None,
+            },
+        );
+        recorder.record_outbound("s1", &ended);
+        recorder.flush().await;
+        assert!(!path.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_reused_session_id_is_recorded_again() {
+        let dir = std::env::temp_dir().join(format!("pathcollab-rec-{}", Uuid::new_v4()));
+        let recorder = SessionRecorder::new(&dir).unwrap();
+        let ping = ClientMessage::Ping {
+            seq: 1,
+            client_ts: None,
+        };
+
+        recorder.record_inbound("demo", Uuid::new_v4(), &ping);
+        recorder.record_outbound(
+            "demo",
+            &ServerMessage::SessionEnded {
+                reason: SessionEndReason::Expired,
+            },
+        );
+        recorder.flush().await;
+        let path = recorder.transcript_path("demo");
+        std::fs::remove_file(&path).unwrap();
+
+        // The id comes back for a new session
+        recorder.session_started("demo");
+        recorder.record_inbound("demo", Uuid::new_v4(), &ping);
+        recorder.flush().await;
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,122 @@
+//! Recent-event replay for late joiners
+//!
+//! A follower joining mid-session gets a snapshot of the current state, but
+//! not the transient events that led up to it. [`EventReplay`] keeps the last
+//! few broadcasts of selected types per session (chat by default) and the join
+//! handler sends them right after `SessionJoined`, so late joiners see what was
+//! just said.
+
+use crate::protocol::ServerMessage;
+use crate::session::state::SessionId;
+use dashmap::DashMap;
+use std::collections::VecDeque;
+
+/// Bounded buffer of recent broadcasts, per session
+pub struct EventReplay {
+    /// Events kept per session
+    capacity: usize,
+    /// Message types worth replaying (see `REPLAYABLE_MESSAGE_TYPES`)
+    types: Vec<String>,
+    recent: DashMap<SessionId, VecDeque<ServerMessage>>,
+}
+
+impl EventReplay {
+    pub fn new(capacity: usize, types: Vec<String>) -> Self {
+        Self {
+            capacity,
+            types,
+            recent: DashMap::new(),
+        }
+    }
+
+    /// Keep `msg` if it is of a replayed type, dropping the oldest event once
+    /// the session's buffer is full
+    pub fn record(&self, session_id: &str, msg: &ServerMessage) {
+        if self.capacity == 0 || !self.types.iter().any(|t| t == msg.message_type()) {
+            return;
+        }
+        let mut recent = self.recent.entry(session_id.to_string()).or_default();
+        if recent.len() == self.capacity {
+            recent.pop_front();
+        }
+        recent.push_back(msg.clone());
+    }
+
+    /// Buffered events of a session, oldest first
+    pub fn recent(&self, session_id: &str) -> Vec<ServerMessage> {
+        self.recent
+            .get(session_id)
+            .map(|recent| recent.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forget an ended session
+    pub fn remove(&self, session_id: &str) {
+        self.recent.remove(session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ClientMessage;
+    use crate::server::harness::{FakeConnection, fixture_state};
+    use std::sync::Arc;
+
+    fn chat_texts(messages: &[ServerMessage]) -> Vec<&str> {
+        messages
+            .iter()
+            .filter_map(|m| match m {
+                ServerMessage::ChatMessage { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_late_joiner_receives_recent_chat() {
+        let replay = EventReplay::new(2, vec!["chat_message".to_string()]);
+        let state = fixture_state().with_event_replay(Arc::new(replay));
+        let (mut presenter, join_secret) = FakeConnection::create_session(&state).await;
+        let session_id = presenter.session_id().unwrap();
+
+        for (seq, text) in ["first", "second", "third"].into_iter().enumerate() {
+            presenter
+                .send(ClientMessage::ChatMessage {
+                    text: text.to_string(),
+                    seq: seq as u64 + 2,
+                })
+                .await;
+        }
+
+        let mut follower = FakeConnection::join(&state, &session_id, &join_secret).await;
+        let messages = follower.drain();
+        assert!(matches!(
+            messages.first(),
+            Some(ServerMessage::SessionJoined { .. })
+        ));
+        // Only the newest two fit in the buffer
+        assert_eq!(chat_texts(&messages), vec!["second", "third"]);
+    }
+
+    #[test]
+    fn test_only_configured_types_are_kept() {
+        let replay = EventReplay::new(8, vec!["chat_message".to_string()]);
+        replay.record("s1", &ServerMessage::Ping);
+        replay.record(
+            "s1",
+            &ServerMessage::ChatMessage {
+                participant_id: uuid::Uuid::new_v4(),
+                name: "Presenter".to_string(),
+                text: "hello".to_string(),
+                sent_at: 0,
+            },
+        );
+
+        assert_eq!(chat_texts(&replay.recent("s1")), vec!["hello"]);
+        assert_eq!(replay.recent("s1").len(), 1);
+
+        replay.remove("s1");
+        assert!(replay.recent("s1").is_empty());
+    }
+}
@@ -0,0 +1,79 @@
+//! Session HTTP endpoints
+//!
+//! Lets session members inspect a session without opening a WebSocket.
+//! Requests must carry the session's join secret (`X-Join-Secret`) or
+//! presenter key (`X-Presenter-Key`). The optional features enabled for
+//! sessions on this server (`SESSION_FEATURES`) are public.
+
+use super::AppState;
+use crate::protocol::Participant;
+use crate::session::manager::SessionError;
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::{Deserialize, Serialize};
+
+/// Header carrying the session join secret
+pub const JOIN_SECRET_HEADER: &str = "x-join-secret";
+/// Header carrying the session presenter key
+pub const PRESENTER_KEY_HEADER: &str = "x-presenter-key";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeaturesResponse {
+    /// Optional features enabled for new sessions
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParticipantListResponse {
+    pub session_id: String,
+    /// Presenter first, then followers
+    pub participants: Vec<Participant>,
+}
+
+/// Session routes, to be nested under `/api`
+pub fn session_routes() -> Router<AppState> {
+    Router::new()
+        .route("/features", get(list_features))
+        .route("/session/:id/participants", get(list_participants))
+}
+
+/// GET /api/features - Optional features enabled on this server
+async fn list_features(State(state): State<AppState>) -> Json<FeaturesResponse> {
+    Json(FeaturesResponse {
+        features: state.session_manager.features().to_vec(),
+    })
+}
+
+/// GET /api/session/:id/participants - Public participant list for session members
+async fn list_participants(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    match state
+        .session_manager
+        .list_participants(
+            &id,
+            header(JOIN_SECRET_HEADER),
+            header(PRESENTER_KEY_HEADER),
+        )
+        .await
+    {
+        Ok(participants) => Json(ParticipantListResponse {
+            session_id: id,
+            participants,
+        })
+        .into_response(),
+        Err(SessionError::NotFound(_)) => {
+            (StatusCode::NOT_FOUND, "session not found").into_response()
+        }
+        Err(_) => (StatusCode::UNAUTHORIZED, "invalid session credentials").into_response(),
+    }
+}
@@ -1,24 +1,44 @@
+use crate::audit::{AuditAction, AuditRecord, AuditSink};
+use crate::fovea::FoveaAppState;
 use crate::protocol::{
-    CellOverlayState, ClientMessage, CursorWithParticipant, ServerMessage, SlideInfo,
-    TissueOverlayState, Viewport,
+    CellOverlayState, ClientMessage, CoordinateSpace, CursorWithParticipant,
+    DEFAULT_VIEWPORT_TRANSITION_MS, PROTOCOL_VERSION, QosProfileData, ServerMessage,
+    SessionEndReason, SlideInfo, TissueOverlayState, Viewport, is_protocol_compatible,
 };
-use crate::session::manager::{SessionError, SessionManager};
-use crate::slide::SlideService;
+use crate::request_id::RequestId;
+use crate::server::client_ip::{IpCidr, client_ip};
+use crate::server::outbound::{ConnectionSender, SEND_QUEUE_CAPACITY, connection_channel};
+use crate::server::presence::{PresenceBatcher, ViewportBatcher};
+use crate::server::rate_limit::TokenBucket;
+use crate::server::recording::SessionRecorder;
+use crate::server::replay::EventReplay;
+use crate::session::links::SessionLinks;
+use crate::session::manager::{JoinSnapshot, SessionError, SessionManager};
+use crate::session::state::{DEMO_SESSION_ID, SessionId, now_millis};
+use crate::slide::{SlideListItem, SlideService};
+use crate::webhook::{WebhookEvent, WebhookEventKind, WebhookNotifier};
 use axum::{
+    Extension,
     extract::{
-        State,
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, State,
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
     },
-    response::Response,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
 };
 use dashmap::DashMap;
-use metrics::{counter, histogram};
+use metrics::{counter, gauge, histogram};
+use rand::Rng;
 use std::{
-    sync::Arc,
+    net::{IpAddr, SocketAddr},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    },
     time::{Duration, Instant},
 };
-use tokio::sync::{broadcast, mpsc};
-use tracing::{debug, error, info, warn};
+use tokio::{sync::broadcast, task::AbortHandle};
+use tracing::{Instrument, Span, debug, error, field, info, info_span, warn};
 use uuid::Uuid;
 
 /// Connection state for a single client
@@ -27,12 +47,18 @@ pub struct Connection {
     pub session_id: Option<String>,
     pub participant_id: Option<Uuid>,
     pub is_presenter: bool,
-    pub last_ping: Instant,
-    pub sender: mpsc::Sender<ServerMessage>,
+    pub last_ping: tokio::time::Instant,
+    pub sender: ConnectionSender,
     /// Cached participant name (avoids session lookups on every cursor update)
     pub name: Option<String>,
     /// Cached participant color (avoids session lookups on every cursor update)
     pub color: Option<String>,
+    /// False after a `Hello` with an incompatible protocol version. The
+    /// socket is closed right after, so this only matters for messages that
+    /// were already read (or for in-memory connections that never close).
+    pub protocol_supported: bool,
+    /// Limits how often this connection's cursor updates are broadcast
+    pub cursor_bucket: TokenBucket,
 }
 
 /// Global connection registry
@@ -52,6 +78,49 @@ pub struct AppState {
     pub slide_service: Option<Arc<dyn SlideService>>,
     /// Public base URL for link generation (e.g., "https://pathcollab.example.com")
     pub public_base_url: Option<String>,
+    /// Sink for presenter action audit records (disabled if None)
+    pub audit_sink: Option<Arc<dyn AuditSink>>,
+    /// Bearer token for `/api/admin/*` (admin API disabled if None)
+    pub admin_token: Option<String>,
+    /// When set, new sessions and joins are rejected while existing sessions continue
+    pub draining: Arc<AtomicBool>,
+    /// Connections upgraded or being upgraded, counted against `max_connections`
+    pub open_connections: Arc<AtomicUsize>,
+    /// WebSocket keepalive settings applied to every connection
+    pub ws_config: WsConfig,
+    /// Session transcript recorder (recording disabled if None)
+    pub recorder: Option<Arc<SessionRecorder>>,
+    /// How often the session cleanup task is expected to run
+    pub cleanup_interval: Duration,
+    /// Upper bound of the random delay added to each cleanup pass
+    pub cleanup_jitter: Duration,
+    /// Unix timestamp in milliseconds of the last completed cleanup pass
+    pub last_cleanup_at: Arc<AtomicU64>,
+    /// Collects cursor updates for per-tick broadcast (immediate broadcast if None)
+    pub presence_batcher: Option<Arc<PresenceBatcher>>,
+    /// Coalesces presenter viewports for per-tick broadcast (immediate broadcast if None)
+    pub viewport_batcher: Option<Arc<ViewportBatcher>>,
+    /// Proxies allowed to report the client address in forwarding headers
+    pub trusted_proxies: Arc<Vec<IpCidr>>,
+    /// Fovea forwarder, whose prepared sources are evicted when a slide is deleted
+    pub fovea: Option<FoveaAppState>,
+    /// Session lifecycle webhook (disabled if None)
+    pub webhooks: Option<Arc<WebhookNotifier>>,
+    /// Recent events replayed to late joiners (disabled if None)
+    pub event_replay: Option<Arc<EventReplay>>,
+    /// Notice followers get before a slide change is applied (immediate if zero)
+    pub slide_change_delay: Duration,
+    /// Announced slide changes that have not been applied yet
+    pending_slide_changes: Arc<DashMap<SessionId, PendingSlideChange>>,
+    /// Connection of each session member, for messages sent to one participant
+    participant_connections: Arc<DashMap<(SessionId, Uuid), Uuid>>,
+}
+
+/// A slide change waiting out `slide_change_delay`
+struct PendingSlideChange {
+    /// Distinguishes this change from one that replaced it
+    token: Uuid,
+    task: AbortHandle,
 }
 
 impl AppState {
@@ -64,6 +133,24 @@ impl AppState {
             session_broadcasters: Arc::new(DashMap::new()),
             slide_service: None,
             public_base_url: None,
+            audit_sink: None,
+            admin_token: None,
+            draining: Arc::new(AtomicBool::new(false)),
+            open_connections: Arc::new(AtomicUsize::new(0)),
+            ws_config: WsConfig::default(),
+            recorder: None,
+            cleanup_interval: Duration::from_secs(60),
+            cleanup_jitter: Duration::ZERO,
+            last_cleanup_at: Arc::new(AtomicU64::new(now_millis())),
+            presence_batcher: None,
+            viewport_batcher: None,
+            trusted_proxies: Arc::new(Vec::new()),
+            fovea: None,
+            webhooks: None,
+            event_replay: None,
+            slide_change_delay: Duration::ZERO,
+            pending_slide_changes: Arc::new(DashMap::new()),
+            participant_connections: Arc::new(DashMap::new()),
         }
     }
 
@@ -82,6 +169,142 @@ impl AppState {
         self
     }
 
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    pub fn with_admin_token(mut self, token: Option<String>) -> Self {
+        self.admin_token = token;
+        self
+    }
+
+    pub fn with_ws_config(mut self, ws_config: WsConfig) -> Self {
+        self.ws_config = ws_config;
+        self
+    }
+
+    pub fn with_session_recorder(mut self, recorder: Arc<SessionRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub fn with_presence_batcher(mut self, batcher: Arc<PresenceBatcher>) -> Self {
+        self.presence_batcher = Some(batcher);
+        self
+    }
+
+    pub fn with_viewport_batcher(mut self, batcher: Arc<ViewportBatcher>) -> Self {
+        self.viewport_batcher = Some(batcher);
+        self
+    }
+
+    pub fn with_trusted_proxies(mut self, trusted_proxies: Vec<IpCidr>) -> Self {
+        self.trusted_proxies = Arc::new(trusted_proxies);
+        self
+    }
+
+    pub fn with_fovea(mut self, fovea: FoveaAppState) -> Self {
+        self.fovea = Some(fovea);
+        self
+    }
+
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookNotifier>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    pub fn with_event_replay(mut self, replay: Arc<EventReplay>) -> Self {
+        self.event_replay = Some(replay);
+        self
+    }
+
+    pub fn with_slide_change_delay(mut self, delay: Duration) -> Self {
+        self.slide_change_delay = delay;
+        self
+    }
+
+    pub fn with_cleanup_interval(mut self, interval: Duration) -> Self {
+        self.cleanup_interval = interval;
+        self
+    }
+
+    pub fn with_cleanup_jitter(mut self, jitter: Duration) -> Self {
+        self.cleanup_jitter = jitter;
+        self
+    }
+
+    /// Heartbeat from the cleanup task after each pass
+    pub fn record_cleanup(&self) {
+        self.last_cleanup_at.store(now_millis(), Ordering::Relaxed);
+    }
+
+    /// True when cleanup hasn't run for more than twice its longest delay,
+    /// i.e. the task has died or is stuck and sessions are no longer expiring
+    pub fn cleanup_stalled(&self) -> bool {
+        let elapsed = now_millis().saturating_sub(self.last_cleanup_at.load(Ordering::Relaxed));
+        u128::from(elapsed) > (self.cleanup_interval + self.cleanup_jitter).as_millis() * 2
+    }
+
+    /// Cleanup task: right away, then every `cleanup_interval` plus up to
+    /// `cleanup_jitter`, remove expired sessions and, in demo mode,
+    /// (re-)create the demo session from `(slide_id, join_secret)`
+    pub async fn run_cleanup(self, demo: Option<(String, String)>) {
+        loop {
+            self.cleanup_expired().await;
+            if let Some((ref slide_id, ref join_secret)) = demo {
+                self.ensure_demo_session(slide_id, join_secret).await;
+            }
+            self.record_cleanup();
+
+            let jitter_ms = rand::rng().random_range(0..=self.cleanup_jitter.as_millis() as u64);
+            tokio::time::sleep(self.cleanup_interval + Duration::from_millis(jitter_ms)).await;
+        }
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Relaxed)
+    }
+
+    pub fn set_draining(&self, draining: bool) {
+        self.draining.store(draining, Ordering::Relaxed);
+    }
+
+    /// Take one of the `max_connections` slots, or None if all are in use
+    fn reserve_connection(&self) -> Option<ConnectionSlot> {
+        let max = self.ws_config.max_connections;
+        self.open_connections
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |open| {
+                (open < max).then_some(open + 1)
+            })
+            .ok()
+            .map(|_| ConnectionSlot(self.open_connections.clone()))
+    }
+
+    /// Record a presenter action performed over `connection_id`, if auditing is enabled
+    pub fn audit(&self, connection_id: Uuid, session_id: &str, action: AuditAction) {
+        let actor_id = self
+            .connections
+            .get(&connection_id)
+            .and_then(|c| c.participant_id);
+        self.audit_as(actor_id, session_id, action);
+    }
+
+    /// Record a presenter action by participant, for actions that complete
+    /// after the connection may have gone
+    fn audit_as(&self, actor_id: Option<Uuid>, session_id: &str, action: AuditAction) {
+        if let Some(ref sink) = self.audit_sink {
+            sink.record(&AuditRecord::new(session_id, actor_id, action));
+        }
+    }
+
+    /// Send a session lifecycle event to the webhook, if one is configured
+    pub fn notify_webhook(&self, session_id: &str, kind: WebhookEventKind) {
+        if let Some(ref webhooks) = self.webhooks {
+            webhooks.notify(WebhookEvent::new(session_id, kind));
+        }
+    }
+
     /// Get or create a broadcast channel for a session
     pub async fn get_session_broadcaster(
         &self,
@@ -94,16 +317,137 @@ impl AppState {
             let (tx, _) = broadcast::channel(256);
             self.session_broadcasters
                 .insert(session_id.to_string(), tx.clone());
+            self.record_broadcaster_count();
             tx
         }
     }
 
+    fn record_broadcaster_count(&self) {
+        gauge!("pathcollab_session_broadcasters").set(self.session_broadcasters.len() as f64);
+    }
+
+    /// Remove expired sessions and tell their remaining participants, and
+    /// warn participants of sessions about to expire
+    pub async fn cleanup_expired(&self) {
+        for (session_id, reason) in self.session_manager.cleanup_expired().await {
+            self.close_session(&session_id, reason).await;
+        }
+        for (session_id, in_secs) in self.session_manager.take_expiry_warnings() {
+            self.broadcast_to_session(&session_id, ServerMessage::SessionExpiring { in_secs })
+                .await;
+        }
+    }
+
+    /// End a session now and tell its participants why
+    ///
+    /// `SessionEnded` goes straight to each member connection rather than
+    /// through the session broadcaster, because the connections are detached
+    /// from the session at the same time and would stop listening to it.
+    pub async fn end_session(
+        &self,
+        session_id: &str,
+        reason: SessionEndReason,
+    ) -> Result<(), SessionError> {
+        self.session_manager.end_session(session_id)?;
+        self.close_session(session_id, reason).await;
+        Ok(())
+    }
+
+    /// Send `SessionEnded` to the members of a removed session, detach their
+    /// connections and drop the session's broadcaster
+    async fn close_session(&self, session_id: &str, reason: SessionEndReason) {
+        let msg = ServerMessage::SessionEnded { reason };
+        if let Some(ref recorder) = self.recorder {
+            recorder.record_outbound(session_id, &msg);
+        }
+        let members: Vec<(Option<Uuid>, ConnectionSender)> = self
+            .connections
+            .iter_mut()
+            .filter(|conn| conn.session_id.as_deref() == Some(session_id))
+            .map(|mut conn| {
+                conn.session_id = None;
+                conn.is_presenter = false;
+                (conn.participant_id.take(), conn.sender.clone())
+            })
+            .collect();
+        for (participant_id, sender) in members {
+            if let Some(participant_id) = participant_id {
+                self.participant_connections
+                    .remove(&(session_id.to_string(), participant_id));
+            }
+            let _ = sender.send(msg.clone()).await;
+        }
+        self.session_broadcasters.remove(session_id);
+        self.record_broadcaster_count();
+        if let Some((_, pending)) = self.pending_slide_changes.remove(session_id) {
+            pending.task.abort();
+        }
+        if let Some(ref replay) = self.event_replay {
+            replay.remove(session_id);
+        }
+        self.notify_webhook(session_id, WebhookEventKind::SessionEnded { reason });
+    }
+
+    /// Remove the connection's participant from its session, tell the rest
+    /// of the session and detach the connection. A presenter leaving starts
+    /// the grace period, whether it left explicitly or its socket closed.
+    /// Returns the session that was left, if the connection was in one.
+    pub async fn leave_session(&self, connection_id: Uuid) -> Option<SessionId> {
+        let (session_id, participant_id) = {
+            let mut conn = self.connections.get_mut(&connection_id)?;
+            let left = conn.session_id.take().zip(conn.participant_id.take());
+            conn.is_presenter = false;
+            left?
+        };
+        self.participant_connections
+            .remove_if(&(session_id.clone(), participant_id), |_, conn| {
+                *conn == connection_id
+            });
+
+        match self
+            .session_manager
+            .remove_participant(&session_id, participant_id)
+            .await
+        {
+            Ok(was_presenter) => {
+                // Broadcast participant left
+                self.broadcast_to_session(
+                    &session_id,
+                    ServerMessage::ParticipantLeft { participant_id },
+                )
+                .await;
+                self.notify_webhook(
+                    &session_id,
+                    WebhookEventKind::ParticipantLeft { participant_id },
+                );
+
+                if was_presenter {
+                    info!(
+                        "Presenter {} left session {}, grace period started",
+                        participant_id, session_id
+                    );
+                }
+            }
+            Err(e) => {
+                debug!("Failed to remove participant from session: {}", e);
+            }
+        }
+        Some(session_id)
+    }
+
     /// Broadcast a message to all participants in a session
     pub async fn broadcast_to_session(&self, session_id: &str, msg: ServerMessage) {
         let start = Instant::now();
+        if let Some(ref recorder) = self.recorder {
+            recorder.record_outbound(session_id, &msg);
+        }
+        if let Some(ref replay) = self.event_replay {
+            replay.record(session_id, &msg);
+        }
         if let Some(sender) = self.session_broadcasters.get(session_id) {
             let msg_type = msg.message_type();
             let receiver_count = sender.receiver_count();
+            let bytes = serde_json::to_vec(&msg).map_or(0, |json| json.len());
 
             // Ignore send errors (no receivers)
             let result = sender.send(msg);
@@ -112,7 +456,9 @@ impl AppState {
             histogram!("pathcollab_ws_broadcast_duration_seconds", "type" => msg_type)
                 .record(start.elapsed());
             counter!("pathcollab_ws_broadcasts_total", "type" => msg_type).increment(1);
-            histogram!("pathcollab_ws_broadcast_recipients").record(receiver_count as f64);
+            histogram!("pathcollab_ws_broadcast_recipients", "type" => msg_type)
+                .record(receiver_count as f64);
+            histogram!("pathcollab_ws_broadcast_bytes", "type" => msg_type).record(bytes as f64);
 
             if result.is_err() {
                 counter!("pathcollab_ws_broadcast_errors_total", "type" => msg_type).increment(1);
@@ -120,6 +466,221 @@ impl AppState {
         }
     }
 
+    /// Send a message to one participant of a session, straight to its
+    /// connection rather than through the session broadcaster. Returns
+    /// whether it was delivered to the connection's queue: false if the
+    /// participant has no connection in the session or it has closed.
+    pub async fn send_to_participant(
+        &self,
+        session_id: &str,
+        participant_id: Uuid,
+        msg: ServerMessage,
+    ) -> bool {
+        let sender = self
+            .participant_connections
+            .get(&(session_id.to_string(), participant_id))
+            .and_then(|connection_id| self.connections.get(&*connection_id))
+            .filter(|conn| {
+                conn.session_id.as_deref() == Some(session_id)
+                    && conn.participant_id == Some(participant_id)
+            })
+            .map(|conn| conn.sender.clone());
+        let Some(sender) = sender else {
+            return false;
+        };
+        counter!("pathcollab_ws_directed_messages_total", "type" => msg.message_type())
+            .increment(1);
+        sender.send(msg).await.is_ok()
+    }
+
+    /// Tell sessions about cursors that went idle for longer than `stale_after`
+    pub async fn broadcast_stale_cursors(&self, stale_after: Duration) {
+        for (session_id, removed) in self.session_manager.expire_idle_cursors(stale_after) {
+            counter!("pathcollab_presence_cursors_stale_total").increment(removed.len() as u64);
+            self.broadcast_to_session(
+                &session_id,
+                ServerMessage::PresenceDelta {
+                    changed: vec![],
+                    removed,
+                    server_ts: crate::session::state::now_millis(),
+                },
+            )
+            .await;
+        }
+    }
+
+    /// Broadcast the cursors batched since the last tick, one delta per session
+    pub async fn flush_presence(&self) {
+        let Some(ref batcher) = self.presence_batcher else {
+            return;
+        };
+        for (session_id, changed) in batcher.take() {
+            histogram!("pathcollab_presence_batch_size").record(changed.len() as f64);
+            self.broadcast_to_session(
+                &session_id,
+                ServerMessage::PresenceDelta {
+                    changed,
+                    removed: vec![],
+                    server_ts: crate::session::state::now_millis(),
+                },
+            )
+            .await;
+        }
+    }
+
+    /// Store and broadcast the newest presenter viewport of each session
+    /// queued since the last tick
+    pub async fn flush_viewports(&self) {
+        let Some(ref batcher) = self.viewport_batcher else {
+            return;
+        };
+        for (session_id, (viewport, transition_ms)) in batcher.take() {
+            self.apply_presenter_viewport(&session_id, viewport, transition_ms)
+                .await;
+        }
+    }
+
+    /// Make `viewport` the session's presenter viewport and tell followers,
+    /// who animate towards it over `transition_ms`
+    async fn apply_presenter_viewport(
+        &self,
+        session_id: &str,
+        viewport: Viewport,
+        transition_ms: u32,
+    ) {
+        if let Err(e) = self
+            .session_manager
+            .update_presenter_viewport(session_id, viewport.clone())
+            .await
+        {
+            debug!("Failed to update presenter viewport: {}", e);
+            return;
+        }
+        self.broadcast_to_session(
+            session_id,
+            ServerMessage::PresenterViewport {
+                viewport,
+                transition_ms,
+            },
+        )
+        .await;
+    }
+
+    /// Make `slide` the session's slide and tell participants, whose overlay
+    /// layers are reset with it
+    async fn apply_slide_change(
+        &self,
+        session_id: &str,
+        slide: SlideInfo,
+    ) -> Result<(), SessionError> {
+        let snapshot = self.session_manager.change_slide(session_id, slide).await?;
+        self.broadcast_to_session(
+            session_id,
+            ServerMessage::SlideChanged {
+                slide: snapshot.slide,
+                rev: snapshot.rev,
+                cell_overlay: snapshot.cell_overlay,
+                tissue_overlay: snapshot.tissue_overlay,
+            },
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Announce a slide change and apply it once `slide_change_delay` has
+    /// passed, replacing any change already pending for the session. The
+    /// change is audited as `connection_id`'s when it is applied.
+    async fn schedule_slide_change(&self, connection_id: Uuid, session_id: &str, slide: SlideInfo) {
+        self.broadcast_to_session(
+            session_id,
+            ServerMessage::SlideChangePending {
+                slide: slide.clone(),
+                // Rounded up so that sub-second delays are not announced as 0
+                in_secs: self.slide_change_delay.as_millis().div_ceil(1000) as u64,
+            },
+        )
+        .await;
+
+        let actor_id = self
+            .connections
+            .get(&connection_id)
+            .and_then(|c| c.participant_id);
+        let token = Uuid::new_v4();
+        let state = self.clone();
+        let delay = self.slide_change_delay;
+        let pending_session = session_id.to_string();
+        let task = tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let still_pending = state
+                .pending_slide_changes
+                .remove_if(&pending_session, |_, pending| pending.token == token)
+                .is_some();
+            if !still_pending {
+                return;
+            }
+            let slide_id = slide.id.clone();
+            match state.apply_slide_change(&pending_session, slide).await {
+                Ok(()) => state.audit_as(
+                    actor_id,
+                    &pending_session,
+                    AuditAction::SlideChange { slide_id },
+                ),
+                Err(e) => debug!(
+                    "Pending slide change for session {} failed: {}",
+                    pending_session, e
+                ),
+            }
+        });
+        let pending = PendingSlideChange {
+            token,
+            task: task.abort_handle(),
+        };
+        if let Some(replaced) = self
+            .pending_slide_changes
+            .insert(session_id.to_string(), pending)
+        {
+            replaced.task.abort();
+        }
+    }
+
+    /// Drop the session's pending slide change and tell participants.
+    /// Returns false if no change was pending.
+    pub async fn cancel_slide_change(&self, session_id: &str) -> bool {
+        let Some((_, pending)) = self.pending_slide_changes.remove(session_id) else {
+            return false;
+        };
+        pending.task.abort();
+        self.broadcast_to_session(session_id, ServerMessage::SlideChangeCancelled)
+            .await;
+        true
+    }
+
+    /// Make sure the demo session on `slide_id` is live, re-creating it after
+    /// it expires. Returns whether a session was created.
+    pub async fn ensure_demo_session(&self, slide_id: &str, join_secret: &str) -> bool {
+        let Some(ref slide_service) = self.slide_service else {
+            warn!("Demo session needs a slide service");
+            return false;
+        };
+        match slide_service.get_slide(slide_id).await {
+            Ok(metadata) => {
+                let created = self
+                    .session_manager
+                    .ensure_demo_session(SlideInfo::from(metadata), join_secret);
+                // The demo session id is fixed, so its transcript was closed
+                // when the previous demo session ended
+                if created && let Some(ref recorder) = self.recorder {
+                    recorder.session_started(DEMO_SESSION_ID);
+                }
+                created
+            }
+            Err(e) => {
+                warn!("Demo slide {} unavailable: {}", slide_id, e);
+                false
+            }
+        }
+    }
+
     /// Get server statistics for monitoring (async version)
     pub async fn get_stats(&self) -> (usize, usize) {
         let sessions = self.session_manager.session_count_async().await;
@@ -134,11 +695,27 @@ impl Default for AppState {
     }
 }
 
+/// WebSocket subprotocol spoken by this server
+pub const WS_SUBPROTOCOL: &str = "pathcollab.v1";
+
 /// Configuration for WebSocket connections
+#[derive(Debug, Clone)]
 pub struct WsConfig {
     pub ping_interval: Duration,
     pub ping_timeout: Duration,
     pub max_message_size: usize,
+    /// Reject upgrades that request subprotocols but not [`WS_SUBPROTOCOL`]
+    pub strict_subprotocol: bool,
+    /// Log and count unknown fields in client messages (they are ignored
+    /// either way, so newer clients keep working)
+    pub report_unknown_fields: bool,
+    /// Accept `CreateSessionWithSlide` (slide metadata supplied by the client)
+    pub allow_client_slides: bool,
+    /// Cursor updates broadcast per connection per second; faster updates are
+    /// stored but not broadcast
+    pub max_cursor_hz: u32,
+    /// Open connections allowed at once; further upgrades are rejected
+    pub max_connections: usize,
 }
 
 impl Default for WsConfig {
@@ -147,22 +724,140 @@ impl Default for WsConfig {
             ping_interval: Duration::from_secs(30),
             ping_timeout: Duration::from_secs(10),
             max_message_size: 64 * 1024, // 64KB
+            strict_subprotocol: false,
+            report_unknown_fields: false,
+            allow_client_slides: false,
+            max_cursor_hz: QosProfileData::default().cursor_send_hz,
+            max_connections: 10_000,
         }
     }
 }
 
+/// A place under `WsConfig::max_connections`, released when the connection
+/// (or the failed upgrade holding it) is dropped
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
 /// WebSocket upgrade handler
-pub async fn ws_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
-    ws.on_upgrade(|socket| handle_socket(socket, state))
+///
+/// Echoes [`WS_SUBPROTOCOL`] when the client offers it. Clients that offer no
+/// subprotocol are always accepted; in strict mode, clients offering only
+/// other subprotocols are rejected before the upgrade. Upgrades beyond
+/// `max_connections` are rejected with 503.
+pub async fn ws_handler(
+    ws: WebSocketUpgrade,
+    headers: HeaderMap,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    request_id: Option<Extension<RequestId>>,
+    State(state): State<AppState>,
+) -> Response {
+    let client_ip = connect_info
+        .map(|ConnectInfo(peer)| client_ip(peer.ip(), &headers, &state.trusted_proxies));
+
+    let requested = requested_subprotocols(&headers);
+    if state.ws_config.strict_subprotocol
+        && !requested.is_empty()
+        && !requested.contains(&WS_SUBPROTOCOL)
+    {
+        warn!(
+            "Rejecting WebSocket upgrade from {:?} with subprotocols {:?}",
+            client_ip, requested
+        );
+        counter!("pathcollab_ws_upgrades_rejected_total", "reason" => "subprotocol").increment(1);
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported WebSocket subprotocol, expected {WS_SUBPROTOCOL}"),
+        )
+            .into_response();
+    }
+
+    let Some(slot) = state.reserve_connection() else {
+        warn!(
+            "Rejecting WebSocket upgrade from {:?}: {} connections open",
+            client_ip, state.ws_config.max_connections
+        );
+        counter!("pathcollab_ws_connections_rejected_total").increment(1);
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Too many open connections, try again later",
+        )
+            .into_response();
+    };
+
+    ws.protocols([WS_SUBPROTOCOL]).on_upgrade(move |socket| {
+        let connection_id = Uuid::new_v4();
+        let span = connection_span(connection_id);
+        if let Some(Extension(RequestId(request_id))) = request_id {
+            span.record("request_id", field::display(request_id));
+        }
+        async move {
+            handle_socket(socket, state, client_ip, connection_id).await;
+            drop(slot);
+        }
+        .instrument(span)
+    })
+}
+
+/// Span wrapping everything done for one connection. `request_id` is the
+/// upgrade request's id. `participant_id` and `session_id` are filled in by
+/// [`record_session_fields`] once the connection joins a session and blanked
+/// when it leaves, so a participant can be followed in the logs across
+/// reconnects.
+pub(crate) fn connection_span(connection_id: Uuid) -> Span {
+    info_span!(
+        "connection",
+        %connection_id,
+        request_id = field::Empty,
+        participant_id = field::Empty,
+        session_id = field::Empty,
+    )
+}
+
+/// Tag the current connection span with the session the connection joined
+fn record_session_fields(session_id: &str, participant_id: Uuid) {
+    let span = Span::current();
+    span.record("session_id", field::display(session_id));
+    span.record("participant_id", field::display(participant_id));
+}
+
+/// Blank the session tags once the connection leaves its session
+fn clear_session_fields() {
+    let span = Span::current();
+    span.record("session_id", "");
+    span.record("participant_id", "");
+}
+
+/// Subprotocols listed in the client's `Sec-WebSocket-Protocol` headers
+fn requested_subprotocols(headers: &HeaderMap) -> Vec<&str> {
+    headers
+        .get_all(header::SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|protocol| !protocol.is_empty())
+        .collect()
 }
 
 /// Handle a WebSocket connection
-async fn handle_socket(socket: WebSocket, state: AppState) {
-    let connection_id = Uuid::new_v4();
-    info!("New WebSocket connection: {}", connection_id);
+async fn handle_socket(
+    socket: WebSocket,
+    state: AppState,
+    client_ip: Option<IpAddr>,
+    connection_id: Uuid,
+) {
+    match client_ip {
+        Some(ip) => info!("New WebSocket connection: {} from {}", connection_id, ip),
+        None => info!("New WebSocket connection: {}", connection_id),
+    }
 
     // Create channel for outgoing messages
-    let (tx, mut rx) = mpsc::channel::<ServerMessage>(32);
+    let (tx, mut rx) = connection_channel(connection_id, SEND_QUEUE_CAPACITY);
 
     // Register connection
     {
@@ -173,10 +868,12 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                 session_id: None,
                 participant_id: None,
                 is_presenter: false,
-                last_ping: Instant::now(),
+                last_ping: tokio::time::Instant::now(),
                 sender: tx.clone(),
                 name: None,
                 color: None,
+                protocol_supported: true,
+                cursor_bucket: TokenBucket::per_second(state.ws_config.max_cursor_hz),
             },
         );
     }
@@ -184,138 +881,190 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
     // Split socket into sender and receiver
     let (mut ws_sender, mut ws_receiver) = socket.split();
 
-    // Spawn task to forward outgoing messages to WebSocket
-    let send_task = tokio::spawn(async move {
-        use futures_util::SinkExt;
-        while let Some(msg) = rx.recv().await {
-            match serde_json::to_string(&msg) {
-                Ok(json) => {
-                    if ws_sender.send(Message::Text(json)).await.is_err() {
-                        break;
+    // Spawn task to forward outgoing messages to WebSocket. A fatal error is
+    // followed by a close frame, which ends this task and the connection.
+    let mut send_task = tokio::spawn(
+        async move {
+            use futures_util::SinkExt;
+            while let Some(msg) = rx.recv().await {
+                match serde_json::to_string(&msg) {
+                    Ok(json) => {
+                        if ws_sender.send(Message::Text(json)).await.is_err() {
+                            break;
+                        }
+                        if let Some(frame) = fatal_close_frame(&msg) {
+                            let _ = ws_sender.send(Message::Close(Some(frame))).await;
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        error!("Failed to serialize message: {}", e);
                     }
-                }
-                Err(e) => {
-                    error!("Failed to serialize message: {}", e);
                 }
             }
         }
-    });
+        .in_current_span(),
+    );
 
     // Spawn ping task
     let ping_tx = tx.clone();
     let ping_state = state.clone();
     let ping_connection_id = connection_id;
-    let ping_task = tokio::spawn(async move {
-        let config = WsConfig::default();
-        let mut interval = tokio::time::interval(config.ping_interval);
+    let ping_config = state.ws_config.clone();
+    let mut ping_task = tokio::spawn(
+        async move {
+            let config = ping_config;
+            let mut interval = tokio::time::interval(config.ping_interval);
 
-        loop {
-            interval.tick().await;
+            loop {
+                interval.tick().await;
 
-            // Check if connection is still alive
-            let should_close = {
-                if let Some(conn) = ping_state.connections.get(&ping_connection_id) {
-                    conn.last_ping.elapsed() > config.ping_timeout + config.ping_interval
-                } else {
-                    true
-                }
-            };
+                // Check if connection is still alive
+                let should_close = {
+                    if let Some(conn) = ping_state.connections.get(&ping_connection_id) {
+                        conn.last_ping.elapsed() > config.ping_timeout + config.ping_interval
+                    } else {
+                        true
+                    }
+                };
 
-            if should_close {
-                debug!("Connection {} timed out", ping_connection_id);
-                break;
-            }
+                // Ending this task closes the connection (see the receive loop)
+                if should_close {
+                    debug!("Connection {} timed out", ping_connection_id);
+                    break;
+                }
 
-            // Send ping (client may respond, or we just use any activity as keepalive)
-            if ping_tx.send(ServerMessage::Ping).await.is_err() {
-                break;
+                // Send ping (client may respond, or we just use any activity as keepalive)
+                if ping_tx.send(ServerMessage::Ping).await.is_err() {
+                    break;
+                }
             }
         }
-    });
+        .in_current_span(),
+    );
 
     // Spawn task to forward broadcast messages to client
     let broadcast_tx = tx.clone();
     let broadcast_state = state.clone();
     let broadcast_connection_id = connection_id;
-    let broadcast_task = tokio::spawn(async move {
-        // Poll for session_id and subscribe when available
-        let mut current_session_id: Option<String> = None;
-        let mut broadcast_rx: Option<broadcast::Receiver<ServerMessage>> = None;
-
-        loop {
-            // Check if session_id changed
-            let session_id = {
-                broadcast_state
-                    .connections
-                    .get(&broadcast_connection_id)
-                    .and_then(|c| c.session_id.clone())
-            };
+    let broadcast_task = tokio::spawn(
+        async move {
+            // Poll for session_id and subscribe when available
+            let mut current_session_id: Option<String> = None;
+            let mut broadcast_rx: Option<broadcast::Receiver<ServerMessage>> = None;
 
-            // If session changed, subscribe to new broadcast
-            if session_id != current_session_id {
-                if let Some(ref sid) = session_id {
-                    let broadcaster = broadcast_state.get_session_broadcaster(sid).await;
-                    broadcast_rx = Some(broadcaster.subscribe());
-                    debug!(
-                        "Connection {} subscribed to session {} broadcasts",
-                        broadcast_connection_id, sid
-                    );
-                } else {
-                    broadcast_rx = None;
-                }
-                current_session_id = session_id;
-            }
+            loop {
+                // Check if session_id changed
+                let session_id = {
+                    broadcast_state
+                        .connections
+                        .get(&broadcast_connection_id)
+                        .and_then(|c| c.session_id.clone())
+                };
 
-            // Forward broadcast messages
-            if let Some(ref mut rx) = broadcast_rx {
-                match tokio::time::timeout(Duration::from_millis(100), rx.recv()).await {
-                    Ok(Ok(msg)) => {
-                        if broadcast_tx.send(msg).await.is_err() {
-                            break;
-                        }
-                    }
-                    Ok(Err(broadcast::error::RecvError::Lagged(n))) => {
-                        warn!(
-                            "Broadcast lagged {} messages for {}",
-                            n, broadcast_connection_id
+                // If session changed, subscribe to new broadcast
+                if session_id != current_session_id {
+                    if let Some(ref sid) = session_id {
+                        let broadcaster = broadcast_state.get_session_broadcaster(sid).await;
+                        broadcast_rx = Some(broadcaster.subscribe());
+                        debug!(
+                            "Connection {} subscribed to session {} broadcasts",
+                            broadcast_connection_id, sid
                         );
-                    }
-                    Ok(Err(broadcast::error::RecvError::Closed)) => {
+                    } else {
                         broadcast_rx = None;
-                        current_session_id = None;
                     }
-                    Err(_) => {
-                        // Timeout - continue polling
+                    current_session_id = session_id;
+                }
+
+                // Forward broadcast messages
+                if let Some(ref mut rx) = broadcast_rx {
+                    match tokio::time::timeout(Duration::from_millis(100), rx.recv()).await {
+                        Ok(Ok(msg)) => {
+                            if broadcast_tx.send(msg).await.is_err() {
+                                break;
+                            }
+                        }
+                        Ok(Err(broadcast::error::RecvError::Lagged(n))) => {
+                            // The client can recover with a RequestSnapshot
+                            warn!(
+                                "Broadcast lagged {} messages for {}",
+                                n, broadcast_connection_id
+                            );
+                        }
+                        Ok(Err(broadcast::error::RecvError::Closed)) => {
+                            broadcast_rx = None;
+                            current_session_id = None;
+                        }
+                        Err(_) => {
+                            // Timeout - continue polling
+                        }
                     }
+                } else {
+                    // No session yet, wait before checking again
+                    tokio::time::sleep(Duration::from_millis(100)).await;
                 }
-            } else {
-                // No session yet, wait before checking again
-                tokio::time::sleep(Duration::from_millis(100)).await;
             }
         }
-    });
+        .in_current_span(),
+    );
 
-    // Handle incoming messages
+    // Handle incoming messages until the client leaves or the ping task gives up
     use futures_util::StreamExt;
-    while let Some(result) = ws_receiver.next().await {
+    loop {
+        let result = tokio::select! {
+            next = ws_receiver.next() => match next {
+                Some(result) => result,
+                None => break,
+            },
+            _ = &mut ping_task => {
+                info!("Closing unresponsive connection {}", connection_id);
+                counter!("pathcollab_ws_connections_reaped_total").increment(1);
+                break;
+            }
+            _ = &mut send_task => {
+                debug!("Outgoing stream closed for {}", connection_id);
+                break;
+            }
+        };
+
         match result {
             Ok(msg) => {
                 match msg {
+                    Message::Text(text) if text.len() > state.ws_config.max_message_size => {
+                        warn!(
+                            "Message of {} bytes from {} exceeds limit of {} bytes",
+                            text.len(),
+                            connection_id,
+                            state.ws_config.max_message_size
+                        );
+                        counter!("pathcollab_ws_oversized_messages_total").increment(1);
+                        let _ = tx
+                            .send(ServerMessage::SessionError {
+                                code: crate::protocol::ErrorCode::MessageTooLarge,
+                                message: format!(
+                                    "Message exceeds {} bytes",
+                                    state.ws_config.max_message_size
+                                ),
+                            })
+                            .await;
+                    }
                     Message::Text(text) => {
                         // Update last ping time
                         {
                             if let Some(mut conn) = state.connections.get_mut(&connection_id) {
-                                conn.last_ping = Instant::now();
+                                conn.last_ping = tokio::time::Instant::now();
                             }
                         }
 
                         // Parse and handle message
-                        match serde_json::from_str::<ClientMessage>(&text) {
+                        match parse_client_message(&text, state.ws_config.report_unknown_fields) {
                             Ok(client_msg) => {
                                 handle_client_message(client_msg, connection_id, &state, &tx).await;
                             }
                             Err(e) => {
                                 warn!("Failed to parse client message: {}", e);
+                                record_parse_error(&e);
                                 let _ = tx
                                     .send(ServerMessage::SessionError {
                                         code: crate::protocol::ErrorCode::InvalidMessage,
@@ -337,7 +1086,7 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                     Message::Pong(_) => {
                         // Update last ping time
                         if let Some(mut conn) = state.connections.get_mut(&connection_id) {
-                            conn.last_ping = Instant::now();
+                            conn.last_ping = tokio::time::Instant::now();
                         }
                     }
                     Message::Close(_) => {
@@ -354,41 +1103,7 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
     }
 
     // Cleanup: handle participant removal from session
-    let (session_id, participant_id) = {
-        let conn = state.connections.get(&connection_id);
-        (
-            conn.as_ref().and_then(|c| c.session_id.clone()),
-            conn.and_then(|c| c.participant_id),
-        )
-    };
-
-    if let (Some(session_id), Some(participant_id)) = (session_id, participant_id) {
-        match state
-            .session_manager
-            .remove_participant(&session_id, participant_id)
-            .await
-        {
-            Ok(was_presenter) => {
-                // Broadcast participant left
-                state
-                    .broadcast_to_session(
-                        &session_id,
-                        ServerMessage::ParticipantLeft { participant_id },
-                    )
-                    .await;
-
-                if was_presenter {
-                    info!(
-                        "Presenter {} disconnected from session {}, grace period started",
-                        participant_id, session_id
-                    );
-                }
-            }
-            Err(e) => {
-                debug!("Failed to remove participant from session: {}", e);
-            }
-        }
-    }
+    state.leave_session(connection_id).await;
 
     // Cleanup tasks
     ping_task.abort();
@@ -403,6 +1118,118 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
     info!("WebSocket connection closed: {}", connection_id);
 }
 
+/// Coarse category of a client message parse failure, for metrics
+fn parse_error_category(err: &serde_json::Error) -> &'static str {
+    use serde_json::error::Category;
+
+    match err.classify() {
+        Category::Syntax | Category::Eof | Category::Io => "not_json",
+        Category::Data => {
+            // Internally tagged enums report a bad or missing `type` this way
+            let msg = err.to_string();
+            if msg.starts_with("unknown variant") || msg.starts_with("missing field `type`") {
+                "unknown_type"
+            } else {
+                "bad_field"
+            }
+        }
+    }
+}
+
+/// Close frame to follow `msg` with, if it reports a fatal error
+fn fatal_close_frame(msg: &ServerMessage) -> Option<CloseFrame<'static>> {
+    // Close reasons are limited to 123 bytes
+    const MAX_REASON_LEN: usize = 123;
+
+    let ServerMessage::SessionError { code, message } = msg else {
+        return None;
+    };
+    let mut reason_len = message.len().min(MAX_REASON_LEN);
+    while !message.is_char_boundary(reason_len) {
+        reason_len -= 1;
+    }
+    Some(CloseFrame {
+        code: code.close_code()?,
+        reason: message[..reason_len].to_string().into(),
+    })
+}
+
+/// Parse a client message. Fields the server does not know are ignored so
+/// that newer clients keep working; with `report_unknown` they are also
+/// logged and counted.
+fn parse_client_message(
+    text: &str,
+    report_unknown: bool,
+) -> Result<ClientMessage, serde_json::Error> {
+    let msg = serde_json::from_str::<ClientMessage>(text)?;
+    if report_unknown {
+        let unknown = unknown_fields(text, &msg);
+        if !unknown.is_empty() {
+            warn!(
+                "Ignored unknown fields in {} message: {}",
+                msg.message_type(),
+                unknown.join(", ")
+            );
+            counter!("pathcollab_ws_unknown_fields_total", "type" => msg.message_type())
+                .increment(unknown.len() as u64);
+        }
+    }
+    Ok(msg)
+}
+
+/// Fields of the raw message `text` that the parsed `msg` does not carry, as
+/// dotted paths. Null fields are skipped: they cannot be told apart from
+/// omitted optional fields.
+fn unknown_fields(text: &str, msg: &ClientMessage) -> Vec<String> {
+    fn collect(
+        raw: &serde_json::Value,
+        known: &serde_json::Value,
+        path: &str,
+        out: &mut Vec<String>,
+    ) {
+        use serde_json::Value;
+
+        match (raw, known) {
+            (Value::Object(raw), Value::Object(known)) => {
+                for (key, value) in raw {
+                    let field = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{path}.{key}")
+                    };
+                    match known.get(key) {
+                        Some(known) => collect(value, known, &field, out),
+                        None if !value.is_null() => out.push(field),
+                        None => {}
+                    }
+                }
+            }
+            (Value::Array(raw), Value::Array(known)) => {
+                for (i, (value, known)) in raw.iter().zip(known).enumerate() {
+                    collect(value, known, &format!("{path}[{i}]"), out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let (Ok(raw), Ok(known)) = (
+        serde_json::from_str::<serde_json::Value>(text),
+        serde_json::to_value(msg),
+    ) else {
+        return Vec::new();
+    };
+    let mut unknown = Vec::new();
+    collect(&raw, &known, "", &mut unknown);
+    unknown
+}
+
+/// Count a client message parse failure by category
+fn record_parse_error(err: &serde_json::Error) {
+    counter!("pathcollab_ws_parse_errors_total", "category" => parse_error_category(err))
+        .increment(1);
+}
+
 /// Scope guard that records message handling latency on drop
 struct MessageMetricsGuard {
     start: Instant,
@@ -417,11 +1244,11 @@ impl Drop for MessageMetricsGuard {
 }
 
 /// Handle a parsed client message
-async fn handle_client_message(
+pub(super) async fn handle_client_message(
     msg: ClientMessage,
     connection_id: Uuid,
     state: &AppState,
-    tx: &mpsc::Sender<ServerMessage>,
+    tx: &ConnectionSender,
 ) {
     let msg_type = msg.message_type();
 
@@ -434,9 +1261,72 @@ async fn handle_client_message(
     // Record message received
     counter!("pathcollab_ws_messages_total", "type" => msg_type, "direction" => "in").increment(1);
 
+    // Rejecting an unsupported `Hello` closes the socket, but messages the
+    // client pipelined behind it may be handled before the close lands
+    let protocol_supported = state
+        .connections
+        .get(&connection_id)
+        .is_none_or(|c| c.protocol_supported);
+    if !protocol_supported
+        && !matches!(
+            msg,
+            ClientMessage::Hello { .. } | ClientMessage::Ping { .. }
+        )
+    {
+        reject_unsupported_protocol(tx, msg.seq()).await;
+        return;
+    }
+
+    // Messages creating or joining a session are recorded once they succeed
+    if let Some(ref recorder) = state.recorder
+        && !matches!(msg, ClientMessage::Ping { .. })
+        && let Some(session_id) = current_session(state, connection_id)
+    {
+        recorder.record_inbound(&session_id, connection_id, &msg);
+    }
+
     match msg {
-        ClientMessage::Ping { seq } => {
-            let _ = tx.send(ServerMessage::Pong).await;
+        ClientMessage::Hello {
+            protocol_version,
+            features,
+            seq,
+        } => {
+            let supported = is_protocol_compatible(&protocol_version);
+            if let Some(mut conn) = state.connections.get_mut(&connection_id) {
+                conn.protocol_supported = supported;
+            }
+
+            if supported {
+                // Advertise enabled features, narrowed to those the client offered
+                let features = state
+                    .session_manager
+                    .features()
+                    .iter()
+                    .filter(|f| features.as_ref().is_none_or(|offered| offered.contains(f)))
+                    .cloned()
+                    .collect();
+                let _ = tx
+                    .send(ServerMessage::Welcome {
+                        protocol_version: PROTOCOL_VERSION.to_string(),
+                        server_version: env!("CARGO_PKG_VERSION").to_string(),
+                        features,
+                    })
+                    .await;
+            } else {
+                warn!(
+                    "Connection {} speaks unsupported protocol {}",
+                    connection_id, protocol_version
+                );
+                reject_unsupported_protocol(tx, seq).await;
+            }
+        }
+        ClientMessage::Ping { seq, client_ts } => {
+            let _ = tx
+                .send(ServerMessage::Pong {
+                    client_ts,
+                    server_ts: now_millis(),
+                })
+                .await;
             let _ = tx
                 .send(ServerMessage::Ack {
                     ack_seq: seq,
@@ -445,12 +1335,26 @@ async fn handle_client_message(
                 })
                 .await;
         }
-        ClientMessage::CreateSession { slide_id, seq } => {
+        ClientMessage::CreateSession {
+            slide_id,
+            max_followers,
+            seq,
+        } => {
             info!(
                 "Create session request from {}: slide={}",
                 connection_id, slide_id
             );
 
+            if let Some(current) = current_session(state, connection_id) {
+                reject_already_in_session(tx, seq, &current).await;
+                return;
+            }
+
+            if state.is_draining() {
+                reject_draining(tx, seq).await;
+                return;
+            }
+
             // Fetch slide metadata from slide service
             let slide_service = match &state.slide_service {
                 Some(service) => service,
@@ -474,18 +1378,7 @@ async fn handle_client_message(
             };
 
             let slide = match slide_service.get_slide(&slide_id).await {
-                Ok(metadata) => SlideInfo {
-                    id: metadata.id,
-                    name: metadata.name,
-                    width: metadata.width,
-                    height: metadata.height,
-                    tile_size: metadata.tile_size,
-                    num_levels: metadata.num_levels,
-                    tile_url_template: format!(
-                        "/api/slide/{}/tile/{{level}}/{{x}}/{{y}}",
-                        slide_id
-                    ),
-                },
+                Ok(metadata) => SlideInfo::from(metadata),
                 Err(e) => {
                     error!("Failed to get slide metadata: {}", e);
                     let _ = tx
@@ -505,91 +1398,71 @@ async fn handle_client_message(
                 }
             };
 
-            match state
-                .session_manager
-                .create_session(slide, connection_id)
-                .await
-            {
-                Ok((session, join_secret, presenter_key)) => {
-                    let session_id = session.id.clone();
-                    let presenter_id = session.presenter_id;
-
-                    // Get presenter info from the session for caching
-                    let (presenter_name, presenter_color) = session
-                        .participants
-                        .get(&presenter_id)
-                        .map(|p| (p.name.clone(), p.color.clone()))
-                        .unwrap_or_else(|| ("Unknown".to_string(), "#888888".to_string()));
-
-                    // Update connection with session info and cached participant data
-                    {
-                        if let Some(mut conn) = state.connections.get_mut(&connection_id) {
-                            conn.session_id = Some(session_id.clone());
-                            conn.participant_id = Some(presenter_id);
-                            conn.is_presenter = true;
-                            conn.name = Some(presenter_name);
-                            conn.color = Some(presenter_color);
-                        }
-                    }
+            let request = ClientMessage::CreateSession {
+                slide_id,
+                max_followers,
+                seq,
+            };
+            start_session(state, connection_id, tx, seq, slide, max_followers, request).await;
+        }
+        ClientMessage::CreateSessionWithSlide {
+            slide,
+            max_followers,
+            seq,
+        } => {
+            info!(
+                "Create session request from {} with client-supplied slide={}",
+                connection_id, slide.id
+            );
 
-                    // Get session snapshot
-                    let snapshot = match state.session_manager.get_session(&session_id).await {
-                        Ok(s) => s,
-                        Err(e) => {
-                            error!(
-                                "Failed to retrieve newly created session {}: {}",
-                                session_id, e
-                            );
-                            let _ = tx
-                                .send(ServerMessage::SessionError {
-                                    code: crate::protocol::ErrorCode::InvalidSlide,
-                                    message: "Internal error: session created but not retrievable"
-                                        .to_string(),
-                                })
-                                .await;
-                            return;
-                        }
-                    };
+            if let Some(current) = current_session(state, connection_id) {
+                reject_already_in_session(tx, seq, &current).await;
+                return;
+            }
 
-                    let _ = tx
-                        .send(ServerMessage::SessionCreated {
-                            session: snapshot,
-                            join_secret,
-                            presenter_key,
-                        })
-                        .await;
-                    let _ = tx
-                        .send(ServerMessage::Ack {
-                            ack_seq: seq,
-                            status: crate::protocol::AckStatus::Ok,
-                            reason: None,
-                        })
-                        .await;
+            if state.is_draining() {
+                reject_draining(tx, seq).await;
+                return;
+            }
 
-                    info!("Session {} created by {}", session_id, connection_id);
-                }
-                Err(e) => {
-                    error!("Failed to create session: {}", e);
-                    let _ = tx
-                        .send(ServerMessage::SessionError {
-                            code: crate::protocol::ErrorCode::InvalidSlide,
-                            message: format!("Failed to create session: {}", e),
-                        })
-                        .await;
-                    let _ = tx
-                        .send(ServerMessage::Ack {
-                            ack_seq: seq,
-                            status: crate::protocol::AckStatus::Rejected,
-                            reason: Some(e.to_string()),
-                        })
-                        .await;
-                }
+            let rejection = if state.ws_config.allow_client_slides {
+                slide.validate().err()
+            } else {
+                Some("Client-supplied slides are disabled".to_string())
+            };
+            if let Some(message) = rejection {
+                warn!(
+                    "Rejected client-supplied slide from {}: {}",
+                    connection_id, message
+                );
+                let _ = tx
+                    .send(ServerMessage::SessionError {
+                        code: crate::protocol::ErrorCode::InvalidSlide,
+                        message: message.clone(),
+                    })
+                    .await;
+                let _ = tx
+                    .send(ServerMessage::Ack {
+                        ack_seq: seq,
+                        status: crate::protocol::AckStatus::Rejected,
+                        reason: Some(message),
+                    })
+                    .await;
+                return;
             }
+
+            let request = ClientMessage::CreateSessionWithSlide {
+                slide: slide.clone(),
+                max_followers,
+                seq,
+            };
+            start_session(state, connection_id, tx, seq, slide, max_followers, request).await;
         }
         ClientMessage::JoinSession {
             session_id,
             join_secret,
-            last_seen_rev: _,
+            last_seen_rev,
+            reconnect_token,
             seq,
         } => {
             info!(
@@ -597,12 +1470,25 @@ async fn handle_client_message(
                 connection_id, session_id
             );
 
+            if let Some(current) = current_session(state, connection_id) {
+                reject_already_in_session(tx, seq, &current).await;
+                return;
+            }
+
+            // While draining, only reconnects (holding a token issued on an
+            // earlier join) may join
             match state
                 .session_manager
-                .join_session(&session_id, &join_secret)
+                .rejoin_session(
+                    &session_id,
+                    &join_secret,
+                    last_seen_rev,
+                    reconnect_token.as_deref(),
+                    state.is_draining(),
+                )
                 .await
             {
-                Ok((snapshot, participant)) => {
+                Ok((joined, participant, reconnect_token)) => {
                     let participant_id = participant.id;
                     let participant_name = participant.name.clone();
                     let participant_color = participant.color.clone();
@@ -617,14 +1503,59 @@ async fn handle_client_message(
                             conn.color = Some(participant_color.clone());
                         }
                     }
+                    state
+                        .participant_connections
+                        .insert((session_id.clone(), participant_id), connection_id);
+                    record_session_fields(&session_id, participant_id);
 
-                    // Send session joined to this client
-                    let _ = tx
-                        .send(ServerMessage::SessionJoined {
-                            session: snapshot.clone(),
+                    if let Some(ref recorder) = state.recorder {
+                        recorder.record_inbound(
+                            &session_id,
+                            connection_id,
+                            &ClientMessage::JoinSession {
+                                session_id: session_id.clone(),
+                                join_secret: join_secret.clone(),
+                                last_seen_rev,
+                                reconnect_token: None,
+                                seq,
+                            },
+                        );
+                    }
+
+                    // Send session state to this client (skipped if already up to date)
+                    let joined_msg = match joined {
+                        JoinSnapshot::Full(snapshot) => ServerMessage::SessionJoined {
+                            session: *snapshot,
                             you: participant.clone(),
-                        })
-                        .await;
+                            features: state
+                                .session_manager
+                                .session_features(&session_id)
+                                .unwrap_or_default(),
+                            reconnect_token,
+                        },
+                        JoinSnapshot::Unchanged {
+                            rev,
+                            presenter,
+                            followers,
+                        } => ServerMessage::SessionUnchanged {
+                            rev,
+                            you: participant.clone(),
+                            presenter,
+                            followers,
+                            reconnect_token,
+                        },
+                    };
+                    let replay = match (&joined_msg, &state.event_replay) {
+                        (ServerMessage::SessionJoined { .. }, Some(replay)) => {
+                            replay.recent(&session_id)
+                        }
+                        _ => Vec::new(),
+                    };
+                    let _ = tx.send(joined_msg).await;
+                    // Recent chat and the like, so late joiners have context
+                    for msg in replay {
+                        let _ = tx.send(msg).await;
+                    }
                     let _ = tx
                         .send(ServerMessage::Ack {
                             ack_seq: seq,
@@ -642,12 +1573,21 @@ async fn handle_client_message(
                             },
                         )
                         .await;
+                    state.notify_webhook(
+                        &session_id,
+                        WebhookEventKind::ParticipantJoined {
+                            participant_id,
+                            name: participant.name.clone(),
+                            role: participant.role,
+                        },
+                    );
 
                     info!(
                         "Participant {} ({}) joined session {}",
                         participant.name, participant_id, session_id
                     );
                 }
+                Err(SessionError::ReconnectRequired) => reject_draining(tx, seq).await,
                 Err(e) => {
                     let (code, message) = match &e {
                         SessionError::NotFound(_) | SessionError::InvalidJoinSecret => {
@@ -686,7 +1626,12 @@ async fn handle_client_message(
                 }
             }
         }
-        ClientMessage::CursorUpdate { x, y, seq: _ } => {
+        ClientMessage::CursorUpdate {
+            x,
+            y,
+            space,
+            seq: _,
+        } => {
             // Get session and participant info from cached connection data
             let (session_id, participant_id, name, color, is_presenter) = {
                 let conn = state.connections.get(&connection_id);
@@ -702,13 +1647,21 @@ async fn handle_client_message(
             if let (Some(session_id), Some(participant_id), Some(name), Some(color)) =
                 (session_id, participant_id, name, color)
             {
-                // Update cursor in session
-                if let Err(e) = state
+                // Update cursor in session, converted to slide pixels
+                let (x, y) = match state
                     .session_manager
-                    .update_cursor(&session_id, participant_id, x, y)
+                    .update_cursor(&session_id, participant_id, x, y, space)
                     .await
                 {
-                    debug!("Failed to update cursor: {}", e);
+                    Ok(position) => position,
+                    Err(e) => {
+                        debug!("Failed to update cursor: {}", e);
+                        return;
+                    }
+                };
+
+                // Hidden follower cursors still update state but are not broadcast
+                if !is_presenter && !state.session_manager.follower_cursors_visible(&session_id) {
                     return;
                 }
 
@@ -719,8 +1672,28 @@ async fn handle_client_message(
                     is_presenter,
                     x,
                     y,
+                    space: CoordinateSpace::SlidePixels,
                 };
 
+                // Over the rate limit: the position is stored but not broadcast
+                let within_limit =
+                    state
+                        .connections
+                        .get_mut(&connection_id)
+                        .is_some_and(|mut conn| {
+                            conn.cursor_bucket.try_take(tokio::time::Instant::now())
+                        });
+                if !within_limit {
+                    counter!("pathcollab_ws_cursor_throttled_total").increment(1);
+                    return;
+                }
+
+                // Batched: sent with the session's other cursors on the next tick
+                if let Some(ref batcher) = state.presence_batcher {
+                    batcher.push(&session_id, cursor);
+                    return;
+                }
+
                 // Broadcast cursor update to session
                 state
                     .broadcast_to_session(
@@ -738,6 +1711,7 @@ async fn handle_client_message(
             center_x,
             center_y,
             zoom,
+            transition_ms,
             seq: _,
         } => {
             // Get session and presenter status
@@ -757,40 +1731,35 @@ async fn handle_client_message(
                     timestamp: crate::session::state::now_millis(),
                 };
 
+                let transition_ms = transition_ms.unwrap_or(DEFAULT_VIEWPORT_TRANSITION_MS);
+
                 // Only broadcast presenter viewport to followers
                 if is_presenter {
-                    if let Err(e) = state
-                        .session_manager
-                        .update_presenter_viewport(&session_id, viewport.clone())
-                        .await
-                    {
-                        debug!("Failed to update presenter viewport: {}", e);
-                        return;
+                    match state.viewport_batcher {
+                        Some(ref batcher) => batcher.push(&session_id, viewport, transition_ms),
+                        None => {
+                            state
+                                .apply_presenter_viewport(&session_id, viewport, transition_ms)
+                                .await
+                        }
                     }
-
-                    state
-                        .broadcast_to_session(
-                            &session_id,
-                            ServerMessage::PresenterViewport { viewport },
-                        )
-                        .await;
                 }
             }
         }
         ClientMessage::PresenterAuth { presenter_key, seq } => {
-            // Get session ID
-            let session_id = {
+            // Get session and participant IDs
+            let ids = {
                 state
                     .connections
                     .get(&connection_id)
-                    .and_then(|c| c.session_id.clone())
+                    .and_then(|c| c.session_id.clone().zip(c.participant_id))
             };
 
-            match session_id {
-                Some(session_id) => {
+            match ids {
+                Some((session_id, participant_id)) => {
                     match state
                         .session_manager
-                        .authenticate_presenter(&session_id, &presenter_key)
+                        .authenticate_presenter(&session_id, participant_id, &presenter_key)
                         .await
                     {
                         Ok(()) => {
@@ -800,6 +1769,7 @@ async fn handle_client_message(
                                     conn.is_presenter = true;
                                 }
                             }
+                            state.audit(connection_id, &session_id, AuditAction::PresenterAuth);
                             let _ = tx
                                 .send(ServerMessage::Ack {
                                     ack_seq: seq,
@@ -840,75 +1810,137 @@ async fn handle_client_message(
                     .and_then(|c| c.session_id.clone())
             };
 
-            #[allow(clippy::collapsible_if)]
-            if let Some(session_id) = session_id {
-                if let Ok(snapshot) = state.session_manager.get_session(&session_id).await {
-                    let _ = tx
-                        .send(ServerMessage::PresenterViewport {
-                            viewport: snapshot.presenter_viewport,
-                        })
-                        .await;
-                }
-            }
-
-            let _ = tx
-                .send(ServerMessage::Ack {
-                    ack_seq: seq,
-                    status: crate::protocol::AckStatus::Ok,
-                    reason: None,
-                })
-                .await;
-        }
-        ClientMessage::ChangeSlide { slide_id, seq } => {
-            // Get session ID and presenter status
-            let (session_id, is_presenter) = {
-                let conn = state.connections.get(&connection_id);
-                (
-                    conn.as_ref().and_then(|c| c.session_id.clone()),
-                    conn.is_some_and(|c| c.is_presenter),
-                )
-            };
-
-            // Only presenter can change slides
-            if !is_presenter {
+            let Some(session_id) = session_id else {
                 let _ = tx
                     .send(ServerMessage::Ack {
                         ack_seq: seq,
                         status: crate::protocol::AckStatus::Rejected,
-                        reason: Some("Only presenter can change slides".to_string()),
+                        reason: Some("Not in a session".to_string()),
                     })
                     .await;
                 return;
-            }
+            };
 
-            if let Some(session_id) = session_id {
-                // Fetch slide metadata
-                let slide = if let Some(ref slide_service) = state.slide_service {
-                    match slide_service.get_slide(&slide_id).await {
-                        Ok(metadata) => SlideInfo {
-                            id: metadata.id,
-                            name: metadata.name,
-                            width: metadata.width,
-                            height: metadata.height,
-                            tile_size: metadata.tile_size,
-                            num_levels: metadata.num_levels,
-                            tile_url_template: format!(
-                                "/api/slide/{}/tile/{{level}}/{{x}}/{{y}}",
-                                slide_id
-                            ),
-                        },
-                        Err(e) => {
-                            let _ = tx
-                                .send(ServerMessage::Ack {
-                                    ack_seq: seq,
-                                    status: crate::protocol::AckStatus::Rejected,
-                                    reason: Some(format!("Slide not found: {}", e)),
-                                })
-                                .await;
-                            return;
-                        }
-                    }
-                } else {
+            // With the presenter gone the stored viewport may be stale, so
+            // the snap is refused rather than served
+            match state.session_manager.presenter_viewport(&session_id).await {
+                Ok(viewport) => {
+                    let _ = tx
+                        .send(ServerMessage::PresenterViewport {
+                            viewport,
+                            transition_ms: DEFAULT_VIEWPORT_TRANSITION_MS,
+                        })
+                        .await;
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Ok,
+                            reason: None,
+                        })
+                        .await;
+                }
+                Err(e) => {
+                    if let SessionError::NoPresenter = e {
+                        let _ = tx
+                            .send(ServerMessage::SessionError {
+                                code: crate::protocol::ErrorCode::NoPresenter,
+                                message: e.to_string(),
+                            })
+                            .await;
+                    }
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Rejected,
+                            reason: Some(e.to_string()),
+                        })
+                        .await;
+                }
+            }
+        }
+        ClientMessage::RequestSnapshot { seq } => {
+            // Reply with the full session state to this connection only
+            let session_id = {
+                state
+                    .connections
+                    .get(&connection_id)
+                    .and_then(|c| c.session_id.clone())
+            };
+
+            let Some(session_id) = session_id else {
+                let _ = tx
+                    .send(ServerMessage::Ack {
+                        ack_seq: seq,
+                        status: crate::protocol::AckStatus::Rejected,
+                        reason: Some("Not in a session".to_string()),
+                    })
+                    .await;
+                return;
+            };
+
+            match state.session_manager.get_session(&session_id).await {
+                Ok(snapshot) => {
+                    let _ = tx
+                        .send(ServerMessage::SessionSnapshot { session: snapshot })
+                        .await;
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Ok,
+                            reason: None,
+                        })
+                        .await;
+                }
+                Err(e) => {
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Rejected,
+                            reason: Some(e.to_string()),
+                        })
+                        .await;
+                }
+            }
+        }
+        ClientMessage::ChangeSlide { slide_id, seq } => {
+            // Get session ID and presenter status
+            let (session_id, is_presenter) = {
+                let conn = state.connections.get(&connection_id);
+                (
+                    conn.as_ref().and_then(|c| c.session_id.clone()),
+                    conn.is_some_and(|c| c.is_presenter),
+                )
+            };
+
+            // Only presenter can change slides
+            if !is_presenter {
+                let _ = tx
+                    .send(ServerMessage::Ack {
+                        ack_seq: seq,
+                        status: crate::protocol::AckStatus::Rejected,
+                        reason: Some("Only presenter can change slides".to_string()),
+                    })
+                    .await;
+                return;
+            }
+
+            if let Some(session_id) = session_id {
+                // Fetch slide metadata
+                let slide = if let Some(ref slide_service) = state.slide_service {
+                    match slide_service.get_slide(&slide_id).await {
+                        Ok(metadata) => SlideInfo::from(metadata),
+                        Err(e) => {
+                            let _ = tx
+                                .send(ServerMessage::Ack {
+                                    ack_seq: seq,
+                                    status: crate::protocol::AckStatus::Rejected,
+                                    reason: Some(format!("Slide not found: {}", e)),
+                                })
+                                .await;
+                            return;
+                        }
+                    }
+                } else {
                     let _ = tx
                         .send(ServerMessage::Ack {
                             ack_seq: seq,
@@ -919,20 +1951,35 @@ async fn handle_client_message(
                     return;
                 };
 
+                // Give followers notice first when a delay is configured
+                if !state.slide_change_delay.is_zero() {
+                    state
+                        .schedule_slide_change(connection_id, &session_id, slide)
+                        .await;
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Ok,
+                            reason: None,
+                        })
+                        .await;
+                    info!(
+                        "Session {} slide change to {} pending",
+                        session_id, slide_id
+                    );
+                    return;
+                }
+
                 // Update session with new slide
-                match state
-                    .session_manager
-                    .change_slide(&session_id, slide.clone())
-                    .await
-                {
-                    Ok(new_slide) => {
-                        // Broadcast slide change to all participants
-                        state
-                            .broadcast_to_session(
-                                &session_id,
-                                ServerMessage::SlideChanged { slide: new_slide },
-                            )
-                            .await;
+                match state.apply_slide_change(&session_id, slide).await {
+                    Ok(()) => {
+                        state.audit(
+                            connection_id,
+                            &session_id,
+                            AuditAction::SlideChange {
+                                slide_id: slide_id.clone(),
+                            },
+                        );
 
                         let _ = tx
                             .send(ServerMessage::Ack {
@@ -967,6 +2014,73 @@ async fn handle_client_message(
                     .await;
             }
         }
+        ClientMessage::CancelSlideChange { seq } => {
+            let (session_id, is_presenter) = {
+                let conn = state.connections.get(&connection_id);
+                (
+                    conn.as_ref().and_then(|c| c.session_id.clone()),
+                    conn.is_some_and(|c| c.is_presenter),
+                )
+            };
+
+            let rejection = match session_id {
+                _ if !is_presenter => Some("Only presenter can cancel slide changes"),
+                Some(session_id) if state.cancel_slide_change(&session_id).await => {
+                    info!("Session {} pending slide change cancelled", session_id);
+                    None
+                }
+                Some(_) => Some("No slide change pending"),
+                None => Some("Not in a session"),
+            };
+            let _ = tx
+                .send(ServerMessage::Ack {
+                    ack_seq: seq,
+                    status: if rejection.is_some() {
+                        crate::protocol::AckStatus::Rejected
+                    } else {
+                        crate::protocol::AckStatus::Ok
+                    },
+                    reason: rejection.map(str::to_string),
+                })
+                .await;
+        }
+        ClientMessage::ExtendSession { seq } => {
+            let (session_id, is_presenter) = {
+                let conn = state.connections.get(&connection_id);
+                (
+                    conn.as_ref().and_then(|c| c.session_id.clone()),
+                    conn.is_some_and(|c| c.is_presenter),
+                )
+            };
+
+            let result = match session_id {
+                _ if !is_presenter => Err("Only presenter can extend the session".to_string()),
+                Some(session_id) => match state.session_manager.extend_session(&session_id).await {
+                    Ok(expires_at) => {
+                        state
+                            .broadcast_to_session(
+                                &session_id,
+                                ServerMessage::SessionExtended { expires_at },
+                            )
+                            .await;
+                        Ok(())
+                    }
+                    Err(e) => Err(e.to_string()),
+                },
+                None => Err("Not in a session".to_string()),
+            };
+            let _ = tx
+                .send(ServerMessage::Ack {
+                    ack_seq: seq,
+                    status: if result.is_ok() {
+                        crate::protocol::AckStatus::Ok
+                    } else {
+                        crate::protocol::AckStatus::Rejected
+                    },
+                    reason: result.err(),
+                })
+                .await;
+        }
         ClientMessage::CellOverlayUpdate {
             enabled,
             opacity,
@@ -1008,6 +2122,16 @@ async fn handle_client_message(
                     .await
                 {
                     Ok(_) => {
+                        state.audit(
+                            connection_id,
+                            &session_id,
+                            AuditAction::CellOverlayUpdate {
+                                enabled,
+                                opacity,
+                                visible_cell_types: visible_cell_types.clone(),
+                            },
+                        );
+
                         // Broadcast to all participants
                         state
                             .broadcast_to_session(
@@ -1091,6 +2215,16 @@ async fn handle_client_message(
                     .await
                 {
                     Ok(_) => {
+                        state.audit(
+                            connection_id,
+                            &session_id,
+                            AuditAction::TissueOverlayUpdate {
+                                enabled,
+                                opacity,
+                                visible_tissue_types: visible_tissue_types.clone(),
+                            },
+                        );
+
                         // Broadcast to all participants
                         state
                             .broadcast_to_session(
@@ -1133,6 +2267,1898 @@ async fn handle_client_message(
                     .await;
             }
         }
-    }
-    // Note: The MessageMetricsGuard will record latency metrics when it's dropped here
+        ClientMessage::LayerPatch { patch, seq } => {
+            // Get session ID and presenter status
+            let (session_id, is_presenter) = {
+                let conn = state.connections.get(&connection_id);
+                (
+                    conn.as_ref().and_then(|c| c.session_id.clone()),
+                    conn.is_some_and(|c| c.is_presenter),
+                )
+            };
+
+            // Only presenter can patch overlay layers
+            if !is_presenter {
+                let _ = tx
+                    .send(ServerMessage::Ack {
+                        ack_seq: seq,
+                        status: crate::protocol::AckStatus::Rejected,
+                        reason: Some("Only presenter can update layers".to_string()),
+                    })
+                    .await;
+                return;
+            }
+
+            let Some(session_id) = session_id else {
+                let _ = tx
+                    .send(ServerMessage::Ack {
+                        ack_seq: seq,
+                        status: crate::protocol::AckStatus::Rejected,
+                        reason: Some("Not in a session".to_string()),
+                    })
+                    .await;
+                return;
+            };
+
+            match state
+                .session_manager
+                .apply_layer_patch(&session_id, &patch)
+                .await
+            {
+                Ok(rev) => {
+                    // Empty patches change nothing, so there is nothing to broadcast
+                    if !patch.is_empty() {
+                        state.audit(
+                            connection_id,
+                            &session_id,
+                            AuditAction::LayerPatch {
+                                patch: patch.clone(),
+                            },
+                        );
+                        state
+                            .broadcast_to_session(
+                                &session_id,
+                                ServerMessage::LayerPatched { patch, rev },
+                            )
+                            .await;
+                    }
+
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Ok,
+                            reason: None,
+                        })
+                        .await;
+
+                    debug!("Session {} layers patched by presenter", session_id);
+                }
+                Err(e) => {
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Rejected,
+                            reason: Some(e.to_string()),
+                        })
+                        .await;
+                }
+            }
+        }
+        ClientMessage::SetFollowerCursors { visible, seq } => {
+            // Get session ID and presenter status
+            let (session_id, is_presenter) = {
+                let conn = state.connections.get(&connection_id);
+                (
+                    conn.as_ref().and_then(|c| c.session_id.clone()),
+                    conn.is_some_and(|c| c.is_presenter),
+                )
+            };
+
+            // Only presenter can toggle follower cursors
+            if !is_presenter {
+                let _ = tx
+                    .send(ServerMessage::Ack {
+                        ack_seq: seq,
+                        status: crate::protocol::AckStatus::Rejected,
+                        reason: Some("Only presenter can toggle follower cursors".to_string()),
+                    })
+                    .await;
+                return;
+            }
+
+            let Some(session_id) = session_id else {
+                let _ = tx
+                    .send(ServerMessage::Ack {
+                        ack_seq: seq,
+                        status: crate::protocol::AckStatus::Rejected,
+                        reason: Some("Not in a session".to_string()),
+                    })
+                    .await;
+                return;
+            };
+
+            match state
+                .session_manager
+                .set_follower_cursors(&session_id, visible)
+                .await
+            {
+                Ok(_) => {
+                    state.audit(
+                        connection_id,
+                        &session_id,
+                        AuditAction::FollowerCursorsToggle { visible },
+                    );
+                    state
+                        .broadcast_to_session(
+                            &session_id,
+                            ServerMessage::FollowerCursorsToggled { visible },
+                        )
+                        .await;
+
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Ok,
+                            reason: None,
+                        })
+                        .await;
+                }
+                Err(e) => {
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Rejected,
+                            reason: Some(e.to_string()),
+                        })
+                        .await;
+                }
+            }
+        }
+        ClientMessage::RewindViewport { steps, seq } => {
+            // Get session ID and presenter status
+            let (session_id, is_presenter) = {
+                let conn = state.connections.get(&connection_id);
+                (
+                    conn.as_ref().and_then(|c| c.session_id.clone()),
+                    conn.is_some_and(|c| c.is_presenter),
+                )
+            };
+
+            // Only presenter can rewind the viewport
+            if !is_presenter {
+                let _ = tx
+                    .send(ServerMessage::Ack {
+                        ack_seq: seq,
+                        status: crate::protocol::AckStatus::Rejected,
+                        reason: Some("Only presenter can rewind the viewport".to_string()),
+                    })
+                    .await;
+                return;
+            }
+
+            let Some(session_id) = session_id else {
+                let _ = tx
+                    .send(ServerMessage::Ack {
+                        ack_seq: seq,
+                        status: crate::protocol::AckStatus::Rejected,
+                        reason: Some("Not in a session".to_string()),
+                    })
+                    .await;
+                return;
+            };
+
+            match state
+                .session_manager
+                .rewind_presenter_viewport(&session_id, steps as usize)
+                .await
+            {
+                Ok(viewport) => {
+                    state
+                        .broadcast_to_session(
+                            &session_id,
+                            ServerMessage::PresenterViewport {
+                                viewport,
+                                transition_ms: DEFAULT_VIEWPORT_TRANSITION_MS,
+                            },
+                        )
+                        .await;
+
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Ok,
+                            reason: None,
+                        })
+                        .await;
+                }
+                Err(e) => {
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Rejected,
+                            reason: Some(e.to_string()),
+                        })
+                        .await;
+                }
+            }
+        }
+        ClientMessage::SetName { name, seq } => {
+            let (session_id, participant_id) = {
+                let conn = state.connections.get(&connection_id);
+                (
+                    conn.as_ref().and_then(|c| c.session_id.clone()),
+                    conn.as_ref().and_then(|c| c.participant_id),
+                )
+            };
+
+            let (Some(session_id), Some(participant_id)) = (session_id, participant_id) else {
+                let _ = tx
+                    .send(ServerMessage::Ack {
+                        ack_seq: seq,
+                        status: crate::protocol::AckStatus::Rejected,
+                        reason: Some("Not in a session".to_string()),
+                    })
+                    .await;
+                return;
+            };
+
+            match state
+                .session_manager
+                .rename_participant(&session_id, participant_id, &name)
+                .await
+            {
+                Ok(name) => {
+                    // Keep the cached name used for cursor broadcasts in sync
+                    if let Some(mut conn) = state.connections.get_mut(&connection_id) {
+                        conn.name = Some(name.clone());
+                    }
+                    state
+                        .broadcast_to_session(
+                            &session_id,
+                            ServerMessage::ParticipantRenamed {
+                                participant_id,
+                                name,
+                            },
+                        )
+                        .await;
+
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Ok,
+                            reason: None,
+                        })
+                        .await;
+                }
+                Err(e) => {
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Rejected,
+                            reason: Some(e.to_string()),
+                        })
+                        .await;
+                }
+            }
+        }
+        ClientMessage::LeaveSession { seq } => {
+            let ack = match state.leave_session(connection_id).await {
+                Some(session_id) => {
+                    info!("Connection {} left session {}", connection_id, session_id);
+                    clear_session_fields();
+                    ServerMessage::Ack {
+                        ack_seq: seq,
+                        status: crate::protocol::AckStatus::Ok,
+                        reason: None,
+                    }
+                }
+                None => ServerMessage::Ack {
+                    ack_seq: seq,
+                    status: crate::protocol::AckStatus::Rejected,
+                    reason: Some("Not in a session".to_string()),
+                },
+            };
+            let _ = tx.send(ack).await;
+        }
+        ClientMessage::GetSlide { slide_id, seq } => {
+            let reply = match state.slide_service {
+                Some(ref service) => service
+                    .get_slide(&slide_id)
+                    .await
+                    .map(|slide| ServerMessage::SlideMetadata {
+                        request_seq: seq,
+                        slide,
+                    })
+                    .map_err(|e| e.to_string()),
+                None => Err("Slide service unavailable".to_string()),
+            };
+            send_reply(tx, seq, reply).await;
+        }
+        ClientMessage::ListSlides { seq } => {
+            let reply = match state.slide_service {
+                Some(ref service) => service
+                    .list_slides()
+                    .await
+                    .map(|slides| ServerMessage::SlideList {
+                        request_seq: seq,
+                        slides: slides.into_iter().map(SlideListItem::from).collect(),
+                    })
+                    .map_err(|e| e.to_string()),
+                None => Err("Slide service unavailable".to_string()),
+            };
+            send_reply(tx, seq, reply).await;
+        }
+        ClientMessage::ChatMessage { text, seq } => {
+            let (session_id, participant_id) = {
+                let conn = state.connections.get(&connection_id);
+                (
+                    conn.as_ref().and_then(|c| c.session_id.clone()),
+                    conn.as_ref().and_then(|c| c.participant_id),
+                )
+            };
+
+            let (Some(session_id), Some(participant_id)) = (session_id, participant_id) else {
+                let _ = tx
+                    .send(ServerMessage::Ack {
+                        ack_seq: seq,
+                        status: crate::protocol::AckStatus::Rejected,
+                        reason: Some("Not in a session".to_string()),
+                    })
+                    .await;
+                return;
+            };
+
+            match state
+                .session_manager
+                .post_chat(&session_id, participant_id, &text)
+            {
+                Ok((name, text)) => {
+                    state
+                        .broadcast_to_session(
+                            &session_id,
+                            ServerMessage::ChatMessage {
+                                participant_id,
+                                name,
+                                text,
+                                sent_at: now_millis(),
+                            },
+                        )
+                        .await;
+
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Ok,
+                            reason: None,
+                        })
+                        .await;
+                }
+                Err(e) => {
+                    if let SessionError::FeatureDisabled(_) = e {
+                        let _ = tx
+                            .send(ServerMessage::SessionError {
+                                code: crate::protocol::ErrorCode::FeatureDisabled,
+                                message: e.to_string(),
+                            })
+                            .await;
+                    }
+                    let _ = tx
+                        .send(ServerMessage::Ack {
+                            ack_seq: seq,
+                            status: crate::protocol::AckStatus::Rejected,
+                            reason: Some(e.to_string()),
+                        })
+                        .await;
+                }
+            }
+        }
+    }
+    // Note: The MessageMetricsGuard will record latency metrics when it's dropped here
+}
+
+/// Send a reply to a request followed by its ack, or a rejected ack on error
+async fn send_reply(tx: &ConnectionSender, seq: u64, reply: Result<ServerMessage, String>) {
+    let ack = match reply {
+        Ok(msg) => {
+            let _ = tx.send(msg).await;
+            ServerMessage::Ack {
+                ack_seq: seq,
+                status: crate::protocol::AckStatus::Ok,
+                reason: None,
+            }
+        }
+        Err(reason) => ServerMessage::Ack {
+            ack_seq: seq,
+            status: crate::protocol::AckStatus::Rejected,
+            reason: Some(reason),
+        },
+    };
+    let _ = tx.send(ack).await;
+}
+
+/// Reject a message from a client whose protocol version is incompatible
+async fn reject_unsupported_protocol(tx: &ConnectionSender, seq: u64) {
+    counter!("pathcollab_ws_protocol_rejections_total").increment(1);
+    let message = format!(
+        "Unsupported protocol version; server speaks {}",
+        PROTOCOL_VERSION
+    );
+    // Ack first: the error closes the connection
+    let _ = tx
+        .send(ServerMessage::Ack {
+            ack_seq: seq,
+            status: crate::protocol::AckStatus::Rejected,
+            reason: Some(message.clone()),
+        })
+        .await;
+    let _ = tx
+        .send(ServerMessage::SessionError {
+            code: crate::protocol::ErrorCode::UnsupportedProtocol,
+            message,
+        })
+        .await;
+}
+
+/// Create a session on `slide` with the connection as presenter, reply with
+/// the credentials and links, and record `request` as the session's first message
+async fn start_session(
+    state: &AppState,
+    connection_id: Uuid,
+    tx: &ConnectionSender,
+    seq: u64,
+    slide: SlideInfo,
+    max_followers: Option<usize>,
+    request: ClientMessage,
+) {
+    let slide_id = slide.id.clone();
+    match state
+        .session_manager
+        .create_session_with_max_followers(slide, connection_id, max_followers)
+        .await
+    {
+        Ok((session, join_secret, presenter_key)) => {
+            let session_id = session.id.clone();
+            let presenter_id = session.presenter_id;
+
+            if let Some(ref recorder) = state.recorder {
+                recorder.record_inbound(&session_id, connection_id, &request);
+            }
+
+            // Get presenter info from the session for caching
+            let (presenter_name, presenter_color) = session
+                .participants
+                .get(&presenter_id)
+                .map(|p| (p.name.clone(), p.color.clone()))
+                .unwrap_or_else(|| ("Unknown".to_string(), "#888888".to_string()));
+
+            // Update connection with session info and cached participant data
+            {
+                if let Some(mut conn) = state.connections.get_mut(&connection_id) {
+                    conn.session_id = Some(session_id.clone());
+                    conn.participant_id = Some(presenter_id);
+                    conn.is_presenter = true;
+                    conn.name = Some(presenter_name);
+                    conn.color = Some(presenter_color);
+                }
+            }
+            state
+                .participant_connections
+                .insert((session_id.clone(), presenter_id), connection_id);
+            record_session_fields(&session_id, presenter_id);
+
+            // Get session snapshot
+            let snapshot = match state.session_manager.get_session(&session_id).await {
+                Ok(s) => s,
+                Err(e) => {
+                    error!(
+                        "Failed to retrieve newly created session {}: {}",
+                        session_id, e
+                    );
+                    let _ = tx
+                        .send(ServerMessage::SessionError {
+                            code: crate::protocol::ErrorCode::InvalidSlide,
+                            message: "Internal error: session created but not retrievable"
+                                .to_string(),
+                        })
+                        .await;
+                    return;
+                }
+            };
+
+            let links = SessionLinks::build(
+                state.public_base_url.as_deref(),
+                &session_id,
+                &join_secret,
+                &presenter_key,
+            );
+            let _ = tx
+                .send(ServerMessage::SessionCreated {
+                    session: snapshot,
+                    join_secret,
+                    presenter_key,
+                    join_url: links.join_url,
+                    viewer_url: links.viewer_url,
+                    presenter_url: links.presenter_url,
+                })
+                .await;
+            let _ = tx
+                .send(ServerMessage::Ack {
+                    ack_seq: seq,
+                    status: crate::protocol::AckStatus::Ok,
+                    reason: None,
+                })
+                .await;
+
+            state.notify_webhook(
+                &session_id,
+                WebhookEventKind::SessionCreated {
+                    slide_id,
+                    presenter_id,
+                },
+            );
+
+            info!("Session {} created by {}", session_id, connection_id);
+        }
+        Err(e) => {
+            error!("Failed to create session: {}", e);
+            let code = match e {
+                SessionError::ServerAtCapacity(_) => crate::protocol::ErrorCode::ServerBusy,
+                _ => crate::protocol::ErrorCode::InvalidSlide,
+            };
+            let _ = tx
+                .send(ServerMessage::SessionError {
+                    code,
+                    message: format!("Failed to create session: {}", e),
+                })
+                .await;
+            let _ = tx
+                .send(ServerMessage::Ack {
+                    ack_seq: seq,
+                    status: crate::protocol::AckStatus::Rejected,
+                    reason: Some(e.to_string()),
+                })
+                .await;
+        }
+    }
+}
+
+/// Session the connection currently belongs to, if any
+fn current_session(state: &AppState, connection_id: Uuid) -> Option<String> {
+    state
+        .connections
+        .get(&connection_id)
+        .and_then(|conn| conn.session_id.clone())
+}
+
+/// Reject a session create/join on a connection that is already in a session
+async fn reject_already_in_session(tx: &ConnectionSender, seq: u64, session_id: &str) {
+    let message = format!("Already in session {}", session_id);
+    let _ = tx
+        .send(ServerMessage::SessionError {
+            code: crate::protocol::ErrorCode::AlreadyInSession,
+            message: message.clone(),
+        })
+        .await;
+    let _ = tx
+        .send(ServerMessage::Ack {
+            ack_seq: seq,
+            status: crate::protocol::AckStatus::Rejected,
+            reason: Some(message),
+        })
+        .await;
+}
+
+/// Reject a session create/join because the server is draining
+async fn reject_draining(tx: &ConnectionSender, seq: u64) {
+    counter!("pathcollab_drain_rejections_total").increment(1);
+    let message = "Server is draining; try again shortly".to_string();
+    let _ = tx
+        .send(ServerMessage::SessionError {
+            code: crate::protocol::ErrorCode::ServerDraining,
+            message: message.clone(),
+        })
+        .await;
+    let _ = tx
+        .send(ServerMessage::Ack {
+            ack_seq: seq,
+            status: crate::protocol::AckStatus::Rejected,
+            reason: Some(message),
+        })
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::AckStatus;
+    use crate::server::harness::{
+        FIXTURE_SLIDE_ID, FakeConnection, ack_status, fixture_state, session_with_follower,
+    };
+    use metrics::{
+        Counter, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString,
+        Unit,
+    };
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Minimal recorder capturing counters by their `category` label
+    #[derive(Default)]
+    struct CategoryRecorder {
+        counters: Mutex<HashMap<String, Arc<AtomicU64>>>,
+    }
+
+    impl CategoryRecorder {
+        fn count(&self, category: &str) -> u64 {
+            self.counters
+                .lock()
+                .unwrap()
+                .get(category)
+                .map_or(0, |c| c.load(Ordering::Relaxed))
+        }
+    }
+
+    impl Recorder for CategoryRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            let category = key
+                .labels()
+                .find(|l| l.key() == "category")
+                .map(|l| l.value().to_string())
+                .unwrap_or_default();
+            let counter = self
+                .counters
+                .lock()
+                .unwrap()
+                .entry(category)
+                .or_default()
+                .clone();
+            Counter::from_arc(counter)
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    /// Histogram capturing every recorded value
+    #[derive(Default)]
+    struct Samples(Mutex<Vec<f64>>);
+
+    impl HistogramFn for Samples {
+        fn record(&self, value: f64) {
+            self.0.lock().unwrap().push(value);
+        }
+    }
+
+    /// Recorder capturing histogram samples by metric name and `type` label
+    #[derive(Default)]
+    struct TypeRecorder {
+        histograms: Mutex<HashMap<(String, String), Arc<Samples>>>,
+    }
+
+    impl TypeRecorder {
+        fn samples(&self, name: &str, msg_type: &str) -> Vec<f64> {
+            self.histograms
+                .lock()
+                .unwrap()
+                .get(&(name.to_string(), msg_type.to_string()))
+                .map(|s| s.0.lock().unwrap().clone())
+                .unwrap_or_default()
+        }
+    }
+
+    impl Recorder for TypeRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, _: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::noop()
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            let Some(msg_type) = key.labels().find(|l| l.key() == "type") else {
+                return Histogram::noop();
+            };
+            let samples = self
+                .histograms
+                .lock()
+                .unwrap()
+                .entry((key.name().to_string(), msg_type.value().to_string()))
+                .or_default()
+                .clone();
+            Histogram::from_arc(samples)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ping_pong() {
+        let state = fixture_state();
+        let mut conn = FakeConnection::connect(&state);
+
+        conn.send(ClientMessage::Ping {
+            seq: 1,
+            client_ts: None,
+        })
+        .await;
+
+        let messages = conn.drain();
+        assert!(matches!(messages.first(), Some(ServerMessage::Pong { .. })));
+        assert_eq!(ack_status(&messages, 1), Some(AckStatus::Ok));
+    }
+
+    #[tokio::test]
+    async fn test_pong_echoes_client_timestamp() {
+        let state = fixture_state();
+        let mut conn = FakeConnection::connect(&state);
+
+        let before = now_millis();
+        conn.send(ClientMessage::Ping {
+            seq: 1,
+            client_ts: Some(123_456),
+        })
+        .await;
+
+        match conn.drain().first() {
+            Some(ServerMessage::Pong {
+                client_ts,
+                server_ts,
+            }) => {
+                assert_eq!(*client_ts, Some(123_456));
+                assert!(*server_ts >= before && *server_ts <= now_millis());
+            }
+            other => panic!("expected pong, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_session() {
+        let state = fixture_state();
+        let mut conn = FakeConnection::connect(&state);
+
+        conn.send(ClientMessage::CreateSession {
+            slide_id: FIXTURE_SLIDE_ID.to_string(),
+            max_followers: None,
+            seq: 1,
+        })
+        .await;
+
+        let messages = conn.drain();
+        assert_eq!(ack_status(&messages, 1), Some(AckStatus::Ok));
+        let Some(ServerMessage::SessionCreated {
+            session,
+            join_secret,
+            presenter_key,
+            join_url,
+            ..
+        }) = messages
+            .iter()
+            .find(|m| matches!(m, ServerMessage::SessionCreated { .. }))
+        else {
+            panic!("Should receive session_created, got {messages:?}");
+        };
+
+        assert_eq!(conn.session_id().as_deref(), Some(session.id.as_str()));
+        assert_eq!(session.slide.mpp_x, Some(0.25));
+        assert_eq!(session.id.len(), 10, "Session ID should be 10 characters");
+        assert!(
+            session
+                .id
+                .chars()
+                .all(|c| "abcdefghijklmnopqrstuvwxyz234567".contains(c)),
+            "Session ID should be base32"
+        );
+        // Without a public base URL the join link is relative
+        assert_eq!(*join_url, format!("/s/{}#join={}", session.id, join_secret));
+        assert!(join_secret.len() >= 32, "join_secret needs 128+ bits");
+        assert!(presenter_key.len() >= 48, "presenter_key needs 192+ bits");
+    }
+
+    #[tokio::test]
+    async fn test_join_is_broadcast_to_presenter() {
+        let state = fixture_state();
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+
+        assert_eq!(ack_status(&follower.drain(), 1), Some(AckStatus::Ok));
+        assert!(
+            presenter
+                .drain()
+                .iter()
+                .any(|m| matches!(m, ServerMessage::ParticipantJoined { .. }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_is_broadcast_sanitized() {
+        let state = fixture_state();
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        let session_id = presenter.session_id().unwrap();
+        let follower_id = follower.drain().into_iter().find_map(|m| match m {
+            ServerMessage::SessionJoined { you, .. } => Some(you.id),
+            _ => None,
+        });
+        let follower_id = follower_id.expect("Follower should join");
+        presenter.drain();
+        let rev_before = state
+            .session_manager
+            .get_session(&session_id)
+            .await
+            .unwrap()
+            .rev;
+
+        follower
+            .send(ClientMessage::SetName {
+                name: "  Dr.\u{7}  Ada\n   Lovelace of the Pathology Department  ".to_string(),
+                seq: 2,
+            })
+            .await;
+        assert_eq!(ack_status(&follower.drain(), 2), Some(AckStatus::Ok));
+
+        let expected = "Dr. Ada Lovelace of the Patholog";
+        let renamed = presenter.drain().into_iter().find_map(|m| match m {
+            ServerMessage::ParticipantRenamed {
+                participant_id,
+                name,
+            } => Some((participant_id, name)),
+            _ => None,
+        });
+        assert_eq!(renamed, Some((follower_id, expected.to_string())));
+
+        let snapshot = state
+            .session_manager
+            .get_session(&session_id)
+            .await
+            .unwrap();
+        assert_eq!(snapshot.rev, rev_before + 1);
+        assert_eq!(snapshot.followers[0].name, expected);
+
+        // A name that sanitizes to nothing keeps the current one
+        follower
+            .send(ClientMessage::SetName {
+                name: " \u{1b} ".to_string(),
+                seq: 3,
+            })
+            .await;
+        assert_eq!(ack_status(&follower.drain(), 3), Some(AckStatus::Rejected));
+    }
+
+    #[tokio::test]
+    async fn test_create_session_with_client_slide() {
+        let slide = SlideInfo {
+            id: "external-slide".to_string(),
+            name: "External".to_string(),
+            width: 40000,
+            height: 30000,
+            tile_size: 256,
+            num_levels: 17,
+            tile_url_template: "https://tiles.example.com/{level}/{x}_{y}.jpg".to_string(),
+            mpp_x: Some(0.5),
+            mpp_y: Some(0.5),
+            objective_power: None,
+            has_overlay: false,
+        };
+        let request = |slide: SlideInfo, seq| ClientMessage::CreateSessionWithSlide {
+            slide,
+            max_followers: None,
+            seq,
+        };
+
+        // Disabled unless the deployment opts in
+        let state = AppState::new();
+        let mut conn = FakeConnection::connect(&state);
+        conn.send(request(slide.clone(), 1)).await;
+        assert_eq!(ack_status(&conn.drain(), 1), Some(AckStatus::Rejected));
+
+        // No slide service needed once enabled
+        let state = AppState::new().with_ws_config(WsConfig {
+            allow_client_slides: true,
+            ..WsConfig::default()
+        });
+        let mut conn = FakeConnection::connect(&state);
+        let invalid = SlideInfo {
+            width: 0,
+            ..slide.clone()
+        };
+        conn.send(request(invalid, 1)).await;
+        assert_eq!(ack_status(&conn.drain(), 1), Some(AckStatus::Rejected));
+        assert!(conn.session_id().is_none());
+
+        conn.send(request(slide, 2)).await;
+        let messages = conn.drain();
+        assert_eq!(ack_status(&messages, 2), Some(AckStatus::Ok));
+        let Some(ServerMessage::SessionCreated { session, .. }) = messages.first() else {
+            panic!("Session should be created, got {messages:?}");
+        };
+        assert_eq!(session.slide.id, "external-slide");
+        assert_eq!(session.slide.width, 40000);
+        assert_eq!(conn.session_id(), Some(session.id.clone()));
+    }
+
+    #[tokio::test]
+    async fn test_chat_rejected_when_disabled_for_session() {
+        use crate::session::state::SessionConfig;
+
+        let chat = |seq| ClientMessage::ChatMessage {
+            text: "  Look at the margin  ".to_string(),
+            seq,
+        };
+
+        // Enabled by default: advertised on join and broadcast
+        let state = fixture_state();
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        let features = follower.drain().into_iter().find_map(|m| match m {
+            ServerMessage::SessionJoined { features, .. } => Some(features),
+            _ => None,
+        });
+        assert_eq!(features, Some(vec!["chat".to_string()]));
+        follower.send(chat(2)).await;
+        assert_eq!(ack_status(&follower.drain(), 2), Some(AckStatus::Ok));
+        assert!(presenter.drain().iter().any(
+            |m| matches!(m, ServerMessage::ChatMessage { text, .. } if text == "Look at the margin")
+        ));
+
+        // Disabled for sessions on this server
+        let manager = SessionManager::with_config(SessionConfig {
+            features: Vec::new(),
+            ..Default::default()
+        });
+        let state = fixture_state().with_session_manager(Arc::new(manager));
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        let features = follower.drain().into_iter().find_map(|m| match m {
+            ServerMessage::SessionJoined { features, .. } => Some(features),
+            _ => None,
+        });
+        assert_eq!(features, Some(Vec::new()));
+        follower.send(chat(2)).await;
+        let replies = follower.drain();
+        assert_eq!(ack_status(&replies, 2), Some(AckStatus::Rejected));
+        assert!(replies.iter().any(|m| matches!(
+            m,
+            ServerMessage::SessionError {
+                code: crate::protocol::ErrorCode::FeatureDisabled,
+                ..
+            }
+        )));
+        assert!(
+            !presenter
+                .drain()
+                .iter()
+                .any(|m| matches!(m, ServerMessage::ChatMessage { .. }))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cursor_flood_broadcasts_capped_to_rate() {
+        let state = fixture_state().with_ws_config(WsConfig {
+            max_cursor_hz: 10,
+            ..WsConfig::default()
+        });
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        follower.drain();
+
+        // Bursts of 50 updates every 100ms: 500 Hz against a 10 Hz limit
+        let mut seq = 2;
+        for _ in 0..5 {
+            for _ in 0..50 {
+                presenter
+                    .send(ClientMessage::CursorUpdate {
+                        x: seq as f64,
+                        y: 50.0,
+                        space: CoordinateSpace::SlidePixels,
+                        seq,
+                    })
+                    .await;
+                seq += 1;
+            }
+            tokio::time::advance(Duration::from_millis(100)).await;
+        }
+
+        let broadcasts = follower
+            .drain()
+            .iter()
+            .filter(|m| matches!(m, ServerMessage::PresenceDelta { .. }))
+            .count();
+        // The burst of 2, then one token refilled per 100ms for the four later bursts
+        assert_eq!(broadcasts, 6);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cleanup_task_removes_expired_sessions() {
+        let manager = SessionManager::with_config(crate::session::state::SessionConfig {
+            max_duration: Duration::from_millis(20),
+            ..Default::default()
+        });
+        let state = fixture_state()
+            .with_session_manager(Arc::new(manager))
+            .with_cleanup_interval(Duration::from_millis(50))
+            .with_cleanup_jitter(Duration::from_millis(10));
+        let (mut presenter, _) = FakeConnection::create_session(&state).await;
+        presenter.drain();
+
+        // The first pass runs right away, before the session expires
+        let cleanup = tokio::spawn(state.clone().run_cleanup(None));
+        tokio::task::yield_now().await;
+        assert_eq!(state.session_manager.session_count(), 1);
+
+        // Session expiry is measured on the wall clock, which paused time does not move
+        std::thread::sleep(Duration::from_millis(25));
+
+        // The second pass lands 50-60ms after the first
+        tokio::time::sleep(Duration::from_millis(49)).await;
+        assert_eq!(state.session_manager.session_count(), 1);
+        tokio::time::sleep(Duration::from_millis(12)).await;
+        cleanup.abort();
+
+        assert_eq!(state.session_manager.session_count(), 0);
+        assert!(presenter.drain().iter().any(|m| matches!(
+            m,
+            ServerMessage::SessionEnded {
+                reason: SessionEndReason::Expired
+            }
+        )));
+        assert!(!state.cleanup_stalled());
+    }
+
+    #[tokio::test]
+    async fn test_expired_session_drops_its_broadcaster() {
+        let manager = SessionManager::with_config(crate::session::state::SessionConfig {
+            max_duration: Duration::from_millis(20),
+            ..Default::default()
+        });
+        let state = fixture_state().with_session_manager(Arc::new(manager));
+        let (mut presenter, _follower) = session_with_follower(&state).await;
+        presenter.drain();
+        assert_eq!(state.session_broadcasters.len(), 1);
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        state.cleanup_expired().await;
+
+        assert!(state.session_broadcasters.is_empty());
+        assert!(presenter.drain().iter().any(|m| matches!(
+            m,
+            ServerMessage::SessionEnded {
+                reason: SessionEndReason::Expired
+            }
+        )));
+        assert_eq!(presenter.session_id(), None);
+    }
+
+    #[tokio::test]
+    async fn test_expiry_warning_broadcast_once() {
+        let manager = SessionManager::with_config(crate::session::state::SessionConfig {
+            max_duration: Duration::from_secs(60),
+            expiry_warning: Duration::from_secs(120),
+            ..Default::default()
+        });
+        let state = fixture_state().with_session_manager(Arc::new(manager));
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        presenter.drain();
+        follower.drain();
+
+        state.cleanup_expired().await;
+        for conn in [&mut presenter, &mut follower] {
+            let warnings: Vec<u64> = conn
+                .drain()
+                .into_iter()
+                .filter_map(|m| match m {
+                    ServerMessage::SessionExpiring { in_secs } => Some(in_secs),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(warnings.len(), 1);
+            assert!((59..=60).contains(&warnings[0]), "{warnings:?}");
+        }
+
+        // Warned once per expiry
+        state.cleanup_expired().await;
+        assert!(follower.drain().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_extend_session_pushes_back_expiry() {
+        let manager = SessionManager::with_config(crate::session::state::SessionConfig {
+            max_duration: Duration::from_secs(60),
+            extension: Duration::from_secs(60),
+            max_total_duration: Duration::from_secs(150),
+            ..Default::default()
+        });
+        let state = fixture_state().with_session_manager(Arc::new(manager));
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        presenter.drain();
+        follower.drain();
+        let extended = |messages: Vec<ServerMessage>| {
+            messages.into_iter().find_map(|m| match m {
+                ServerMessage::SessionExtended { expires_at } => Some(expires_at),
+                _ => None,
+            })
+        };
+
+        // Followers cannot extend
+        follower.send(ClientMessage::ExtendSession { seq: 1 }).await;
+        assert_eq!(ack_status(&follower.drain(), 1), Some(AckStatus::Rejected));
+
+        presenter
+            .send(ClientMessage::ExtendSession { seq: 2 })
+            .await;
+        assert_eq!(ack_status(&presenter.drain(), 2), Some(AckStatus::Ok));
+        // Created with 60s to live, extended to 120s
+        let first = extended(follower.drain()).unwrap();
+
+        // Capped at the maximum total duration of 150s
+        presenter
+            .send(ClientMessage::ExtendSession { seq: 3 })
+            .await;
+        assert_eq!(ack_status(&presenter.drain(), 3), Some(AckStatus::Ok));
+        assert_eq!(extended(follower.drain()), Some(first + 30_000));
+        presenter
+            .send(ClientMessage::ExtendSession { seq: 4 })
+            .await;
+        assert_eq!(ack_status(&presenter.drain(), 4), Some(AckStatus::Rejected));
+    }
+
+    #[tokio::test]
+    async fn test_send_to_participant_reaches_only_target() {
+        let state = fixture_state();
+        let (mut presenter, join_secret) = FakeConnection::create_session(&state).await;
+        let session_id = presenter.session_id().unwrap();
+        let mut target = FakeConnection::join(&state, &session_id, &join_secret).await;
+        let mut bystander = FakeConnection::join(&state, &session_id, &join_secret).await;
+        let participant_id = state
+            .connections
+            .get(&target.id)
+            .and_then(|conn| conn.participant_id)
+            .unwrap();
+        for conn in [&mut presenter, &mut target, &mut bystander] {
+            conn.drain();
+        }
+
+        let directed = || ServerMessage::SessionExpiring { in_secs: 42 };
+        assert!(
+            state
+                .send_to_participant(&session_id, participant_id, directed())
+                .await
+        );
+        assert!(matches!(
+            target.drain().as_slice(),
+            [ServerMessage::SessionExpiring { in_secs: 42 }]
+        ));
+        assert!(presenter.drain().is_empty());
+        assert!(bystander.drain().is_empty());
+
+        // Unknown participant, or a participant of another session
+        assert!(
+            !state
+                .send_to_participant(&session_id, Uuid::new_v4(), directed())
+                .await
+        );
+        assert!(
+            !state
+                .send_to_participant("othersessn", participant_id, directed())
+                .await
+        );
+        assert!(target.drain().is_empty());
+
+        // Nor once the participant has left
+        target.send(ClientMessage::LeaveSession { seq: 2 }).await;
+        assert!(
+            !state
+                .send_to_participant(&session_id, participant_id, directed())
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snap_rejected_without_presenter() {
+        let state = fixture_state();
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        follower.drain();
+
+        follower
+            .send(ClientMessage::SnapToPresenter { seq: 2 })
+            .await;
+        let replies = follower.drain();
+        assert_eq!(ack_status(&replies, 2), Some(AckStatus::Ok));
+        assert!(
+            replies
+                .iter()
+                .any(|m| matches!(m, ServerMessage::PresenterViewport { .. }))
+        );
+
+        presenter.send(ClientMessage::LeaveSession { seq: 2 }).await;
+        follower.drain();
+        follower
+            .send(ClientMessage::SnapToPresenter { seq: 3 })
+            .await;
+        let replies = follower.drain();
+        assert_eq!(ack_status(&replies, 3), Some(AckStatus::Rejected));
+        assert!(replies.iter().any(|m| matches!(
+            m,
+            ServerMessage::SessionError {
+                code: crate::protocol::ErrorCode::NoPresenter,
+                ..
+            }
+        )));
+        assert!(
+            !replies
+                .iter()
+                .any(|m| matches!(m, ServerMessage::PresenterViewport { .. }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_presenter_reauth_restores_snap() {
+        let manager = SessionManager::with_config(crate::session::state::SessionConfig {
+            presenter_grace_period: Duration::from_millis(30),
+            ..Default::default()
+        });
+        let state = fixture_state().with_session_manager(Arc::new(manager));
+        let mut presenter = FakeConnection::connect(&state);
+        presenter
+            .send(ClientMessage::CreateSession {
+                slide_id: FIXTURE_SLIDE_ID.to_string(),
+                max_followers: None,
+                seq: 1,
+            })
+            .await;
+        let Some(ServerMessage::SessionCreated {
+            session,
+            join_secret,
+            presenter_key,
+            ..
+        }) = presenter.drain().into_iter().next()
+        else {
+            panic!("Session should be created");
+        };
+        let mut follower = FakeConnection::join(&state, &session.id, &join_secret).await;
+
+        presenter.send(ClientMessage::LeaveSession { seq: 2 }).await;
+        let mut returning = FakeConnection::join(&state, &session.id, &join_secret).await;
+        returning
+            .send(ClientMessage::PresenterAuth {
+                presenter_key,
+                seq: 2,
+            })
+            .await;
+        assert_eq!(ack_status(&returning.drain(), 2), Some(AckStatus::Ok));
+
+        follower.drain();
+        follower
+            .send(ClientMessage::SnapToPresenter { seq: 2 })
+            .await;
+        let replies = follower.drain();
+        assert_eq!(ack_status(&replies, 2), Some(AckStatus::Ok));
+        assert!(
+            replies
+                .iter()
+                .any(|m| matches!(m, ServerMessage::PresenterViewport { .. }))
+        );
+
+        // Survives past the grace period that started at the disconnect
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        state.cleanup_expired().await;
+        assert_eq!(follower.session_id(), Some(session.id.clone()));
+        assert!(state.session_manager.get_session(&session.id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cursor_rate_limit_applies_when_batching() {
+        let state = fixture_state()
+            .with_ws_config(WsConfig {
+                max_cursor_hz: 10,
+                ..WsConfig::default()
+            })
+            .with_presence_batcher(Arc::new(crate::server::presence::PresenceBatcher::new()));
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        follower.drain();
+
+        // 50 updates at once against a burst of 2
+        for seq in 1..=50 {
+            presenter
+                .send(ClientMessage::CursorUpdate {
+                    x: seq as f64,
+                    y: 50.0,
+                    space: CoordinateSpace::SlidePixels,
+                    seq,
+                })
+                .await;
+        }
+        state.flush_presence().await;
+
+        let xs: Vec<f64> = follower
+            .drain()
+            .into_iter()
+            .filter_map(|m| match m {
+                ServerMessage::PresenceDelta { changed, .. } => Some(changed),
+                _ => None,
+            })
+            .flatten()
+            .map(|c| c.x)
+            .collect();
+        // Throttled updates never reach the batcher
+        assert_eq!(xs, vec![2.0]);
+    }
+
+    #[tokio::test]
+    async fn test_viewport_updates_coalesced_per_tick() {
+        let state = fixture_state().with_viewport_batcher(Arc::new(ViewportBatcher::new()));
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        follower.drain();
+        let session_id = presenter.session_id().unwrap();
+        let rev_before = state
+            .session_manager
+            .get_session(&session_id)
+            .await
+            .unwrap()
+            .rev;
+
+        for seq in 1..=30 {
+            presenter
+                .send(ClientMessage::ViewportUpdate {
+                    center_x: seq as f64 / 100.0,
+                    center_y: 0.5,
+                    zoom: 2.0,
+                    transition_ms: None,
+                    seq,
+                })
+                .await;
+        }
+        assert!(follower.drain().is_empty());
+        state.flush_viewports().await;
+
+        let viewports: Vec<Viewport> = follower
+            .drain()
+            .into_iter()
+            .filter_map(|m| match m {
+                ServerMessage::PresenterViewport { viewport, .. } => Some(viewport),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(viewports.len(), 1);
+        assert_eq!(viewports[0].center_x, 0.3);
+        let session = state
+            .session_manager
+            .get_session(&session_id)
+            .await
+            .unwrap();
+        assert_eq!(session.rev, rev_before + 1);
+        assert_eq!(session.presenter_viewport.center_x, 0.3);
+    }
+
+    #[tokio::test]
+    async fn test_viewport_transition_hint_forwarded() {
+        let state = fixture_state();
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        follower.drain();
+
+        for (seq, transition_ms) in [(1, Some(0)), (2, Some(750)), (3, None)] {
+            presenter
+                .send(ClientMessage::ViewportUpdate {
+                    center_x: 0.5,
+                    center_y: 0.5,
+                    zoom: seq as f64,
+                    transition_ms,
+                    seq,
+                })
+                .await;
+        }
+
+        let hints: Vec<u32> = follower
+            .drain()
+            .into_iter()
+            .filter_map(|m| match m {
+                ServerMessage::PresenterViewport { transition_ms, .. } => Some(transition_ms),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(hints, vec![0, 750, DEFAULT_VIEWPORT_TRANSITION_MS]);
+    }
+
+    /// Audit sink keeping records in memory
+    #[derive(Default)]
+    struct MemoryAudit(Mutex<Vec<crate::audit::AuditRecord>>);
+
+    impl AuditSink for MemoryAudit {
+        fn record(&self, record: &crate::audit::AuditRecord) {
+            self.0.lock().unwrap().push(record.clone());
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_slide_change_pending_then_applied() {
+        let audit = Arc::new(MemoryAudit::default());
+        let state = fixture_state()
+            .with_slide_change_delay(Duration::from_millis(1500))
+            .with_audit_sink(audit.clone());
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        follower.drain();
+
+        presenter
+            .send(ClientMessage::ChangeSlide {
+                slide_id: FIXTURE_SLIDE_ID.to_string(),
+                seq: 2,
+            })
+            .await;
+        assert_eq!(ack_status(&presenter.drain(), 2), Some(AckStatus::Ok));
+
+        // Sub-second remainders are rounded up
+        let announced = follower.drain();
+        assert!(matches!(
+            announced.as_slice(),
+            [ServerMessage::SlideChangePending { slide, in_secs: 2 }] if slide.id == FIXTURE_SLIDE_ID
+        ));
+        assert!(audit.0.lock().unwrap().is_empty());
+
+        // Time is paused: the runtime jumps to the pending change's deadline
+        tokio::time::sleep(Duration::from_millis(1501)).await;
+        assert!(
+            follower
+                .drain()
+                .iter()
+                .any(|m| matches!(m, ServerMessage::SlideChanged { .. }))
+        );
+        let records = audit.0.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0].action,
+            AuditAction::SlideChange {
+                slide_id: FIXTURE_SLIDE_ID.to_string()
+            }
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cancelled_slide_change_is_not_applied() {
+        let audit = Arc::new(MemoryAudit::default());
+        let state = fixture_state()
+            .with_slide_change_delay(Duration::from_secs(1))
+            .with_audit_sink(audit.clone());
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        let session_id = presenter.session_id().unwrap();
+        let rev_before = state
+            .session_manager
+            .get_session(&session_id)
+            .await
+            .unwrap()
+            .rev;
+
+        presenter
+            .send(ClientMessage::ChangeSlide {
+                slide_id: FIXTURE_SLIDE_ID.to_string(),
+                seq: 2,
+            })
+            .await;
+        presenter
+            .send(ClientMessage::CancelSlideChange { seq: 3 })
+            .await;
+        assert_eq!(ack_status(&presenter.drain(), 3), Some(AckStatus::Ok));
+
+        // Nothing left to cancel
+        presenter
+            .send(ClientMessage::CancelSlideChange { seq: 4 })
+            .await;
+        assert_eq!(ack_status(&presenter.drain(), 4), Some(AckStatus::Rejected));
+
+        tokio::time::sleep(Duration::from_millis(1001)).await;
+        let received = follower.drain();
+        assert!(
+            received
+                .iter()
+                .any(|m| matches!(m, ServerMessage::SlideChangeCancelled))
+        );
+        assert!(
+            !received
+                .iter()
+                .any(|m| matches!(m, ServerMessage::SlideChanged { .. }))
+        );
+        assert!(audit.0.lock().unwrap().is_empty());
+        let session = state
+            .session_manager
+            .get_session(&session_id)
+            .await
+            .unwrap();
+        assert_eq!(session.rev, rev_before);
+    }
+
+    #[tokio::test]
+    async fn test_follower_leaves_and_joins_another_session() {
+        let state = fixture_state();
+        let (mut first_presenter, mut follower) = session_with_follower(&state).await;
+        let first_id = first_presenter.session_id().unwrap();
+        let participant_id = state.connections.get(&follower.id).unwrap().participant_id;
+        first_presenter.drain();
+        follower.drain();
+
+        follower.send(ClientMessage::LeaveSession { seq: 2 }).await;
+        assert_eq!(ack_status(&follower.drain(), 2), Some(AckStatus::Ok));
+        assert_eq!(follower.session_id(), None);
+        assert!(first_presenter.drain().iter().any(|m| matches!(
+            m,
+            ServerMessage::ParticipantLeft { participant_id: left } if Some(*left) == participant_id
+        )));
+        let first = state.session_manager.get_session(&first_id).await.unwrap();
+        assert!(first.followers.is_empty());
+
+        // Leaving twice is rejected
+        follower.send(ClientMessage::LeaveSession { seq: 3 }).await;
+        assert_eq!(ack_status(&follower.drain(), 3), Some(AckStatus::Rejected));
+
+        let (second_presenter, join_secret) = FakeConnection::create_session(&state).await;
+        let second_id = second_presenter.session_id().unwrap();
+        follower
+            .send(ClientMessage::JoinSession {
+                session_id: second_id.clone(),
+                join_secret,
+                last_seen_rev: None,
+                reconnect_token: None,
+                seq: 4,
+            })
+            .await;
+        assert_eq!(ack_status(&follower.drain(), 4), Some(AckStatus::Ok));
+        assert_eq!(follower.session_id(), Some(second_id));
+    }
+
+    #[tokio::test]
+    async fn test_slide_catalog_over_websocket() {
+        let state = fixture_state();
+        let mut conn = FakeConnection::connect(&state);
+
+        conn.send(ClientMessage::ListSlides { seq: 1 }).await;
+        let replies = conn.drain();
+        assert_eq!(ack_status(&replies, 1), Some(AckStatus::Ok));
+        let Some(ServerMessage::SlideList {
+            request_seq: 1,
+            slides,
+        }) = replies.first()
+        else {
+            panic!("Expected slide_list, got {replies:?}");
+        };
+        let ids: Vec<&str> = slides.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec![FIXTURE_SLIDE_ID]);
+
+        conn.send(ClientMessage::GetSlide {
+            slide_id: FIXTURE_SLIDE_ID.to_string(),
+            seq: 2,
+        })
+        .await;
+        let replies = conn.drain();
+        assert_eq!(ack_status(&replies, 2), Some(AckStatus::Ok));
+        assert!(matches!(
+            replies.first(),
+            Some(ServerMessage::SlideMetadata { request_seq: 2, slide })
+                if slide.width == 10000 && slide.mpp_x == Some(0.25)
+        ));
+
+        conn.send(ClientMessage::GetSlide {
+            slide_id: "missing".to_string(),
+            seq: 3,
+        })
+        .await;
+        let replies = conn.drain();
+        assert_eq!(ack_status(&replies, 3), Some(AckStatus::Rejected));
+        assert_eq!(replies.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cursors_batched_into_one_delta_per_tick() {
+        let state = fixture_state()
+            .with_presence_batcher(Arc::new(crate::server::presence::PresenceBatcher::new()));
+        let (mut presenter, join_secret) = FakeConnection::create_session(&state).await;
+        let session_id = presenter.session_id().unwrap();
+
+        let mut followers = Vec::new();
+        for _ in 0..2 {
+            followers.push(FakeConnection::join(&state, &session_id, &join_secret).await);
+        }
+        presenter.drain();
+
+        // Three cursors move, one of them twice, within a single tick
+        let mut movers: Vec<&mut FakeConnection> = followers.iter_mut().collect();
+        movers.push(&mut presenter);
+        for (i, conn) in movers.into_iter().enumerate() {
+            for step in 0..2 {
+                conn.send(ClientMessage::CursorUpdate {
+                    x: (i * 100 + step) as f64,
+                    y: 50.0,
+                    space: CoordinateSpace::SlidePixels,
+                    seq: 2 + step as u64,
+                })
+                .await;
+            }
+        }
+        let deltas = |messages: Vec<ServerMessage>| -> Vec<Vec<CursorWithParticipant>> {
+            messages
+                .into_iter()
+                .filter_map(|m| match m {
+                    ServerMessage::PresenceDelta { changed, .. } => Some(changed),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert!(deltas(presenter.drain()).is_empty());
+
+        state.flush_presence().await;
+        let deltas = deltas(presenter.drain());
+        assert_eq!(deltas.len(), 1);
+        let mut xs: Vec<f64> = deltas[0].iter().map(|c| c.x).collect();
+        xs.sort_by(f64::total_cmp);
+        // Only the latest position per participant
+        assert_eq!(xs, vec![1.0, 101.0, 201.0]);
+
+        // Nothing pending: the next tick is silent
+        state.flush_presence().await;
+        assert!(presenter.drain().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cursor_spaces_broadcast_in_slide_pixels() {
+        let state = fixture_state();
+        let (mut presenter, mut follower) = session_with_follower(&state).await;
+        presenter.drain();
+
+        let broadcast_positions = |messages: Vec<ServerMessage>| -> Vec<(f64, f64)> {
+            messages
+                .into_iter()
+                .filter_map(|m| match m {
+                    ServerMessage::PresenceDelta { changed, .. } => Some(changed),
+                    _ => None,
+                })
+                .flatten()
+                .map(|c| {
+                    assert_eq!(c.space, CoordinateSpace::SlidePixels);
+                    (c.x, c.y)
+                })
+                .collect()
+        };
+
+        // Fixture slide is 10000x10000
+        follower
+            .send(ClientMessage::CursorUpdate {
+                x: 0.25,
+                y: 0.5,
+                space: CoordinateSpace::Normalized,
+                seq: 2,
+            })
+            .await;
+        presenter
+            .send(ClientMessage::CursorUpdate {
+                x: 2500.0,
+                y: 5000.0,
+                space: CoordinateSpace::SlidePixels,
+                seq: 2,
+            })
+            .await;
+        assert_eq!(
+            broadcast_positions(presenter.drain()),
+            vec![(2500.0, 5000.0), (2500.0, 5000.0)]
+        );
+
+        // Normalized positions off the slide are dropped
+        follower
+            .send(ClientMessage::CursorUpdate {
+                x: 1.5,
+                y: 0.5,
+                space: CoordinateSpace::Normalized,
+                seq: 3,
+            })
+            .await;
+        assert!(broadcast_positions(presenter.drain()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_demo_session_is_joinable() {
+        let state = fixture_state();
+        assert!(
+            state
+                .ensure_demo_session(FIXTURE_SLIDE_ID, "demo-secret")
+                .await
+        );
+        assert!(
+            !state
+                .ensure_demo_session(FIXTURE_SLIDE_ID, "demo-secret")
+                .await
+        );
+
+        let mut visitor = FakeConnection::connect(&state);
+        visitor
+            .send(ClientMessage::JoinSession {
+                session_id: DEMO_SESSION_ID.to_string(),
+                join_secret: "demo-secret".to_string(),
+                last_seen_rev: None,
+                reconnect_token: None,
+                seq: 1,
+            })
+            .await;
+
+        let messages = visitor.drain();
+        assert_eq!(ack_status(&messages, 1), Some(AckStatus::Ok));
+        let Some(ServerMessage::SessionJoined { session, .. }) = messages.first() else {
+            panic!("Visitor should join the demo session, got {messages:?}");
+        };
+        assert_eq!(session.slide.id, FIXTURE_SLIDE_ID);
+    }
+
+    #[test]
+    fn test_parse_errors_counted_by_category() {
+        let frames = [
+            "not json at all",
+            r#"{"type": "cursor_update", "x": 1.0"#,
+            r#"{"type": "teleport", "seq": 1}"#,
+            r#"{"seq": 1}"#,
+            r#"{"type": "cursor_update", "x": "left", "y": 2.0, "seq": 1}"#,
+            r#"{"type": "ping"}"#,
+        ];
+
+        let recorder = CategoryRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            for frame in frames {
+                let err = serde_json::from_str::<ClientMessage>(frame).unwrap_err();
+                record_parse_error(&err);
+            }
+        });
+
+        assert_eq!(recorder.count("not_json"), 2);
+        assert_eq!(recorder.count("unknown_type"), 2);
+        assert_eq!(recorder.count("bad_field"), 2);
+    }
+
+    #[test]
+    fn test_unknown_fields_ignored() {
+        let frame = r#"{
+            "type": "viewport_update",
+            "center_x": 0.5,
+            "center_y": 0.5,
+            "zoom": 2.0,
+            "rotation": 90,
+            "transition_ms": null,
+            "seq": 3,
+            "client": {"build": "2.1.0"}
+        }"#;
+
+        // Lenient by default: the extra fields do not prevent parsing
+        for report_unknown in [false, true] {
+            let msg = parse_client_message(frame, report_unknown).unwrap();
+            assert!(matches!(
+                msg,
+                ClientMessage::ViewportUpdate {
+                    zoom: 2.0,
+                    seq: 3,
+                    ..
+                }
+            ));
+        }
+
+        let msg = serde_json::from_str::<ClientMessage>(frame).unwrap();
+        assert_eq!(unknown_fields(frame, &msg), ["client", "rotation"]);
+
+        let known = r#"{"type": "snap_to_presenter", "seq": 1}"#;
+        let msg = serde_json::from_str::<ClientMessage>(known).unwrap();
+        assert!(unknown_fields(known, &msg).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_metrics_labelled_by_type() {
+        let state = fixture_state();
+        let (mut presenter, _follower) = session_with_follower(&state).await;
+
+        let recorder = TypeRecorder::default();
+        let _guard = metrics::set_default_local_recorder(&recorder);
+        presenter
+            .send(ClientMessage::CursorUpdate {
+                x: 10.0,
+                y: 20.0,
+                space: CoordinateSpace::SlidePixels,
+                seq: 2,
+            })
+            .await;
+        presenter
+            .send(ClientMessage::ViewportUpdate {
+                center_x: 0.5,
+                center_y: 0.5,
+                zoom: 2.0,
+                transition_ms: None,
+                seq: 3,
+            })
+            .await;
+
+        for msg_type in ["presence_delta", "presenter_viewport"] {
+            // Presenter and follower are both subscribed
+            assert_eq!(
+                recorder.samples("pathcollab_ws_broadcast_recipients", msg_type),
+                vec![2.0],
+                "{msg_type}"
+            );
+            let bytes = recorder.samples("pathcollab_ws_broadcast_bytes", msg_type);
+            assert_eq!(bytes.len(), 1, "{msg_type}");
+            assert!(bytes[0] > 0.0, "{msg_type}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_span_carries_session_id() {
+        let logs = crate::logging::CapturedLogs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(logs.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let state = fixture_state();
+        let (presenter, join_secret) = FakeConnection::create_session(&state).await;
+        let session_id = presenter.session_id().unwrap();
+
+        let mut follower = FakeConnection::connect(&state);
+        let span = connection_span(follower.id);
+        follower
+            .send(ClientMessage::JoinSession {
+                session_id: session_id.clone(),
+                join_secret,
+                last_seen_rev: None,
+                reconnect_token: None,
+                seq: 1,
+            })
+            .instrument(span)
+            .await;
+
+        let lines = logs.lines();
+        let joined = lines
+            .iter()
+            .find(|line| line.contains("websocket: Participant"))
+            .expect("Join should be logged");
+        assert!(joined.contains(&format!("connection_id={}", follower.id)));
+        assert!(joined.contains(&format!("session_id={}", session_id)));
+        let participant_id = state.connections.get(&follower.id).unwrap().participant_id;
+        assert!(joined.contains(&format!("participant_id={}", participant_id.unwrap())));
+    }
+
+    #[tokio::test]
+    async fn test_connection_span_cleared_on_leave() {
+        let logs = crate::logging::CapturedLogs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(logs.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let state = fixture_state();
+        let (presenter, join_secret) = FakeConnection::create_session(&state).await;
+        let session_id = presenter.session_id().unwrap();
+
+        let mut follower = FakeConnection::connect(&state);
+        let span = connection_span(follower.id);
+        follower
+            .send(ClientMessage::JoinSession {
+                session_id,
+                join_secret,
+                last_seen_rev: None,
+                reconnect_token: None,
+                seq: 1,
+            })
+            .instrument(span.clone())
+            .await;
+        follower
+            .send(ClientMessage::LeaveSession { seq: 2 })
+            .instrument(span.clone())
+            .await;
+        span.in_scope(|| info!("after leave"));
+
+        let lines = logs.lines();
+        let after = lines
+            .iter()
+            .find(|line| line.contains("after leave"))
+            .expect("Event should be logged");
+        assert!(after.contains(r#""session_id":"""#), "{after}");
+        assert!(after.contains(r#""participant_id":"""#), "{after}");
+    }
 }
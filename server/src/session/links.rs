@@ -0,0 +1,70 @@
+//! Session link construction
+//!
+//! Links mirror the web client's routes: `/s/{id}` opens a session, and the
+//! join secret / presenter key travel in the URL fragment so they are never
+//! sent to the server in HTTP requests.
+
+/// Links returned to the creator of a session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionLinks {
+    /// Link followers use to join (includes the join secret)
+    pub join_url: String,
+    /// Link to the session page without credentials
+    pub viewer_url: String,
+    /// Link that also reclaims the presenter role (includes the presenter key)
+    pub presenter_url: String,
+}
+
+impl SessionLinks {
+    /// Build links for a session. Links are absolute when `public_base_url` is
+    /// set and relative to the site root otherwise.
+    pub fn build(
+        public_base_url: Option<&str>,
+        session_id: &str,
+        join_secret: &str,
+        presenter_key: &str,
+    ) -> Self {
+        let base = public_base_url.unwrap_or("").trim_end_matches('/');
+        let viewer_url = format!("{}/s/{}", base, session_id);
+        Self {
+            join_url: format!("{}#join={}", viewer_url, join_secret),
+            presenter_url: format!(
+                "{}#join={}&presenter={}",
+                viewer_url, join_secret, presenter_key
+            ),
+            viewer_url,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links_absolute_with_base_url() {
+        let links = SessionLinks::build(
+            Some("https://pathcollab.example.com/"),
+            "abc123",
+            "secret",
+            "key",
+        );
+        assert_eq!(
+            links.join_url,
+            "https://pathcollab.example.com/s/abc123#join=secret"
+        );
+        assert_eq!(links.viewer_url, "https://pathcollab.example.com/s/abc123");
+        assert_eq!(
+            links.presenter_url,
+            "https://pathcollab.example.com/s/abc123#join=secret&presenter=key"
+        );
+    }
+
+    #[test]
+    fn test_links_relative_without_base_url() {
+        let links = SessionLinks::build(None, "abc123", "secret", "key");
+        assert_eq!(links.join_url, "/s/abc123#join=secret");
+        assert_eq!(links.viewer_url, "/s/abc123");
+        assert_eq!(links.presenter_url, "/s/abc123#join=secret&presenter=key");
+    }
+}
@@ -1,15 +1,18 @@
 use crate::protocol::{
-    CellOverlayState, Participant, ParticipantRole, SessionSnapshot, SlideInfo, TissueOverlayState,
-    Viewport,
+    CellOverlayState, CoordinateSpace, FEATURE_CHAT, LayerPatch, Participant, ParticipantRole,
+    SessionEndReason, SessionSnapshot, SlideInfo, TissueOverlayState, Viewport,
 };
 use crate::session::state::{
-    Session, SessionConfig, SessionId, SessionParticipant, SessionState, generate_participant_name,
-    generate_secret, generate_session_id, get_participant_color, now_millis,
+    DEMO_SESSION_ID, IdentityGenerator, MAX_CHAT_MESSAGE_LEN, RandomIdentityGenerator, Session,
+    SessionConfig, SessionId, SessionParticipant, SessionState, generate_secret,
+    generate_session_id, now_millis, sanitize_participant_name,
 };
+use crate::session::stats::{CompletedSession, RecentSessions, SessionLifecycleStats};
 use dashmap::DashMap;
 use metrics::{counter, histogram};
-use std::collections::HashMap;
-use std::time::Instant;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
@@ -40,46 +43,184 @@ pub enum SessionError {
 
     #[error("Participant not found: {0}")]
     ParticipantNotFound(Uuid),
+
+    #[error("Server is at capacity (max {0} sessions)")]
+    ServerAtCapacity(usize),
+
+    #[error("Cursor position is outside the slide")]
+    InvalidCursor,
+
+    #[error("Name is empty")]
+    InvalidName,
+
+    #[error("Feature {0:?} is disabled for this session")]
+    FeatureDisabled(&'static str),
+
+    #[error("Chat message must be 1 to {MAX_CHAT_MESSAGE_LEN} characters")]
+    InvalidChatMessage,
+
+    #[error("Only reconnecting participants may join right now")]
+    ReconnectRequired,
+
+    #[error("Session has reached its maximum duration")]
+    ExtensionLimitReached,
+
+    #[error("No presenter is connected")]
+    NoPresenter,
+}
+
+/// State handed to a joining client
+#[derive(Debug, Clone)]
+pub enum JoinSnapshot {
+    /// Full session state (first join, or the client's revision is stale)
+    Full(Box<SessionSnapshot>),
+    /// The client's content is current: only the new revision and the
+    /// participants (which may have changed while it was away) are sent
+    Unchanged {
+        rev: u64,
+        presenter: Participant,
+        followers: Vec<Participant>,
+    },
+}
+
+/// A follower admitted by `join_session_inner`
+struct Admitted {
+    snapshot: SessionSnapshot,
+    participant: Participant,
+    content_rev: u64,
+    reconnect_token: String,
 }
 
 /// Session manager: handles all session CRUD operations
 pub struct SessionManager {
     sessions: DashMap<SessionId, Session>,
     config: SessionConfig,
+    identity: Arc<dyn IdentityGenerator>,
+    recent: RecentSessions,
+    /// Held across the capacity check and insert of a new session, so
+    /// concurrent creations can't overshoot `max_sessions`
+    create_lock: Mutex<()>,
 }
 
 impl SessionManager {
     pub fn new() -> Self {
-        Self {
-            sessions: DashMap::new(),
-            config: SessionConfig::default(),
-        }
+        Self::with_config(SessionConfig::default())
     }
 
     pub fn with_config(config: SessionConfig) -> Self {
         Self {
             sessions: DashMap::new(),
             config,
+            identity: Arc::new(RandomIdentityGenerator),
+            recent: RecentSessions::default(),
+            create_lock: Mutex::new(()),
         }
     }
 
+    /// Replace the participant name/color generator
+    pub fn with_identity_generator(mut self, generator: Arc<dyn IdentityGenerator>) -> Self {
+        self.identity = generator;
+        self
+    }
+
     /// Create a new session
     pub async fn create_session(
         &self,
         slide: SlideInfo,
         presenter_connection_id: Uuid,
+    ) -> Result<(Session, String, String), SessionError> {
+        self.create_session_with_max_followers(slide, presenter_connection_id, None)
+            .await
+    }
+
+    /// Create a new session with its own follower limit. The limit is clamped
+    /// to the server-wide cap; `None` uses the configured default.
+    pub async fn create_session_with_max_followers(
+        &self,
+        slide: SlideInfo,
+        presenter_connection_id: Uuid,
+        max_followers: Option<usize>,
     ) -> Result<(Session, String, String), SessionError> {
         let start = Instant::now();
-        counter!("pathcollab_sessions_created_total").increment(1);
 
         let session_id = generate_session_id();
         let join_secret = generate_secret(128);
         let presenter_key = generate_secret(192);
 
         // Hash secrets (simple hash for now - use argon2 in production)
-        let join_secret_hash = hash_secret(&join_secret);
-        let presenter_key_hash = hash_secret(&presenter_key);
+        let session = self.build_session(
+            session_id.clone(),
+            hash_secret(&join_secret),
+            hash_secret(&presenter_key),
+            slide,
+            max_followers
+                .unwrap_or(self.config.max_followers)
+                .min(self.config.max_followers_cap),
+        );
+
+        {
+            let _guard = self
+                .create_lock
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if self.sessions.len() >= self.config.max_sessions {
+                counter!("pathcollab_sessions_rejected_total").increment(1);
+                warn!(
+                    "Rejecting session for {}: server at capacity ({} sessions)",
+                    presenter_connection_id, self.config.max_sessions
+                );
+                return Err(SessionError::ServerAtCapacity(self.config.max_sessions));
+            }
+            self.sessions.insert(session_id.clone(), session.clone());
+        }
+
+        counter!("pathcollab_sessions_created_total").increment(1);
+        info!(
+            "Created session {} for presenter {}",
+            session_id, presenter_connection_id
+        );
+
+        histogram!("pathcollab_session_create_duration_seconds").record(start.elapsed());
+        Ok((session, join_secret, presenter_key))
+    }
+
+    /// Create the demo session under [`DEMO_SESSION_ID`] with a well-known
+    /// join secret, unless a live one exists. The demo session has no presenter
+    /// connection, so it is never reaped for a missing presenter; once it
+    /// expires, the next call re-creates it. Returns whether one was created.
+    pub fn ensure_demo_session(&self, slide: SlideInfo, join_secret: &str) -> bool {
+        let now = now_millis();
+        if self
+            .sessions
+            .get(DEMO_SESSION_ID)
+            .is_some_and(|s| s.expires_at >= now && !matches!(s.state, SessionState::Expired))
+        {
+            return false;
+        }
 
+        // Nobody is handed the presenter key: the demo runs without a presenter
+        let session = self.build_session(
+            DEMO_SESSION_ID.to_string(),
+            hash_secret(join_secret),
+            hash_secret(&generate_secret(192)),
+            slide,
+            self.config.max_followers_cap,
+        );
+        self.sessions.insert(DEMO_SESSION_ID.to_string(), session);
+        counter!("pathcollab_demo_sessions_created_total").increment(1);
+        info!("Created demo session {}", DEMO_SESSION_ID);
+        true
+    }
+
+    /// A fresh active session with a presenter participant
+    fn build_session(
+        &self,
+        session_id: SessionId,
+        join_secret_hash: String,
+        presenter_key_hash: String,
+        slide: SlideInfo,
+        max_followers: usize,
+    ) -> Session {
         let now = now_millis();
         let expires_at = now + self.config.max_duration.as_millis() as u64;
 
@@ -87,8 +228,8 @@ impl SessionManager {
         let presenter_id = Uuid::new_v4();
         let presenter = SessionParticipant {
             id: presenter_id,
-            name: generate_participant_name(),
-            color: get_participant_color(0).to_string(),
+            name: self.identity.name(),
+            color: self.identity.color(0),
             role: ParticipantRole::Presenter,
             connected_at: now,
             last_seen_at: now,
@@ -100,17 +241,20 @@ impl SessionManager {
         let mut participants = HashMap::new();
         participants.insert(presenter_id, presenter);
 
-        let session = Session {
-            id: session_id.clone(),
+        Session {
+            id: session_id,
             rev: 1,
+            content_rev: 1,
             join_secret_hash,
             presenter_key_hash,
             locked: false,
             created_at: now,
             expires_at,
+            expiry_warned: false,
             state: SessionState::Active,
             presenter_id,
             participants,
+            reconnect_token_hashes: HashSet::new(),
             slide,
             presenter_viewport: Viewport {
                 center_x: 0.5,
@@ -118,19 +262,27 @@ impl SessionManager {
                 zoom: 1.0,
                 timestamp: now,
             },
+            viewport_history: VecDeque::new(),
             cell_overlay: None,
             tissue_overlay: None,
-        };
-
-        info!(
-            "Created session {} for presenter {}",
-            session_id, presenter_connection_id
-        );
+            follower_cursors_visible: true,
+            peak_followers: 0,
+            max_followers,
+            features: self.config.features.clone(),
+        }
+    }
 
-        self.sessions.insert(session_id.clone(), session.clone());
+    /// Optional features enabled for new sessions
+    pub fn features(&self) -> &[String] {
+        &self.config.features
+    }
 
-        histogram!("pathcollab_session_create_duration_seconds").record(start.elapsed());
-        Ok((session, join_secret, presenter_key))
+    /// Optional features enabled for a session
+    pub fn session_features(&self, session_id: &str) -> Result<Vec<String>, SessionError> {
+        self.sessions
+            .get(session_id)
+            .map(|session| session.features.clone())
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))
     }
 
     /// Join an existing session
@@ -139,6 +291,65 @@ impl SessionManager {
         session_id: &str,
         join_secret: &str,
     ) -> Result<(SessionSnapshot, Participant), SessionError> {
+        let admitted = self
+            .join_session_inner(session_id, join_secret, None, false)
+            .await?;
+        Ok((admitted.snapshot, admitted.participant))
+    }
+
+    /// Join an existing session, skipping the snapshot if the client is up to date
+    ///
+    /// The client is up to date when `last_seen_rev` is no older than the last
+    /// content change. Membership changes, including the client's own leave on
+    /// disconnect, don't count, so a reconnecting client that missed only
+    /// joins and leaves gets the new revision and participant list instead of
+    /// state it already holds.
+    ///
+    /// `reconnect_token` is the token issued on the client's previous join; it
+    /// is redeemed here and a fresh one returned. With `reconnect_only` set
+    /// (while the server drains), joins without a valid token are refused.
+    pub async fn rejoin_session(
+        &self,
+        session_id: &str,
+        join_secret: &str,
+        last_seen_rev: Option<u64>,
+        reconnect_token: Option<&str>,
+        reconnect_only: bool,
+    ) -> Result<(JoinSnapshot, Participant, String), SessionError> {
+        let Admitted {
+            snapshot,
+            participant,
+            content_rev,
+            reconnect_token,
+        } = self
+            .join_session_inner(session_id, join_secret, reconnect_token, reconnect_only)
+            .await?;
+
+        if last_seen_rev.is_some_and(|seen| (content_rev..snapshot.rev).contains(&seen)) {
+            counter!("pathcollab_session_rejoins_unchanged_total").increment(1);
+            let unchanged = JoinSnapshot::Unchanged {
+                rev: snapshot.rev,
+                presenter: snapshot.presenter,
+                followers: snapshot.followers,
+            };
+            return Ok((unchanged, participant, reconnect_token));
+        }
+
+        Ok((
+            JoinSnapshot::Full(Box::new(snapshot)),
+            participant,
+            reconnect_token,
+        ))
+    }
+
+    /// Add a follower to a session, redeeming `reconnect_token` if it is valid
+    async fn join_session_inner(
+        &self,
+        session_id: &str,
+        join_secret: &str,
+        reconnect_token: Option<&str>,
+        reconnect_only: bool,
+    ) -> Result<Admitted, SessionError> {
         let start = Instant::now();
         counter!("pathcollab_session_joins_total").increment(1);
 
@@ -162,15 +373,31 @@ impl SessionManager {
             return Err(SessionError::InvalidJoinSecret);
         }
 
+        let redeemed_hash = reconnect_token
+            .map(hash_secret)
+            .filter(|hash| session.reconnect_token_hashes.contains(hash));
+        if reconnect_only && redeemed_hash.is_none() {
+            return Err(SessionError::ReconnectRequired);
+        }
+
         // Check if session is full
         let follower_count = session
             .participants
             .values()
             .filter(|p| p.role == ParticipantRole::Follower)
             .count();
-        if follower_count >= self.config.max_followers {
-            return Err(SessionError::SessionFull(self.config.max_followers));
+        if follower_count >= session.max_followers {
+            return Err(SessionError::SessionFull(session.max_followers));
+        }
+
+        // Tokens are single use: swap the redeemed one for a fresh one
+        if let Some(hash) = redeemed_hash {
+            session.reconnect_token_hashes.remove(&hash);
         }
+        let reconnect_token = generate_secret(128);
+        session
+            .reconnect_token_hashes
+            .insert(hash_secret(&reconnect_token));
 
         // Create new follower
         let now = now_millis();
@@ -179,8 +406,8 @@ impl SessionManager {
 
         let participant = SessionParticipant {
             id: participant_id,
-            name: generate_participant_name(),
-            color: get_participant_color(color_index).to_string(),
+            name: self.identity.name(),
+            color: self.identity.color(color_index),
             role: ParticipantRole::Follower,
             connected_at: now,
             last_seen_at: now,
@@ -191,6 +418,7 @@ impl SessionManager {
 
         let participant_data = participant.to_participant();
         session.participants.insert(participant_id, participant);
+        session.peak_followers = session.peak_followers.max(follower_count + 1);
         session.rev += 1;
 
         info!(
@@ -204,27 +432,73 @@ impl SessionManager {
         histogram!("pathcollab_session_participants").record(session.participants.len() as f64);
         histogram!("pathcollab_session_join_duration_seconds").record(start.elapsed());
 
-        Ok((snapshot, participant_data))
+        Ok(Admitted {
+            snapshot,
+            participant: participant_data,
+            content_rev: session.content_rev,
+            reconnect_token,
+        })
     }
 
-    /// Authenticate as presenter
+    /// Authenticate `participant_id` as presenter. If the presenter had
+    /// disconnected, the participant takes over as presenter and the session
+    /// leaves its grace period.
     pub async fn authenticate_presenter(
         &self,
         session_id: &str,
+        participant_id: Uuid,
         presenter_key: &str,
     ) -> Result<(), SessionError> {
-        let session = self
+        let mut session = self
             .sessions
-            .get(session_id)
+            .get_mut(session_id)
             .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
 
         if !verify_secret(presenter_key, &session.presenter_key_hash) {
             return Err(SessionError::InvalidPresenterKey);
         }
 
+        let presenter_gone = matches!(session.state, SessionState::PresenterDisconnected { .. });
+        if presenter_gone && let Some(participant) = session.participants.get_mut(&participant_id) {
+            participant.role = ParticipantRole::Presenter;
+            session.presenter_id = participant_id;
+            session.state = SessionState::Active;
+            session.rev += 1;
+            info!(
+                "Participant {} reclaimed presenter of session {}",
+                participant_id, session_id
+            );
+        }
+
         Ok(())
     }
 
+    /// Public participant list (presenter first), for callers holding the
+    /// session's join secret or presenter key
+    pub async fn list_participants(
+        &self,
+        session_id: &str,
+        join_secret: Option<&str>,
+        presenter_key: Option<&str>,
+    ) -> Result<Vec<Participant>, SessionError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
+
+        let is_member = join_secret.is_some_and(|s| verify_secret(s, &session.join_secret_hash))
+            || presenter_key.is_some_and(|k| verify_secret(k, &session.presenter_key_hash));
+        if !is_member {
+            return Err(SessionError::InvalidJoinSecret);
+        }
+
+        let snapshot = create_session_snapshot(&session);
+        let mut participants = Vec::with_capacity(snapshot.followers.len() + 1);
+        participants.push(snapshot.presenter);
+        participants.extend(snapshot.followers);
+        Ok(participants)
+    }
+
     /// Get session snapshot
     pub async fn get_session(&self, session_id: &str) -> Result<SessionSnapshot, SessionError> {
         let session = self
@@ -235,6 +509,15 @@ impl SessionManager {
         Ok(create_session_snapshot(&session))
     }
 
+    /// Full internal state of a session, secret hashes included; for
+    /// server-side inspection only, never send it to clients
+    pub fn session_internals(&self, session_id: &str) -> Result<Session, SessionError> {
+        self.sessions
+            .get(session_id)
+            .map(|session| session.clone())
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))
+    }
+
     /// Update presenter viewport
     pub async fn update_presenter_viewport(
         &self,
@@ -246,25 +529,131 @@ impl SessionManager {
             .get_mut(session_id)
             .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
 
-        session.presenter_viewport = viewport;
-        session.rev += 1;
+        let previous = std::mem::replace(&mut session.presenter_viewport, viewport);
+        if self.config.viewport_history_len > 0 {
+            if session.viewport_history.len() == self.config.viewport_history_len {
+                session.viewport_history.pop_front();
+            }
+            session.viewport_history.push_back(previous);
+        }
+        session.bump_content_rev();
 
         Ok(session.rev)
     }
 
-    /// Change the slide for a session (presenter only)
+    /// The presenter viewport, unless the presenter is disconnected and the
+    /// stored viewport may be stale
+    pub async fn presenter_viewport(&self, session_id: &str) -> Result<Viewport, SessionError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
+        match session.state {
+            SessionState::Active => Ok(session.presenter_viewport.clone()),
+            SessionState::PresenterDisconnected { .. } => Err(SessionError::NoPresenter),
+            SessionState::Expired => Err(SessionError::SessionExpired),
+        }
+    }
+
+    /// Step the presenter viewport back through its history (presenter only).
+    /// Rewinding further than the history reaches stops at the oldest entry;
+    /// with no history the current viewport is kept.
+    pub async fn rewind_presenter_viewport(
+        &self,
+        session_id: &str,
+        steps: usize,
+    ) -> Result<Viewport, SessionError> {
+        let mut session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
+
+        let steps = steps.min(session.viewport_history.len());
+        if steps == 0 {
+            return Ok(session.presenter_viewport.clone());
+        }
+
+        let keep = session.viewport_history.len() - steps;
+        let mut viewport = session.viewport_history[keep].clone();
+        session.viewport_history.truncate(keep);
+        viewport.timestamp = now_millis();
+        session.presenter_viewport = viewport.clone();
+        session.bump_content_rev();
+
+        debug!("Session {} viewport rewound {} steps", session_id, steps);
+
+        Ok(viewport)
+    }
+
+    /// Set a participant's display name. Returns the sanitized name.
+    pub async fn rename_participant(
+        &self,
+        session_id: &str,
+        participant_id: Uuid,
+        name: &str,
+    ) -> Result<String, SessionError> {
+        let name = sanitize_participant_name(name).ok_or(SessionError::InvalidName)?;
+        let mut session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
+
+        let participant = session
+            .participants
+            .get_mut(&participant_id)
+            .ok_or(SessionError::ParticipantNotFound(participant_id))?;
+        participant.name = name.clone();
+        session.rev += 1;
+
+        debug!(
+            "Session {} participant {} renamed",
+            session_id, participant_id
+        );
+
+        Ok(name)
+    }
+
+    /// Check a chat message from a participant. Returns the sender's name and
+    /// the trimmed text to broadcast.
+    pub fn post_chat(
+        &self,
+        session_id: &str,
+        participant_id: Uuid,
+        text: &str,
+    ) -> Result<(String, String), SessionError> {
+        let session = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
+        if !session.has_feature(FEATURE_CHAT) {
+            return Err(SessionError::FeatureDisabled(FEATURE_CHAT));
+        }
+        let participant = session
+            .participants
+            .get(&participant_id)
+            .ok_or(SessionError::ParticipantNotFound(participant_id))?;
+
+        let text = text.trim();
+        if text.is_empty() || text.chars().count() > MAX_CHAT_MESSAGE_LEN {
+            return Err(SessionError::InvalidChatMessage);
+        }
+        Ok((participant.name.clone(), text.to_string()))
+    }
+
+    /// Change the slide for a session (presenter only). Overlay layers are
+    /// reset to defaults since the new slide may use a different taxonomy.
     pub async fn change_slide(
         &self,
         session_id: &str,
         slide: SlideInfo,
-    ) -> Result<SlideInfo, SessionError> {
+    ) -> Result<SessionSnapshot, SessionError> {
         let mut session = self
             .sessions
             .get_mut(session_id)
             .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
 
         session.slide = slide.clone();
-        session.rev += 1;
+        session.bump_content_rev();
 
         // Reset viewport to center when slide changes
         session.presenter_viewport = Viewport {
@@ -274,9 +663,15 @@ impl SessionManager {
             timestamp: now_millis(),
         };
 
+        session.viewport_history.clear();
+
+        // Drop layer state that refers to the previous slide's classes
+        session.cell_overlay = Some(CellOverlayState::default());
+        session.tissue_overlay = Some(TissueOverlayState::default());
+
         info!("Session {} slide changed to {}", session_id, slide.id);
 
-        Ok(slide)
+        Ok(create_session_snapshot(&session))
     }
 
     /// Update cell overlay state (presenter only)
@@ -291,7 +686,7 @@ impl SessionManager {
             .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
 
         session.cell_overlay = Some(cell_overlay);
-        session.rev += 1;
+        session.bump_content_rev();
 
         debug!("Session {} cell overlay updated", session_id);
 
@@ -310,26 +705,112 @@ impl SessionManager {
             .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
 
         session.tissue_overlay = Some(tissue_overlay);
-        session.rev += 1;
+        session.bump_content_rev();
 
         debug!("Session {} tissue overlay updated", session_id);
 
         Ok(session.rev)
     }
 
-    /// Update participant cursor
+    /// Apply a partial overlay update (presenter only). Unset fields keep their
+    /// current values; an overlay with no prior state starts from defaults.
+    /// An empty patch changes nothing and does not bump the revision.
+    pub async fn apply_layer_patch(
+        &self,
+        session_id: &str,
+        patch: &LayerPatch,
+    ) -> Result<u64, SessionError> {
+        let mut session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
+
+        if patch.is_empty() {
+            return Ok(session.rev);
+        }
+
+        if patch.touches_cell_overlay() {
+            let cell = session.cell_overlay.get_or_insert_with(Default::default);
+            if let Some(enabled) = patch.cell_enabled {
+                cell.enabled = enabled;
+            }
+            if let Some(opacity) = patch.cell_opacity {
+                cell.opacity = opacity;
+            }
+            if let Some(ref types) = patch.visible_cell_types {
+                cell.visible_cell_types = types.clone();
+            }
+        }
+
+        if patch.touches_tissue_overlay() {
+            let tissue = session.tissue_overlay.get_or_insert_with(Default::default);
+            if let Some(enabled) = patch.tissue_enabled {
+                tissue.enabled = enabled;
+            }
+            if let Some(opacity) = patch.tissue_opacity {
+                tissue.opacity = opacity;
+            }
+            if let Some(ref types) = patch.visible_tissue_types {
+                tissue.visible_tissue_types = types.clone();
+            }
+        }
+
+        session.bump_content_rev();
+
+        debug!("Session {} layer patch applied", session_id);
+
+        Ok(session.rev)
+    }
+
+    /// Show or hide follower cursors for everyone (presenter only)
+    pub async fn set_follower_cursors(
+        &self,
+        session_id: &str,
+        visible: bool,
+    ) -> Result<u64, SessionError> {
+        let mut session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
+
+        session.follower_cursors_visible = visible;
+        session.bump_content_rev();
+
+        debug!(
+            "Session {} follower cursors {}",
+            session_id,
+            if visible { "shown" } else { "hidden" }
+        );
+
+        Ok(session.rev)
+    }
+
+    /// Whether follower cursor updates should be broadcast (true for unknown sessions)
+    pub fn follower_cursors_visible(&self, session_id: &str) -> bool {
+        self.sessions
+            .get(session_id)
+            .is_none_or(|s| s.follower_cursors_visible)
+    }
+
+    /// Update participant cursor given in `space`. Returns the position in
+    /// slide pixels, the space cursors are stored and broadcast in.
     pub async fn update_cursor(
         &self,
         session_id: &str,
         participant_id: Uuid,
         x: f64,
         y: f64,
-    ) -> Result<(), SessionError> {
+        space: CoordinateSpace,
+    ) -> Result<(f64, f64), SessionError> {
         let mut session = self
             .sessions
             .get_mut(session_id)
             .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
 
+        let (x, y) = space
+            .to_slide_pixels(x, y, session.slide.width, session.slide.height)
+            .ok_or(SessionError::InvalidCursor)?;
+
         let participant = session
             .participants
             .get_mut(&participant_id)
@@ -339,7 +820,31 @@ impl SessionManager {
         participant.cursor_y = Some(y);
         participant.last_seen_at = now_millis();
 
-        Ok(())
+        Ok((x, y))
+    }
+
+    /// Clear cursors that have not moved for longer than `stale_after`.
+    /// Returns the affected participants per session so clients can be told to
+    /// drop them; the next cursor update re-adds the participant.
+    pub fn expire_idle_cursors(&self, stale_after: Duration) -> Vec<(SessionId, Vec<Uuid>)> {
+        let cutoff = now_millis().saturating_sub(stale_after.as_millis() as u64);
+        let mut expired = Vec::new();
+
+        for mut session in self.sessions.iter_mut() {
+            let mut removed = Vec::new();
+            for participant in session.participants.values_mut() {
+                if participant.cursor_x.is_some() && participant.last_seen_at < cutoff {
+                    participant.cursor_x = None;
+                    participant.cursor_y = None;
+                    removed.push(participant.id);
+                }
+            }
+            if !removed.is_empty() {
+                expired.push((session.id.clone(), removed));
+            }
+        }
+
+        expired
     }
 
     /// Remove participant from session
@@ -380,27 +885,107 @@ impl SessionManager {
         Ok(was_presenter)
     }
 
-    /// Clean up expired sessions
-    pub async fn cleanup_expired(&self) {
+    /// Clean up expired sessions, returning the removed ids and why each ended
+    pub async fn cleanup_expired(&self) -> Vec<(SessionId, SessionEndReason)> {
         let now = now_millis();
         let grace_period_ms = self.config.presenter_grace_period.as_millis() as u64;
+        let mut removed = Vec::new();
 
         // DashMap's retain allows atomic filtering without holding a global lock
         self.sessions.retain(|id, session| {
-            let should_remove = session.expires_at < now
-                || matches!(
-                    session.state,
-                    SessionState::PresenterDisconnected { disconnect_at }
-                        if now - disconnect_at > grace_period_ms
-                );
+            let reason = if session.expires_at < now {
+                Some(SessionEndReason::Expired)
+            } else if matches!(
+                session.state,
+                SessionState::PresenterDisconnected { disconnect_at }
+                    if now - disconnect_at > grace_period_ms
+            ) {
+                Some(SessionEndReason::PresenterLeft)
+            } else {
+                None
+            };
 
-            if should_remove {
+            let keep = reason.is_none();
+            if let Some(reason) = reason {
                 info!("Removing expired session: {}", id);
                 counter!("pathcollab_sessions_expired_total").increment(1);
+                self.recent.record(CompletedSession {
+                    lifetime_ms: now.saturating_sub(session.created_at),
+                    peak_followers: session.peak_followers,
+                });
+                removed.push((id.clone(), reason));
             }
 
-            !should_remove // retain returns true to keep, false to remove
+            keep // retain returns true to keep, false to remove
+        });
+        removed
+    }
+
+    /// Sessions that entered their expiry warning window since the last call,
+    /// with the seconds they have left. Each expiry is reported once.
+    pub fn take_expiry_warnings(&self) -> Vec<(SessionId, u64)> {
+        if self.config.expiry_warning.is_zero() {
+            return Vec::new();
+        }
+        let now = now_millis();
+        let warning_ms = self.config.expiry_warning.as_millis() as u64;
+        self.sessions
+            .iter_mut()
+            .filter_map(|mut session| {
+                let remaining_ms = session.expires_at.checked_sub(now)?;
+                if session.expiry_warned || remaining_ms > warning_ms {
+                    return None;
+                }
+                session.expiry_warned = true;
+                Some((session.id.clone(), remaining_ms.div_ceil(1000)))
+            })
+            .collect()
+    }
+
+    /// Push a session's expiry back by the configured extension, but never
+    /// past `max_total_duration` after its creation. Returns the new
+    /// `expires_at`.
+    pub async fn extend_session(&self, session_id: &str) -> Result<u64, SessionError> {
+        let mut session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
+
+        let limit = session.created_at + self.config.max_total_duration.as_millis() as u64;
+        let expires_at = (session.expires_at + self.config.extension.as_millis() as u64).min(limit);
+        if expires_at <= session.expires_at {
+            return Err(SessionError::ExtensionLimitReached);
+        }
+        session.expires_at = expires_at;
+        session.expiry_warned = false;
+
+        info!("Session {} extended until {}", session_id, expires_at);
+        Ok(expires_at)
+    }
+
+    /// Sessions currently showing `slide_id`
+    pub fn sessions_using_slide(&self, slide_id: &str) -> Vec<SessionId> {
+        self.sessions
+            .iter()
+            .filter(|session| session.slide.id == slide_id)
+            .map(|session| session.id.clone())
+            .collect()
+    }
+
+    /// End a session immediately, regardless of its expiry
+    pub fn end_session(&self, session_id: &str) -> Result<(), SessionError> {
+        let (_, session) = self
+            .sessions
+            .remove(session_id)
+            .ok_or_else(|| SessionError::NotFound(session_id.to_string()))?;
+
+        info!("Ended session: {}", session_id);
+        counter!("pathcollab_sessions_terminated_total").increment(1);
+        self.recent.record(CompletedSession {
+            lifetime_ms: now_millis().saturating_sub(session.created_at),
+            peak_followers: session.peak_followers,
         });
+        Ok(())
     }
 
     /// Get count of active sessions
@@ -412,6 +997,13 @@ impl SessionManager {
     pub fn session_count(&self) -> usize {
         self.sessions.len()
     }
+
+    /// Lifecycle summary over live sessions and recently removed ones
+    pub fn lifecycle_stats(&self) -> SessionLifecycleStats {
+        let live_peak_followers: Vec<usize> =
+            self.sessions.iter().map(|s| s.peak_followers).collect();
+        SessionLifecycleStats::compute(&live_peak_followers, &self.recent.snapshot())
+    }
 }
 
 impl Default for SessionManager {
@@ -426,18 +1018,26 @@ impl Clone for Session {
         Self {
             id: self.id.clone(),
             rev: self.rev,
+            content_rev: self.content_rev,
             join_secret_hash: self.join_secret_hash.clone(),
             presenter_key_hash: self.presenter_key_hash.clone(),
             locked: self.locked,
             created_at: self.created_at,
             expires_at: self.expires_at,
+            expiry_warned: self.expiry_warned,
             state: self.state.clone(),
             presenter_id: self.presenter_id,
             participants: self.participants.clone(),
+            reconnect_token_hashes: self.reconnect_token_hashes.clone(),
             slide: self.slide.clone(),
             presenter_viewport: self.presenter_viewport.clone(),
+            viewport_history: self.viewport_history.clone(),
             cell_overlay: self.cell_overlay.clone(),
             tissue_overlay: self.tissue_overlay.clone(),
+            follower_cursors_visible: self.follower_cursors_visible,
+            peak_followers: self.peak_followers,
+            max_followers: self.max_followers,
+            features: self.features.clone(),
         }
     }
 }
@@ -472,6 +1072,8 @@ fn create_session_snapshot(session: &Session) -> SessionSnapshot {
         presenter_viewport: session.presenter_viewport.clone(),
         cell_overlay: session.cell_overlay.clone(),
         tissue_overlay: session.tissue_overlay.clone(),
+        follower_cursors_visible: session.follower_cursors_visible,
+        max_followers: session.max_followers,
     }
 }
 
@@ -505,6 +1107,10 @@ mod tests {
             tile_size: 256,
             num_levels: 10,
             tile_url_template: "/tile/{level}/{x}/{y}".to_string(),
+            mpp_x: None,
+            mpp_y: None,
+            objective_power: None,
+            has_overlay: false,
         }
     }
 
@@ -540,6 +1146,91 @@ mod tests {
         assert_eq!(participant.role, ParticipantRole::Follower);
     }
 
+    #[tokio::test]
+    async fn test_rejoin_with_current_rev_is_unchanged() {
+        let manager = SessionManager::new();
+
+        let (session, join_secret, _) = manager
+            .create_session(test_slide(), Uuid::new_v4())
+            .await
+            .unwrap();
+        let (snapshot, first) = manager
+            .join_session(&session.id, &join_secret)
+            .await
+            .unwrap();
+        let seen_rev = snapshot.rev;
+
+        // The follower drops, then reconnects: only membership changed
+        manager
+            .remove_participant(&session.id, first.id)
+            .await
+            .unwrap();
+        let (outcome, second, _) = manager
+            .rejoin_session(&session.id, &join_secret, Some(seen_rev), None, false)
+            .await
+            .unwrap();
+        match outcome {
+            JoinSnapshot::Unchanged { rev, followers, .. } => {
+                assert_eq!(rev, seen_rev + 2);
+                let ids: Vec<Uuid> = followers.iter().map(|f| f.id).collect();
+                assert_eq!(ids, vec![second.id]);
+            }
+            JoinSnapshot::Full(_) => panic!("Reconnect should not resend the snapshot"),
+        }
+
+        // Once content changes, the old revision is stale
+        manager
+            .update_presenter_viewport(
+                &session.id,
+                Viewport {
+                    center_x: 0.25,
+                    center_y: 0.25,
+                    zoom: 2.0,
+                    timestamp: now_millis(),
+                },
+            )
+            .await
+            .unwrap();
+        let (outcome, _, _) = manager
+            .rejoin_session(&session.id, &join_secret, Some(seen_rev), None, false)
+            .await
+            .unwrap();
+        assert!(matches!(outcome, JoinSnapshot::Full(_)));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_only_join_requires_issued_token() {
+        let manager = SessionManager::new();
+        let (session, join_secret, _) = manager
+            .create_session(test_slide(), Uuid::new_v4())
+            .await
+            .unwrap();
+        let rejoin =
+            |rev, token| manager.rejoin_session(&session.id, &join_secret, rev, token, true);
+
+        // A fresh client can't get in by claiming a revision or making up a token
+        assert!(matches!(
+            rejoin(Some(0), None).await,
+            Err(SessionError::ReconnectRequired)
+        ));
+        assert!(matches!(
+            rejoin(Some(1), Some("made-up")).await,
+            Err(SessionError::ReconnectRequired)
+        ));
+
+        // A token from an earlier join is accepted once, and replaced
+        let (_, _, token) = manager
+            .rejoin_session(&session.id, &join_secret, None, None, false)
+            .await
+            .unwrap();
+        let (_, _, next_token) = rejoin(None, Some(token.as_str())).await.unwrap();
+        assert!(matches!(
+            rejoin(None, Some(token.as_str())).await,
+            Err(SessionError::ReconnectRequired)
+        ));
+        assert!(rejoin(None, Some(next_token.as_str())).await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_invalid_join_secret() {
         let manager = SessionManager::new();
@@ -560,6 +1251,7 @@ mod tests {
             max_duration: Duration::from_millis(1),
             presenter_grace_period: Duration::from_secs(1),
             max_followers: 20,
+            ..Default::default()
         };
         let manager = SessionManager::with_config(config);
 
@@ -569,9 +1261,243 @@ mod tests {
             .unwrap();
 
         tokio::time::sleep(Duration::from_millis(5)).await;
-        manager.cleanup_expired().await;
+        let removed = manager.cleanup_expired().await;
 
         assert_eq!(manager.session_count_async().await, 0);
+        assert!(matches!(
+            removed.as_slice(),
+            [(_, SessionEndReason::Expired)]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_session_rejected_at_capacity() {
+        let manager = SessionManager::with_config(SessionConfig {
+            max_sessions: 1,
+            ..Default::default()
+        });
+
+        manager
+            .create_session(test_slide(), Uuid::new_v4())
+            .await
+            .expect("First session should be created");
+        let result = manager.create_session(test_slide(), Uuid::new_v4()).await;
+
+        assert!(matches!(result, Err(SessionError::ServerAtCapacity(1))));
+        assert_eq!(manager.session_count(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_creates_respect_capacity() {
+        let manager = Arc::new(SessionManager::with_config(SessionConfig {
+            max_sessions: 3,
+            ..Default::default()
+        }));
+
+        let creates: Vec<_> = (0..32)
+            .map(|_| {
+                let manager = manager.clone();
+                tokio::spawn(async move {
+                    manager
+                        .create_session(test_slide(), Uuid::new_v4())
+                        .await
+                        .is_ok()
+                })
+            })
+            .collect();
+        let mut created = 0;
+        for create in creates {
+            created += usize::from(create.await.unwrap());
+        }
+
+        assert_eq!(created, 3);
+        assert_eq!(manager.session_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_per_session_max_followers() {
+        let manager = SessionManager::with_config(SessionConfig {
+            max_followers_cap: 50,
+            ..Default::default()
+        });
+
+        let (session, join_secret, _) = manager
+            .create_session_with_max_followers(test_slide(), Uuid::new_v4(), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(
+            manager
+                .get_session(&session.id)
+                .await
+                .unwrap()
+                .max_followers,
+            2
+        );
+
+        manager
+            .join_session(&session.id, &join_secret)
+            .await
+            .unwrap();
+        manager
+            .join_session(&session.id, &join_secret)
+            .await
+            .unwrap();
+        let third = manager.join_session(&session.id, &join_secret).await;
+        assert!(matches!(third, Err(SessionError::SessionFull(2))));
+
+        // Requests above the server cap are clamped
+        let (lecture, _, _) = manager
+            .create_session_with_max_followers(test_slide(), Uuid::new_v4(), Some(500))
+            .await
+            .unwrap();
+        assert_eq!(lecture.max_followers, 50);
+    }
+
+    #[tokio::test]
+    async fn test_demo_session_recreated_after_expiry() {
+        let manager = SessionManager::with_config(SessionConfig {
+            max_duration: Duration::from_millis(50),
+            ..Default::default()
+        });
+
+        assert!(manager.ensure_demo_session(test_slide(), "demo-secret"));
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        manager.cleanup_expired().await;
+        assert_eq!(manager.session_count(), 0);
+
+        assert!(manager.ensure_demo_session(test_slide(), "demo-secret"));
+        assert!(
+            manager
+                .join_session(DEMO_SESSION_ID, "demo-secret")
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_stats_track_removed_sessions() {
+        let config = SessionConfig {
+            max_duration: Duration::from_millis(20),
+            presenter_grace_period: Duration::from_secs(1),
+            max_followers: 20,
+            ..Default::default()
+        };
+        let manager = SessionManager::with_config(config);
+
+        let (first, join_secret, _) = manager
+            .create_session(test_slide(), Uuid::new_v4())
+            .await
+            .unwrap();
+        manager.join_session(&first.id, &join_secret).await.unwrap();
+        manager.join_session(&first.id, &join_secret).await.unwrap();
+        manager
+            .create_session(test_slide(), Uuid::new_v4())
+            .await
+            .unwrap();
+
+        let stats = manager.lifecycle_stats();
+        assert_eq!(stats.active_sessions, 2);
+        assert_eq!(stats.completed_sessions, 0);
+        assert_eq!(stats.session_lifetime_secs, None);
+        assert_eq!(stats.avg_followers_per_session, 1.0);
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        manager.cleanup_expired().await;
+
+        let stats = manager.lifecycle_stats();
+        assert_eq!(stats.active_sessions, 0);
+        assert_eq!(stats.completed_sessions, 2);
+        let lifetime = stats.session_lifetime_secs.unwrap();
+        assert!(lifetime.p50 >= 0.02 && lifetime.p99 >= lifetime.p50);
+        assert_eq!(stats.avg_followers_per_session, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_expire_idle_cursors() {
+        let manager = SessionManager::new();
+        let (session, join_secret, _) = manager
+            .create_session(test_slide(), Uuid::new_v4())
+            .await
+            .unwrap();
+        let (_, idle) = manager
+            .join_session(&session.id, &join_secret)
+            .await
+            .unwrap();
+        let (_, silent) = manager
+            .join_session(&session.id, &join_secret)
+            .await
+            .unwrap();
+
+        manager
+            .update_cursor(&session.id, idle.id, 1.0, 2.0, CoordinateSpace::SlidePixels)
+            .await
+            .unwrap();
+        assert!(
+            manager
+                .expire_idle_cursors(Duration::from_secs(60))
+                .is_empty()
+        );
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let expired = manager.expire_idle_cursors(Duration::from_millis(10));
+        // Participants that never sent a cursor are not reported
+        assert_eq!(expired, vec![(session.id.clone(), vec![idle.id])]);
+        assert!(!expired[0].1.contains(&silent.id));
+
+        // Already-expired cursors are reported once
+        assert!(
+            manager
+                .expire_idle_cursors(Duration::from_millis(10))
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rewind_presenter_viewport() {
+        let manager = SessionManager::with_config(SessionConfig {
+            viewport_history_len: 3,
+            ..Default::default()
+        });
+        let (session, _, _) = manager
+            .create_session(test_slide(), Uuid::new_v4())
+            .await
+            .unwrap();
+
+        for zoom in 1..=5 {
+            manager
+                .update_presenter_viewport(
+                    &session.id,
+                    Viewport {
+                        center_x: 0.5,
+                        center_y: 0.5,
+                        zoom: zoom as f64,
+                        timestamp: now_millis(),
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        // History holds zooms 2, 3, 4 (bounded to 3); current is 5
+        let viewport = manager
+            .rewind_presenter_viewport(&session.id, 2)
+            .await
+            .unwrap();
+        assert_eq!(viewport.zoom, 3.0);
+        let snapshot = manager.get_session(&session.id).await.unwrap();
+        assert_eq!(snapshot.presenter_viewport.zoom, 3.0);
+
+        // Rewinding past the oldest entry stops there, then history is empty
+        let viewport = manager
+            .rewind_presenter_viewport(&session.id, 10)
+            .await
+            .unwrap();
+        assert_eq!(viewport.zoom, 2.0);
+        let viewport = manager
+            .rewind_presenter_viewport(&session.id, 1)
+            .await
+            .unwrap();
+        assert_eq!(viewport.zoom, 2.0);
     }
 
     #[tokio::test]
@@ -844,6 +1770,44 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_deterministic_identity_generator_across_joins() {
+        use crate::session::state::SeededIdentityGenerator;
+
+        let names_for_seed = |seed: u64| async move {
+            let manager = SessionManager::new()
+                .with_identity_generator(Arc::new(SeededIdentityGenerator::new(seed)));
+            let (session, join_secret, _) = manager
+                .create_session(test_slide(), Uuid::new_v4())
+                .await
+                .unwrap();
+
+            let mut names = vec![
+                manager
+                    .get_session(&session.id)
+                    .await
+                    .unwrap()
+                    .presenter
+                    .name,
+            ];
+            for _ in 0..5 {
+                let (_, participant) = manager
+                    .join_session(&session.id, &join_secret)
+                    .await
+                    .unwrap();
+                names.push(participant.name);
+            }
+            names
+        };
+
+        let first = names_for_seed(1234).await;
+        let second = names_for_seed(1234).await;
+        assert_eq!(
+            first, second,
+            "Same seed must yield the same names across joins"
+        );
+    }
+
     #[tokio::test]
     async fn test_presenter_grace_period_is_30_seconds() {
         let config = SessionConfig::default();
@@ -886,6 +1850,10 @@ mod tests {
             tile_size: 512,
             num_levels: 12,
             tile_url_template: "/tile/{level}/{x}/{y}".to_string(),
+            mpp_x: None,
+            mpp_y: None,
+            objective_power: None,
+            has_overlay: false,
         };
 
         // Change the slide
@@ -946,6 +1914,10 @@ mod tests {
             tile_size: 256,
             num_levels: 8,
             tile_url_template: "/tile/{level}/{x}/{y}".to_string(),
+            mpp_x: None,
+            mpp_y: None,
+            objective_power: None,
+            has_overlay: false,
         };
 
         manager
@@ -960,6 +1932,68 @@ mod tests {
         );
     }
 
+    /// Test: Slide change resets overlay layers left over from the previous slide
+    #[tokio::test]
+    async fn test_change_slide_resets_layers() {
+        let manager = SessionManager::new();
+        let presenter_id = Uuid::new_v4();
+
+        let (session, _, _) = manager
+            .create_session(test_slide(), presenter_id)
+            .await
+            .expect("Session creation should succeed");
+
+        manager
+            .update_cell_overlay(
+                &session.id,
+                CellOverlayState {
+                    enabled: true,
+                    opacity: 0.4,
+                    visible_cell_types: vec!["tumor".to_string()],
+                },
+            )
+            .await
+            .unwrap();
+        manager
+            .update_tissue_overlay(
+                &session.id,
+                TissueOverlayState {
+                    enabled: true,
+                    opacity: 0.3,
+                    visible_tissue_types: vec![1, 2],
+                },
+            )
+            .await
+            .unwrap();
+        let rev_before = manager.get_session(&session.id).await.unwrap().rev;
+
+        let new_slide = SlideInfo {
+            id: "other_slide".to_string(),
+            ..test_slide()
+        };
+        let snapshot = manager
+            .change_slide(&session.id, new_slide)
+            .await
+            .expect("Slide change should succeed");
+
+        assert_eq!(snapshot.slide.id, "other_slide");
+        assert!(snapshot.rev > rev_before);
+
+        let cell = snapshot.cell_overlay.expect("cell overlay should be reset");
+        assert!(!cell.enabled);
+        assert!(cell.visible_cell_types.is_empty());
+        let tissue = snapshot
+            .tissue_overlay
+            .expect("tissue overlay should be reset");
+        assert!(!tissue.enabled);
+        assert!(tissue.visible_tissue_types.is_empty());
+
+        // The stored session matches what was returned for the broadcast
+        let stored = manager.get_session(&session.id).await.unwrap();
+        assert_eq!(stored.rev, snapshot.rev);
+        assert!(!stored.cell_overlay.unwrap().enabled);
+    }
+
     /// Test: Slide change on non-existent session returns error
     #[tokio::test]
     async fn test_change_slide_invalid_session() {
@@ -973,6 +2007,10 @@ mod tests {
             tile_size: 256,
             num_levels: 4,
             tile_url_template: "/tile/{level}/{x}/{y}".to_string(),
+            mpp_x: None,
+            mpp_y: None,
+            objective_power: None,
+            has_overlay: false,
         };
 
         let result = manager.change_slide("nonexistent", new_slide).await;
@@ -982,6 +2020,36 @@ mod tests {
         );
     }
 
+    /// Test: Sessions are looked up by the slide they show
+    #[tokio::test]
+    async fn test_sessions_using_slide() {
+        let manager = SessionManager::new();
+        let other_slide = SlideInfo {
+            id: "other".to_string(),
+            ..test_slide()
+        };
+
+        let mut on_test = Vec::new();
+        for _ in 0..2 {
+            let (session, _, _) = manager
+                .create_session(test_slide(), Uuid::new_v4())
+                .await
+                .unwrap();
+            on_test.push(session.id);
+        }
+        let (on_other, _, _) = manager
+            .create_session(other_slide, Uuid::new_v4())
+            .await
+            .unwrap();
+
+        let mut found = manager.sessions_using_slide("test");
+        found.sort();
+        on_test.sort();
+        assert_eq!(found, on_test);
+        assert_eq!(manager.sessions_using_slide("other"), vec![on_other.id]);
+        assert!(manager.sessions_using_slide("missing").is_empty());
+    }
+
     #[tokio::test]
     async fn test_session_state_transitions() {
         let manager = SessionManager::new();
@@ -1008,4 +2076,119 @@ mod tests {
             "Removing presenter should return true for was_presenter"
         );
     }
+
+    #[tokio::test]
+    async fn test_presenter_reauth_ends_grace_period() {
+        let manager = SessionManager::with_config(SessionConfig {
+            presenter_grace_period: Duration::from_millis(30),
+            ..Default::default()
+        });
+        let (session, join_secret, presenter_key) = manager
+            .create_session(test_slide(), Uuid::new_v4())
+            .await
+            .unwrap();
+
+        manager
+            .remove_participant(&session.id, session.presenter_id)
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.presenter_viewport(&session.id).await,
+            Err(SessionError::NoPresenter)
+        ));
+
+        // The presenter comes back as a new participant and re-authenticates
+        let (_, returning) = manager
+            .join_session(&session.id, &join_secret)
+            .await
+            .unwrap();
+        manager
+            .authenticate_presenter(&session.id, returning.id, &presenter_key)
+            .await
+            .unwrap();
+
+        let internals = manager.session_internals(&session.id).unwrap();
+        assert!(matches!(internals.state, SessionState::Active));
+        assert_eq!(internals.presenter_id, returning.id);
+        let snapshot = manager.get_session(&session.id).await.unwrap();
+        assert_eq!(snapshot.presenter.id, returning.id);
+        assert!(snapshot.followers.is_empty());
+        assert!(manager.presenter_viewport(&session.id).await.is_ok());
+
+        // No longer reaped once the original grace period has run out
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(manager.cleanup_expired().await.is_empty());
+        assert_eq!(manager.session_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_layer_patch_changes_only_given_field() {
+        let manager = SessionManager::new();
+        let (session, _, _) = manager
+            .create_session(test_slide(), Uuid::new_v4())
+            .await
+            .unwrap();
+        manager
+            .update_cell_overlay(
+                &session.id,
+                CellOverlayState {
+                    enabled: true,
+                    opacity: 0.8,
+                    visible_cell_types: vec!["tumor".to_string(), "immune".to_string()],
+                },
+            )
+            .await
+            .unwrap();
+        let before = manager.get_session(&session.id).await.unwrap();
+
+        let patch = LayerPatch {
+            cell_opacity: Some(0.25),
+            ..Default::default()
+        };
+        let rev = manager
+            .apply_layer_patch(&session.id, &patch)
+            .await
+            .unwrap();
+        assert_eq!(rev, before.rev + 1);
+
+        let after = manager.get_session(&session.id).await.unwrap();
+        let cell = after.cell_overlay.unwrap();
+        assert_eq!(cell.opacity, 0.25);
+        assert!(cell.enabled);
+        assert_eq!(cell.visible_cell_types, vec!["tumor", "immune"]);
+        assert!(after.tissue_overlay.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_layer_patch_on_unset_overlay_starts_from_defaults() {
+        let manager = SessionManager::new();
+        let (session, _, _) = manager
+            .create_session(test_slide(), Uuid::new_v4())
+            .await
+            .unwrap();
+
+        let patch = LayerPatch {
+            tissue_enabled: Some(true),
+            ..Default::default()
+        };
+        manager
+            .apply_layer_patch(&session.id, &patch)
+            .await
+            .unwrap();
+
+        let after = manager.get_session(&session.id).await.unwrap();
+        let tissue = after.tissue_overlay.unwrap();
+        let defaults = TissueOverlayState::default();
+        assert!(tissue.enabled);
+        assert_eq!(tissue.opacity, defaults.opacity);
+        assert!(tissue.visible_tissue_types.is_empty());
+        assert!(after.cell_overlay.is_none());
+
+        // An empty patch is a no-op
+        let rev = manager
+            .apply_layer_patch(&session.id, &LayerPatch::default())
+            .await
+            .unwrap();
+        assert_eq!(rev, after.rev);
+    }
 }
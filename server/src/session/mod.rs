@@ -1,5 +1,7 @@
+pub mod links;
 pub mod manager;
 pub mod state;
+pub mod stats;
 
 #[allow(unused_imports)] // Re-exports for when session management is fully integrated
 pub use manager::*;
@@ -1,7 +1,9 @@
 use crate::protocol::{
-    CellOverlayState, Participant, ParticipantRole, SlideInfo, TissueOverlayState, Viewport,
+    CellOverlayState, OPTIONAL_FEATURES, Participant, ParticipantRole, SlideInfo,
+    TissueOverlayState, Viewport,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
@@ -12,6 +14,9 @@ pub type SessionId = String;
 const SESSION_ID_CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
 const SESSION_ID_LENGTH: usize = 10;
 
+/// Well-known id of the demo session (see `SessionManager::ensure_demo_session`)
+pub const DEMO_SESSION_ID: &str = "pathcollab";
+
 /// Generate a cryptographically random session ID
 pub fn generate_session_id() -> SessionId {
     use std::collections::hash_map::RandomState;
@@ -66,6 +71,9 @@ pub struct Session {
     // Identity
     pub id: SessionId,
     pub rev: u64,
+    /// `rev` as of the last change to shared content (slide, viewport,
+    /// overlays, layers). Participants joining and leaving don't move it.
+    pub content_rev: u64,
     pub join_secret_hash: String,
     pub presenter_key_hash: String,
 
@@ -75,6 +83,8 @@ pub struct Session {
     // Timestamps
     pub created_at: u64,
     pub expires_at: u64,
+    /// Participants were warned about the current `expires_at`
+    pub expiry_warned: bool,
 
     // Lifecycle
     pub state: SessionState,
@@ -82,14 +92,42 @@ pub struct Session {
     // Participants
     pub presenter_id: Uuid,
     pub participants: HashMap<Uuid, SessionParticipant>,
+    /// Hashes of the single-use reconnect tokens handed to joining followers
+    pub reconnect_token_hashes: HashSet<String>,
 
     // Content
     pub slide: SlideInfo,
     pub presenter_viewport: Viewport,
+    /// Previous presenter viewports, oldest first (bounded, for rewinding)
+    pub viewport_history: VecDeque<Viewport>,
 
     // Cell overlay state (presenter-controlled)
     pub cell_overlay: Option<CellOverlayState>,
     pub tissue_overlay: Option<TissueOverlayState>,
+
+    // Presence: whether follower cursors are broadcast (presenter-controlled)
+    pub follower_cursors_visible: bool,
+
+    // Stats: most followers connected at once
+    pub peak_followers: usize,
+
+    /// Followers allowed in this session (set at creation)
+    pub max_followers: usize,
+
+    /// Optional features enabled for this session (set at creation)
+    pub features: Vec<String>,
+}
+
+impl Session {
+    /// Bump the revision for a change to shared content
+    pub fn bump_content_rev(&mut self) {
+        self.rev += 1;
+        self.content_rev = self.rev;
+    }
+
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
 }
 
 /// Participant within a session (extended data)
@@ -121,16 +159,39 @@ impl SessionParticipant {
 /// Session configuration
 pub struct SessionConfig {
     pub max_duration: Duration,
+    /// Participants are warned this long before their session expires
+    /// (zero disables the warning)
+    pub expiry_warning: Duration,
+    /// How far each presenter extension pushes the expiry back
+    pub extension: Duration,
+    /// Extensions never push the expiry past this long after creation
+    pub max_total_duration: Duration,
     pub presenter_grace_period: Duration,
+    /// Default follower limit for sessions that don't request one
     pub max_followers: usize,
+    /// Upper bound on a follower limit requested at session creation
+    pub max_followers_cap: usize,
+    /// Maximum live sessions on this server
+    pub max_sessions: usize,
+    /// Presenter viewports kept for rewinding
+    pub viewport_history_len: usize,
+    /// Optional features enabled for new sessions (see [`OPTIONAL_FEATURES`])
+    pub features: Vec<String>,
 }
 
 impl Default for SessionConfig {
     fn default() -> Self {
         Self {
             max_duration: Duration::from_secs(4 * 60 * 60), // 4 hours
+            expiry_warning: Duration::from_secs(5 * 60),
+            extension: Duration::from_secs(60 * 60),
+            max_total_duration: Duration::from_secs(8 * 60 * 60),
             presenter_grace_period: Duration::from_secs(30),
             max_followers: 20,
+            max_followers_cap: 100,
+            max_sessions: 1000,
+            viewport_history_len: 32,
+            features: OPTIONAL_FEATURES.iter().map(|f| f.to_string()).collect(),
         }
     }
 }
@@ -177,6 +238,26 @@ pub fn generate_participant_name() -> String {
     format!("{} {}", ADJECTIVES[adj_idx], ANIMALS[animal_idx])
 }
 
+/// Longest display name a participant may choose, in characters
+pub const MAX_PARTICIPANT_NAME_LEN: usize = 32;
+
+/// Clean up a participant-chosen display name: control characters become
+/// spaces, whitespace runs are collapsed, and the result is capped at
+/// [`MAX_PARTICIPANT_NAME_LEN`] characters. Returns None if nothing is left.
+pub fn sanitize_participant_name(name: &str) -> Option<String> {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    let collapsed = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    let capped: String = collapsed.chars().take(MAX_PARTICIPANT_NAME_LEN).collect();
+    let capped = capped.trim_end();
+    (!capped.is_empty()).then(|| capped.to_string())
+}
+
+/// Longest chat message a participant may post, in characters
+pub const MAX_CHAT_MESSAGE_LEN: usize = 1000;
+
 /// Participant color palette (12 visually distinct colors)
 const PARTICIPANT_COLORS: &[&str] = &[
     "#3B82F6", // Blue
@@ -193,8 +274,129 @@ const PARTICIPANT_COLORS: &[&str] = &[
     "#84CC16", // Lime
 ];
 
-pub fn get_participant_color(index: usize) -> &'static str {
-    PARTICIPANT_COLORS[index % PARTICIPANT_COLORS.len()]
+/// Shade of each pass through a palette, as the share of the way to black
+/// (negative) or white (positive): as is, then darker, then lighter. Passes
+/// beyond these wrap around, so colors repeat from the 37th participant on.
+const PALETTE_SHADES: &[f64] = &[0.0, -0.3, 0.35];
+
+/// Color for the participant at `index` in join order (see [`palette_color`])
+pub fn get_participant_color(index: usize) -> String {
+    palette_color(PARTICIPANT_COLORS, index)
+}
+
+/// Color at `index` of a palette that wraps around once exhausted. Each pass
+/// through the palette gets the next of [`PALETTE_SHADES`], so participants
+/// sharing a base color stay distinguishable. Colors that aren't `#RRGGBB`
+/// are repeated unshaded.
+pub fn palette_color<S: AsRef<str>>(palette: &[S], index: usize) -> String {
+    let base = palette[index % palette.len()].as_ref();
+    let pass = index / palette.len();
+    shade(base, PALETTE_SHADES[pass % PALETTE_SHADES.len()]).unwrap_or_else(|| base.to_string())
+}
+
+/// Mix a `#RRGGBB` color `amount` of the way towards black (negative) or
+/// white (positive). `None` if the color isn't `#RRGGBB`.
+fn shade(color: &str, amount: f64) -> Option<String> {
+    let hex = color.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+    let target = if amount < 0.0 { 0.0 } else { 255.0 };
+    let mut shaded = String::from("#");
+    for i in (0..6).step_by(2) {
+        let channel = f64::from(u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()?);
+        let mixed = channel + (target - channel) * amount.abs();
+        shaded.push_str(&format!("{:02X}", mixed.round() as u8));
+    }
+    Some(shaded)
+}
+
+/// Source of participant display names and colors
+///
+/// `SessionManager` asks its generator for every new participant, so tests can
+/// inject a deterministic one and deployments can plug in custom name lists or
+/// branded palettes.
+pub trait IdentityGenerator: Send + Sync {
+    /// Generate a display name for a new participant
+    fn name(&self) -> String;
+
+    /// Color for the participant at `index` in join order (presenter is 0)
+    fn color(&self, index: usize) -> String;
+}
+
+/// Default generator: random "{Adjective} {Animal}" names and the 12-color palette
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RandomIdentityGenerator;
+
+impl IdentityGenerator for RandomIdentityGenerator {
+    fn name(&self) -> String {
+        generate_participant_name()
+    }
+
+    fn color(&self, index: usize) -> String {
+        get_participant_color(index)
+    }
+}
+
+/// Reproducible generator: names are drawn from a seeded sequence, so two
+/// generators built with the same seed and lists hand out identical identities
+pub struct SeededIdentityGenerator {
+    state: AtomicU64,
+    adjectives: Vec<String>,
+    animals: Vec<String>,
+    palette: Vec<String>,
+}
+
+impl SeededIdentityGenerator {
+    /// Seeded generator over the default name lists and palette
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: AtomicU64::new(seed),
+            adjectives: ADJECTIVES.iter().map(|s| s.to_string()).collect(),
+            animals: ANIMALS.iter().map(|s| s.to_string()).collect(),
+            palette: PARTICIPANT_COLORS.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Replace the adjective and animal lists (empty lists keep the defaults)
+    pub fn with_names(mut self, adjectives: Vec<String>, animals: Vec<String>) -> Self {
+        if !adjectives.is_empty() {
+            self.adjectives = adjectives;
+        }
+        if !animals.is_empty() {
+            self.animals = animals;
+        }
+        self
+    }
+
+    /// Replace the color palette (an empty palette keeps the default)
+    pub fn with_palette(mut self, palette: Vec<String>) -> Self {
+        if !palette.is_empty() {
+            self.palette = palette;
+        }
+        self
+    }
+
+    /// Next value of a splitmix64 sequence
+    fn next(&self) -> u64 {
+        let mut z = self
+            .state
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl IdentityGenerator for SeededIdentityGenerator {
+    fn name(&self) -> String {
+        let hash = self.next();
+        let adj_idx = (hash as usize) % self.adjectives.len();
+        let animal_idx = ((hash >> 32) as usize) % self.animals.len();
+        format!("{} {}", self.adjectives[adj_idx], self.animals[animal_idx])
+    }
+
+    fn color(&self, index: usize) -> String {
+        palette_color(&self.palette, index)
+    }
 }
 
 #[cfg(test)]
@@ -212,4 +414,49 @@ mod tests {
         assert!(!validate_session_id("abcd234568")); // contains 8 (invalid)
         assert!(!validate_session_id("abcd234569")); // contains 9 (invalid)
     }
+
+    #[test]
+    fn test_seeded_identity_generator_is_reproducible() {
+        let a = SeededIdentityGenerator::new(42);
+        let b = SeededIdentityGenerator::new(42);
+
+        for i in 0..10 {
+            assert_eq!(a.name(), b.name());
+            assert_eq!(a.color(i), get_participant_color(i));
+        }
+    }
+
+    #[test]
+    fn test_seeded_identity_generator_custom_lists() {
+        let generator = SeededIdentityGenerator::new(7)
+            .with_names(vec!["Acme".to_string()], vec!["Reviewer".to_string()])
+            .with_palette(vec!["#000000".to_string(), "#FFFFFF".to_string()]);
+
+        assert_eq!(generator.name(), "Acme Reviewer");
+        assert_eq!(generator.color(0), "#000000");
+        assert_eq!(generator.color(1), "#FFFFFF");
+        // Second pass: darkened
+        assert_eq!(generator.color(3), "#B3B3B3");
+    }
+
+    #[test]
+    fn test_participant_colors_beyond_palette() {
+        let colors: Vec<String> = (0..25).map(get_participant_color).collect();
+
+        assert_eq!(&colors[..12], PARTICIPANT_COLORS);
+        for color in &colors {
+            assert!(shade(color, 0.0).is_some(), "{color} is not #RRGGBB");
+        }
+        // A full session (presenter + 20 followers) gets distinct colors
+        let mut session: Vec<&String> = colors[..21].iter().collect();
+        session.sort();
+        session.dedup();
+        assert_eq!(session.len(), 21);
+        // Later passes shade the palette rather than repeat it
+        assert_eq!(colors[12], "#295BAC");
+        assert_eq!(colors[24], "#80AEF9");
+
+        // Unshadable colors wrap as is
+        assert_eq!(palette_color(&["red"], 5), "red");
+    }
 }
@@ -0,0 +1,161 @@
+//! Session lifecycle statistics
+//!
+//! Prometheus already exposes histograms for scraping; this keeps a small ring
+//! buffer of recently completed sessions so a JSON summary can be served to
+//! dashboards that don't scrape Prometheus.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Number of completed sessions kept for lifetime statistics
+pub const RECENT_SESSIONS_CAPACITY: usize = 256;
+
+/// A session that has been removed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompletedSession {
+    /// Time from creation to removal in milliseconds
+    pub lifetime_ms: u64,
+    /// Most followers connected at once
+    pub peak_followers: usize,
+}
+
+/// Ring buffer of recently completed sessions
+#[derive(Debug)]
+pub struct RecentSessions {
+    completed: Mutex<VecDeque<CompletedSession>>,
+    capacity: usize,
+}
+
+impl RecentSessions {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            completed: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// Record a removed session, evicting the oldest entry when full
+    pub fn record(&self, session: CompletedSession) {
+        let mut completed = self.completed.lock().unwrap_or_else(|e| e.into_inner());
+        if completed.len() == self.capacity {
+            completed.pop_front();
+        }
+        completed.push_back(session);
+    }
+
+    pub fn snapshot(&self) -> Vec<CompletedSession> {
+        let completed = self.completed.lock().unwrap_or_else(|e| e.into_inner());
+        completed.iter().copied().collect()
+    }
+}
+
+impl Default for RecentSessions {
+    fn default() -> Self {
+        Self::new(RECENT_SESSIONS_CAPACITY)
+    }
+}
+
+/// Lifetime summary over recently completed sessions (seconds)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LifetimeSummary {
+    pub avg: f64,
+    pub p50: f64,
+    pub p99: f64,
+}
+
+/// JSON summary of session lifecycle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionLifecycleStats {
+    /// Sessions currently live
+    pub active_sessions: usize,
+    /// Completed sessions the lifetime figures are computed from
+    pub completed_sessions: usize,
+    /// Lifetime of completed sessions; `None` until one has completed
+    pub session_lifetime_secs: Option<LifetimeSummary>,
+    /// Average peak follower count across live and completed sessions
+    pub avg_followers_per_session: f64,
+}
+
+impl SessionLifecycleStats {
+    /// Summarize peak follower counts of live sessions and recently completed ones
+    pub fn compute(live_peak_followers: &[usize], completed: &[CompletedSession]) -> Self {
+        let mut lifetimes: Vec<u64> = completed.iter().map(|s| s.lifetime_ms).collect();
+        lifetimes.sort_unstable();
+
+        let session_lifetime_secs = (!lifetimes.is_empty()).then(|| LifetimeSummary {
+            avg: lifetimes.iter().sum::<u64>() as f64 / lifetimes.len() as f64 / 1000.0,
+            p50: percentile(&lifetimes, 0.50) as f64 / 1000.0,
+            p99: percentile(&lifetimes, 0.99) as f64 / 1000.0,
+        });
+
+        let follower_counts = live_peak_followers
+            .iter()
+            .copied()
+            .chain(completed.iter().map(|s| s.peak_followers));
+        let total_sessions = live_peak_followers.len() + completed.len();
+        let avg_followers_per_session = if total_sessions == 0 {
+            0.0
+        } else {
+            follower_counts.sum::<usize>() as f64 / total_sessions as f64
+        };
+
+        Self {
+            active_sessions: live_peak_followers.len(),
+            completed_sessions: completed.len(),
+            session_lifetime_secs,
+            avg_followers_per_session,
+        }
+    }
+}
+
+/// Nearest-rank percentile of a sorted, non-empty slice
+fn percentile(sorted: &[u64], q: f64) -> u64 {
+    let rank = (q * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completed(lifetime_ms: u64, peak_followers: usize) -> CompletedSession {
+        CompletedSession {
+            lifetime_ms,
+            peak_followers,
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer_evicts_oldest() {
+        let recent = RecentSessions::new(2);
+        recent.record(completed(1000, 1));
+        recent.record(completed(2000, 2));
+        recent.record(completed(3000, 3));
+
+        assert_eq!(
+            recent.snapshot(),
+            vec![completed(2000, 2), completed(3000, 3)]
+        );
+    }
+
+    #[test]
+    fn test_stats_without_completed_sessions() {
+        let stats = SessionLifecycleStats::compute(&[2, 4], &[]);
+        assert_eq!(stats.active_sessions, 2);
+        assert_eq!(stats.completed_sessions, 0);
+        assert_eq!(stats.session_lifetime_secs, None);
+        assert_eq!(stats.avg_followers_per_session, 3.0);
+    }
+
+    #[test]
+    fn test_lifetime_percentiles() {
+        let sessions: Vec<_> = (1..=100).map(|i| completed(i * 1000, 0)).collect();
+        let stats = SessionLifecycleStats::compute(&[], &sessions);
+
+        let lifetime = stats.session_lifetime_secs.unwrap();
+        assert_eq!(lifetime.avg, 50.5);
+        assert_eq!(lifetime.p50, 50.0);
+        assert_eq!(lifetime.p99, 99.0);
+    }
+}
@@ -49,6 +49,8 @@ pub struct SlideCache {
     slide_list_cache: RwLock<Option<SlideListCache>>,
     /// Counter for probabilistic LRU updates
     access_counter: AtomicU64,
+    /// Slide open attempts, i.e. cache misses in `get_or_open`
+    opens: AtomicU64,
 }
 
 impl SlideCache {
@@ -60,6 +62,7 @@ impl SlideCache {
             max_size,
             slide_list_cache: RwLock::new(None),
             access_counter: AtomicU64::new(0),
+            opens: AtomicU64::new(0),
         }
     }
 
@@ -81,6 +84,7 @@ impl SlideCache {
             }
 
             debug!("Opening slide: {} at {:?}", id, path);
+            self.opens.fetch_add(1, Ordering::Relaxed);
             let slide = OpenSlide::new(path)
                 .map_err(|e| SlideError::OpenError(format!("Failed to open {:?}: {}", path, e)))?;
             let slide = Arc::new(slide);
@@ -99,6 +103,17 @@ impl SlideCache {
         }
     }
 
+    /// Number of times a slide has been opened
+    #[cfg(test)]
+    pub fn open_count(&self) -> u64 {
+        self.opens.load(Ordering::Relaxed)
+    }
+
+    /// Drop the open handle for a slide, keeping its cached metadata
+    pub async fn close(&self, id: &str) {
+        self.slides.write().await.shift_remove(id);
+    }
+
     /// Get cached metadata for a slide
     pub fn get_metadata(&self, id: &str) -> Option<Arc<SlideMetadata>> {
         self.metadata.get(id).map(|r| Arc::clone(r.value()))
@@ -152,6 +167,14 @@ impl SlideCache {
         None
     }
 
+    /// Forget the slide list, metadata and open handles, e.g. after slides
+    /// were added to or replaced in the slides directory
+    pub async fn clear(&self) {
+        *self.slide_list_cache.write().await = None;
+        self.slides.write().await.clear();
+        self.metadata.clear();
+    }
+
     /// Set the cached slide list
     pub async fn set_slide_list(&self, slides: Vec<(String, PathBuf)>) {
         let mut cache = self.slide_list_cache.write().await;
@@ -1,12 +1,14 @@
 //! Local slide service using OpenSlide
 
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 use async_trait::async_trait;
 use openslide_rs::OpenSlide;
 use tracing::{debug, error, info, warn};
 
-use crate::config::SlideConfig;
+use crate::config::{SlideConfig, SlidePreload};
+use crate::fovea::OverlayLayout;
 
 use super::cache::SlideCache;
 use super::service::SlideService;
@@ -21,6 +23,10 @@ pub struct LocalSlideService {
     slides_dir: PathBuf,
     cache: SlideCache,
     tile_size: u32,
+    /// Used to report `has_overlay` (always false if None)
+    overlays: Option<OverlayLayout>,
+    /// Apply [`normalize_slide_id`] to scanned and requested ids
+    normalize_ids: bool,
 }
 
 impl LocalSlideService {
@@ -51,9 +57,36 @@ impl LocalSlideService {
             slides_dir: slides_dir.clone(),
             cache: SlideCache::new(config.max_cached_slides),
             tile_size: config.tile_size,
+            overlays: None,
+            normalize_ids: config.normalize_ids,
         })
     }
 
+    /// Report `has_overlay` for slides whose overlay is found in `layout`
+    pub fn with_overlay_layout(mut self, layout: OverlayLayout) -> Self {
+        self.overlays = Some(layout);
+        self
+    }
+
+    /// Set `has_overlay` from the files currently on disk. Not cached, so
+    /// overlays added after the slide was first opened are picked up.
+    fn flag_overlay(&self, mut meta: SlideMetadata, path: &Path) -> SlideMetadata {
+        meta.has_overlay = self
+            .overlays
+            .as_ref()
+            .is_some_and(|layout| layout.has_overlay(&meta.id, path));
+        meta
+    }
+
+    /// The catalog id for a scanned or requested slide id
+    fn normalize(&self, id: &str) -> String {
+        if self.normalize_ids {
+            normalize_slide_id(id)
+        } else {
+            id.to_string()
+        }
+    }
+
     /// Scan the slides directory for slide files
     async fn scan_slides_cached(&self) -> Vec<(String, PathBuf)> {
         // Check if we have a valid cached list
@@ -97,9 +130,13 @@ impl LocalSlideService {
                     let id = path
                         .file_stem()
                         .and_then(|s| s.to_str())
-                        .map(sanitize_id)
+                        .map(|stem| self.normalize(&sanitize_id(stem)))
                         .unwrap_or_else(|| format!("slide_{}", slides.len()));
 
+                    if slides.iter().any(|(existing, _)| *existing == id) {
+                        warn!("Skipping slide {:?}: id {} is already taken", path, id);
+                        continue;
+                    }
                     debug!("Found slide: {} at {:?}", id, path);
                     slides.push((id, path));
                 }
@@ -110,6 +147,44 @@ impl LocalSlideService {
         slides
     }
 
+    /// Open the slides in `preload` and cache their metadata, logging
+    /// progress. Unless `keep_open` is set, each slide is closed again once
+    /// its metadata is cached. Returns the number of slides loaded.
+    pub async fn preload(&self, preload: &SlidePreload, keep_open: bool) -> usize {
+        let ids: Vec<String> = match preload {
+            SlidePreload::Disabled => return 0,
+            SlidePreload::All => self
+                .scan_slides_cached()
+                .await
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect(),
+            SlidePreload::Ids(ids) => ids.clone(),
+        };
+
+        let start = Instant::now();
+        let mut loaded = 0;
+        for (i, id) in ids.iter().enumerate() {
+            match self.get_slide(id).await {
+                Ok(_) => {
+                    loaded += 1;
+                    info!("Preloaded slide {}/{}: {}", i + 1, ids.len(), id);
+                }
+                Err(e) => warn!("Failed to preload slide {}: {}", id, e),
+            }
+            if !keep_open {
+                self.cache.close(&self.normalize(id)).await;
+            }
+        }
+        info!(
+            "Preloaded {} of {} slides in {:?}",
+            loaded,
+            ids.len(),
+            start.elapsed()
+        );
+        loaded
+    }
+
     /// Find slide path by ID
     async fn find_slide_path(&self, id: &str) -> Option<PathBuf> {
         for (slide_id, path) in self.scan_slides_cached().await {
@@ -153,6 +228,11 @@ impl LocalSlideService {
             .ok()
             .and_then(|s| s.parse().ok());
 
+        let objective_power = slide
+            .get_property_value("openslide.objective-power")
+            .ok()
+            .and_then(|s| s.parse().ok());
+
         SlideMetadata {
             id: id.to_string(),
             name,
@@ -164,6 +244,8 @@ impl LocalSlideService {
             vendor,
             mpp_x,
             mpp_y,
+            objective_power,
+            has_overlay: false,
         }
     }
 
@@ -189,7 +271,7 @@ impl SlideService for LocalSlideService {
         for (id, path) in slides {
             // Check cache first
             if let Some(meta) = self.cache.get_metadata(&id) {
-                metadata_list.push((*meta).clone());
+                metadata_list.push(self.flag_overlay((*meta).clone(), &path));
                 continue;
             }
 
@@ -198,7 +280,7 @@ impl SlideService for LocalSlideService {
                 Ok(slide) => {
                     let meta = self.extract_metadata(&id, &path, &slide);
                     self.cache.set_metadata(&id, meta.clone());
-                    metadata_list.push(meta);
+                    metadata_list.push(self.flag_overlay(meta, &path));
                 }
                 Err(e) => {
                     warn!("Failed to open slide {}: {}", id, e);
@@ -210,23 +292,51 @@ impl SlideService for LocalSlideService {
         Ok(metadata_list)
     }
 
-    async fn get_slide(&self, id: &str) -> Result<SlideMetadata, SlideError> {
-        if let Some(meta) = self.cache.get_metadata(id) {
-            return Ok((*meta).clone());
-        }
+    async fn get_slide(&self, requested_id: &str) -> Result<SlideMetadata, SlideError> {
+        let id = &self.normalize(requested_id);
 
         // Find the slide path
         let path = self
             .find_slide_path(id)
             .await
-            .ok_or_else(|| SlideError::NotFound(id.to_string()))?;
+            .ok_or_else(|| SlideError::NotFound(requested_id.to_string()))?;
+
+        if let Some(meta) = self.cache.get_metadata(id) {
+            return Ok(self.flag_overlay((*meta).clone(), &path));
+        }
 
         // Open and extract metadata
         let slide = self.cache.get_or_open(id, &path).await?;
         let meta = self.extract_metadata(id, &path, &slide);
         self.cache.set_metadata(id, meta.clone());
 
-        Ok(meta)
+        Ok(self.flag_overlay(meta, &path))
+    }
+
+    async fn reload(&self) -> Result<usize, SlideError> {
+        self.cache.clear().await;
+        Ok(self.scan_slides_cached().await.len())
+    }
+
+    async fn delete_slide(&self, id: &str) -> Result<(), SlideError> {
+        let path = self
+            .find_slide_path(&self.normalize(id))
+            .await
+            .ok_or_else(|| SlideError::NotFound(id.to_string()))?;
+        tokio::fs::remove_file(&path).await?;
+        self.cache.clear().await;
+        Ok(())
+    }
+}
+
+/// Lowercase a slide id and strip a trailing WSI extension, if any
+pub fn normalize_slide_id(id: &str) -> String {
+    let id = id.to_lowercase();
+    match id.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && SLIDE_EXTENSIONS.contains(&ext) => {
+            stem.to_string()
+        }
+        _ => id,
     }
 }
 
@@ -246,6 +356,73 @@ fn sanitize_id(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::OverlayConfig;
+    use crate::protocol::SlideInfo;
+
+    /// A 64x64 single-tile, uncompressed RGB TIFF, which OpenSlide opens
+    /// as a generic tiled TIFF
+    fn tiled_tiff() -> Vec<u8> {
+        const SIZE: u32 = 64;
+        const ENTRIES: u16 = 11;
+        let bits_offset = 8 + 2 + u32::from(ENTRIES) * 12 + 4;
+        let data_offset = bits_offset + 6;
+        let data_len = SIZE * SIZE * 3;
+
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend(8u32.to_le_bytes());
+        tiff.extend(ENTRIES.to_le_bytes());
+        // (tag, type: 3 = SHORT / 4 = LONG, count, value or offset)
+        for (tag, kind, count, value) in [
+            (256u16, 4u16, 1u32, SIZE), // ImageWidth
+            (257, 4, 1, SIZE),          // ImageLength
+            (258, 3, 3, bits_offset),   // BitsPerSample
+            (259, 3, 1, 1),             // Compression: none
+            (262, 3, 1, 2),             // PhotometricInterpretation: RGB
+            (277, 3, 1, 3),             // SamplesPerPixel
+            (284, 3, 1, 1),             // PlanarConfiguration: contiguous
+            (322, 4, 1, SIZE),          // TileWidth
+            (323, 4, 1, SIZE),          // TileLength
+            (324, 4, 1, data_offset),   // TileOffsets
+            (325, 4, 1, data_len),      // TileByteCounts
+        ] {
+            tiff.extend(tag.to_le_bytes());
+            tiff.extend(kind.to_le_bytes());
+            tiff.extend(count.to_le_bytes());
+            tiff.extend(value.to_le_bytes());
+        }
+        tiff.extend(0u32.to_le_bytes());
+        tiff.extend([8u16, 8, 8].iter().flat_map(|b| b.to_le_bytes()));
+        tiff.resize((data_offset + data_len) as usize, 0xdd);
+        tiff
+    }
+
+    #[tokio::test]
+    async fn test_preloaded_slide_is_not_reopened() {
+        let dir = std::env::temp_dir().join(format!("pathcollab-slides-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("warm.tiff"), tiled_tiff()).unwrap();
+        std::fs::write(dir.join("cold.tiff"), tiled_tiff()).unwrap();
+        let service = LocalSlideService::new(&SlideConfig {
+            slides_dir: dir.clone(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let preload = SlidePreload::Ids(vec!["warm".to_string()]);
+        assert_eq!(service.preload(&preload, false).await, 1);
+        assert_eq!(service.cache.open_count(), 1);
+        // Closed again, but the metadata stays cached
+        assert!(service.cache.get_cached("warm").await.is_none());
+
+        assert_eq!(service.get_slide("warm").await.unwrap().id, "warm");
+        assert_eq!(service.cache.open_count(), 1);
+
+        // Slides left out of the preload are opened on first use
+        service.get_slide("cold").await.unwrap();
+        assert_eq!(service.cache.open_count(), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 
     #[test]
     fn test_calculate_dzi_levels() {
@@ -253,6 +430,8 @@ mod tests {
             slides_dir: PathBuf::from("/tmp"),
             cache: SlideCache::new(10),
             tile_size: 256,
+            overlays: None,
+            normalize_ids: false,
         };
 
         // 1x1 -> 1 level
@@ -265,6 +444,157 @@ mod tests {
         assert_eq!(service.calculate_dzi_levels(100000, 100000), 18);
     }
 
+    #[tokio::test]
+    async fn test_reload_picks_up_new_slides() {
+        let dir = std::env::temp_dir().join(format!("pathcollab-slides-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("first.svs"), b"").unwrap();
+        let service = LocalSlideService::new(&SlideConfig {
+            slides_dir: dir.clone(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(service.scan_slides_cached().await.len(), 1);
+        assert!(matches!(
+            service.get_slide("second").await,
+            Err(SlideError::NotFound(_))
+        ));
+
+        // Added after startup: hidden by the cached listing until reload
+        std::fs::write(dir.join("second.svs"), b"").unwrap();
+        assert!(service.find_slide_path("second").await.is_none());
+
+        assert_eq!(service.reload().await.unwrap(), 2);
+        assert_eq!(
+            service.find_slide_path("second").await,
+            Some(dir.join("second.svs"))
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_delete_slide_removes_file() {
+        let dir = std::env::temp_dir().join(format!("pathcollab-slides-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("doomed.svs"), b"").unwrap();
+        let service = LocalSlideService::new(&SlideConfig {
+            slides_dir: dir.clone(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(service.find_slide_path("doomed").await.is_some());
+
+        service.delete_slide("doomed").await.unwrap();
+        assert!(!dir.join("doomed.svs").exists());
+        assert!(matches!(
+            service.get_slide("doomed").await,
+            Err(SlideError::NotFound(_))
+        ));
+        assert!(matches!(
+            service.delete_slide("doomed").await,
+            Err(SlideError::NotFound(_))
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_has_overlay_follows_overlay_file() {
+        let root = std::env::temp_dir().join(format!("pathcollab-slides-{}", uuid::Uuid::new_v4()));
+        let slide_config = SlideConfig {
+            slides_dir: root.join("slides"),
+            ..Default::default()
+        };
+        let overlay_config = OverlayConfig {
+            overlays_dir: root.join("overlays"),
+            ..Default::default()
+        };
+        std::fs::create_dir_all(&slide_config.slides_dir).unwrap();
+        std::fs::create_dir_all(&overlay_config.overlays_dir).unwrap();
+        std::fs::write(slide_config.slides_dir.join("case.svs"), b"").unwrap();
+
+        let service = LocalSlideService::new(&slide_config)
+            .unwrap()
+            .with_overlay_layout(OverlayLayout::new(&slide_config, &overlay_config));
+        // Seed the metadata cache so the empty slide file is never opened
+        service.cache.set_metadata(
+            "case",
+            SlideMetadata {
+                id: "case".to_string(),
+                name: "case".to_string(),
+                width: 1000,
+                height: 1000,
+                tile_size: 256,
+                num_levels: 11,
+                format: "svs".to_string(),
+                vendor: None,
+                mpp_x: None,
+                mpp_y: None,
+                objective_power: None,
+                has_overlay: false,
+            },
+        );
+
+        assert!(!service.get_slide("case").await.unwrap().has_overlay);
+
+        std::fs::write(overlay_config.overlays_dir.join("case.bin"), b"").unwrap();
+        assert!(service.get_slide("case").await.unwrap().has_overlay);
+        let listed = service.list_slides().await.unwrap();
+        assert!(listed[0].has_overlay);
+        assert!(SlideInfo::from(listed[0].clone()).has_overlay);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_normalized_ids_ignore_case_and_extension() {
+        let dir = std::env::temp_dir().join(format!("pathcollab-slides-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("Slide1.TIFF"), tiled_tiff()).unwrap();
+        let config = SlideConfig {
+            slides_dir: dir.clone(),
+            ..Default::default()
+        };
+
+        // Off by default: ids keep their on-disk casing
+        let exact = LocalSlideService::new(&config).unwrap();
+        assert_eq!(exact.get_slide("Slide1").await.unwrap().id, "Slide1");
+        assert!(matches!(
+            exact.get_slide("slide1").await,
+            Err(SlideError::NotFound(_))
+        ));
+
+        let normalized = LocalSlideService::new(&SlideConfig {
+            normalize_ids: true,
+            ..config
+        })
+        .unwrap();
+        let listed = normalized.list_slides().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, "slide1");
+        for requested in ["slide1", "Slide1", "SLIDE1", "slide1.tiff", "Slide1.svs"] {
+            let meta = normalized.get_slide(requested).await.unwrap();
+            assert_eq!(meta.id, "slide1", "requested {requested}");
+        }
+        assert!(matches!(
+            normalized.get_slide("slide1.txt").await,
+            Err(SlideError::NotFound(_))
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_normalize_slide_id() {
+        assert_eq!(normalize_slide_id("TCGA-AB-1234"), "tcga-ab-1234");
+        assert_eq!(normalize_slide_id("Slide1.SVS"), "slide1");
+        assert_eq!(normalize_slide_id("scan.v2.ndpi"), "scan.v2");
+        assert_eq!(normalize_slide_id("notes.txt"), "notes.txt");
+        assert_eq!(normalize_slide_id(".svs"), ".svs");
+    }
+
     #[test]
     fn test_sanitize_id() {
         assert_eq!(sanitize_id("test-slide_123"), "test-slide_123");
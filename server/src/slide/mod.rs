@@ -13,7 +13,7 @@ pub mod routes;
 mod service;
 mod types;
 
-pub use local::LocalSlideService;
+pub use local::{LocalSlideService, normalize_slide_id};
 pub use routes::{SlideAppState, slide_routes};
 pub use service::SlideService;
 pub use types::{SlideError, SlideListItem, SlideMetadata};
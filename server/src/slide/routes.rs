@@ -4,6 +4,7 @@ use axum::{
     Json, Router,
     extract::{Path, State},
     http::StatusCode,
+    middleware,
     response::{IntoResponse, Response},
     routing::get,
 };
@@ -12,6 +13,7 @@ use std::sync::Arc;
 
 use super::service::SlideService;
 use super::types::{SlideError, SlideListItem, SlideMetadata};
+use crate::http_metrics::track_http_metrics;
 
 /// Application state containing the slide service
 #[derive(Clone)]
@@ -128,5 +130,6 @@ pub fn slide_routes(state: SlideAppState) -> Router {
         .route("/slides", get(list_slides))
         .route("/slides/default", get(get_default_slide))
         .route("/slide/:id", get(get_slide))
+        .route_layer(middleware::from_fn(track_http_metrics))
         .with_state(state)
 }
@@ -18,4 +18,17 @@ pub trait SlideService: Send + Sync {
     async fn slide_exists(&self, id: &str) -> bool {
         self.get_slide(id).await.is_ok()
     }
+
+    /// Drop any cached catalog state and rescan, returning the number of slides.
+    /// Services without a cache just count their slides.
+    async fn reload(&self) -> Result<usize, SlideError> {
+        Ok(self.list_slides().await?.len())
+    }
+
+    /// Permanently remove a slide from the catalog and drop cached state for it
+    async fn delete_slide(&self, _id: &str) -> Result<(), SlideError> {
+        Err(SlideError::ServiceUnavailable(
+            "slide deletion is not supported".to_string(),
+        ))
+    }
 }
@@ -1,5 +1,6 @@
 //! Slide-related types and error definitions
 
+use crate::protocol::SlideInfo;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -45,6 +46,12 @@ pub struct SlideMetadata {
     /// Microns per pixel Y (if available)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mpp_y: Option<f64>,
+    /// Scanner objective magnification, e.g. 20 or 40 (if available)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objective_power: Option<f64>,
+    /// Whether an overlay (primary or named) exists for this slide
+    #[serde(default)]
+    pub has_overlay: bool,
 }
 
 /// Summary info for slide listing
@@ -68,3 +75,22 @@ impl From<SlideMetadata> for SlideListItem {
         }
     }
 }
+
+impl From<SlideMetadata> for SlideInfo {
+    fn from(m: SlideMetadata) -> Self {
+        let tile_url_template = format!("/api/slide/{}/tile/{{level}}/{{x}}/{{y}}", m.id);
+        Self {
+            id: m.id,
+            name: m.name,
+            width: m.width,
+            height: m.height,
+            tile_size: m.tile_size,
+            num_levels: m.num_levels,
+            tile_url_template,
+            mpp_x: m.mpp_x,
+            mpp_y: m.mpp_y,
+            objective_power: m.objective_power,
+            has_overlay: m.has_overlay,
+        }
+    }
+}
@@ -6,7 +6,7 @@
 #![cfg(test)]
 
 use crate::protocol::{
-    ClientMessage, Participant, ParticipantRole, ServerMessage, SessionSnapshot,
+    ClientMessage, CoordinateSpace, Participant, ParticipantRole, ServerMessage, SessionSnapshot,
     SlideInfo, Viewport,
 };
 use crate::server::AppState;
@@ -357,7 +357,7 @@ impl ExpectedResponse {
                 matches!(msg, ServerMessage::PresenterViewport { .. })
             }
             ExpectedResponse::Pong => {
-                matches!(msg, ServerMessage::Pong)
+                matches!(msg, ServerMessage::Pong { .. })
             }
             ExpectedResponse::Custom(matcher) => matcher(msg),
         }
@@ -368,6 +368,7 @@ impl ExpectedResponse {
 pub fn create_session_message(slide_id: &str, seq: u64) -> ClientMessage {
     ClientMessage::CreateSession {
         slide_id: slide_id.to_string(),
+        max_followers: None,
         seq,
     }
 }
@@ -382,13 +383,19 @@ pub fn join_session_message(
         session_id: session_id.to_string(),
         join_secret: join_secret.to_string(),
         last_seen_rev: None,
+        reconnect_token: None,
         seq,
     }
 }
 
 /// Create a CursorUpdate client message
 pub fn cursor_update_message(x: f64, y: f64, seq: u64) -> ClientMessage {
-    ClientMessage::CursorUpdate { x, y, seq }
+    ClientMessage::CursorUpdate {
+        x,
+        y,
+        space: CoordinateSpace::SlidePixels,
+        seq,
+    }
 }
 
 /// Create a ViewportUpdate client message
@@ -402,13 +409,17 @@ pub fn viewport_update_message(
         center_x,
         center_y,
         zoom,
+        transition_ms: None,
         seq,
     }
 }
 
 /// Create a Ping client message
 pub fn ping_message(seq: u64) -> ClientMessage {
-    ClientMessage::Ping { seq }
+    ClientMessage::Ping {
+        seq,
+        client_ts: None,
+    }
 }
 
 // ============================================================================
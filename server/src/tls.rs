@@ -0,0 +1,33 @@
+//! Optional in-process TLS
+//!
+//! With `TLS_CERT_PATH` and `TLS_KEY_PATH` set, the server terminates TLS
+//! itself, so HTTPS and WSS work without a reverse proxy. Without them it
+//! serves plain HTTP as before.
+
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use std::net::SocketAddr;
+use std::path::Path;
+
+/// Load a PEM certificate chain and private key
+pub async fn load_rustls_config(
+    cert_path: &Path,
+    key_path: &Path,
+) -> std::io::Result<RustlsConfig> {
+    // Several rustls crypto providers are compiled in, so pick one explicitly.
+    // Fails harmlessly if a provider was already installed.
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    RustlsConfig::from_pem_file(cert_path, key_path).await
+}
+
+/// Serve `app` over TLS on an already-bound listener
+pub async fn serve_tls(
+    listener: std::net::TcpListener,
+    app: Router,
+    config: RustlsConfig,
+) -> std::io::Result<()> {
+    listener.set_nonblocking(true)?;
+    axum_server::from_tcp_rustls(listener, config)
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+}
@@ -0,0 +1,263 @@
+//! Outbound webhooks for session lifecycle events
+//!
+//! When `WEBHOOK_URL` is set, session creation and termination and participant
+//! joins and leaves are POSTed to it as JSON. Every request carries an
+//! `X-PathCollab-Signature: sha256=<hex>` header holding the HMAC-SHA256 of
+//! the body keyed with `WEBHOOK_SECRET`, so receivers can check that the event
+//! came from this server.
+//!
+//! Delivery is fire-and-forget: events are queued for a background task that
+//! posts them in order, retrying failed attempts with exponential backoff.
+//! Events that still fail after `WEBHOOK_MAX_ATTEMPTS` are dropped and counted
+//! in `pathcollab_webhook_dead_letters_total`.
+
+use crate::config::WebhookConfig;
+use crate::protocol::{ParticipantRole, SessionEndReason};
+use crate::session::state::now_millis;
+use hmac::{Hmac, Mac};
+use metrics::counter;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Header carrying the body signature
+pub const SIGNATURE_HEADER: &str = "X-PathCollab-Signature";
+
+/// Events queued for delivery before new ones are dropped
+const WEBHOOK_QUEUE_CAPACITY: usize = 1024;
+
+/// Delay before the first retry; doubled for each further attempt
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// What happened to a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event")]
+pub enum WebhookEventKind {
+    #[serde(rename = "session.created")]
+    SessionCreated {
+        slide_id: String,
+        presenter_id: Uuid,
+    },
+    #[serde(rename = "session.ended")]
+    SessionEnded { reason: SessionEndReason },
+    #[serde(rename = "participant.joined")]
+    ParticipantJoined {
+        participant_id: Uuid,
+        name: String,
+        role: ParticipantRole,
+    },
+    #[serde(rename = "participant.left")]
+    ParticipantLeft { participant_id: Uuid },
+}
+
+/// Body of one webhook request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    /// Unique per event, so receivers can drop retried duplicates
+    pub id: Uuid,
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    pub session_id: String,
+    #[serde(flatten)]
+    pub kind: WebhookEventKind,
+}
+
+impl WebhookEvent {
+    pub fn new(session_id: &str, kind: WebhookEventKind) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            timestamp: now_millis(),
+            session_id: session_id.to_string(),
+            kind,
+        }
+    }
+}
+
+/// Value of the [`SIGNATURE_HEADER`] for `body`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+/// Where and how events are delivered
+struct Endpoint {
+    client: reqwest::Client,
+    url: String,
+    secret: String,
+    max_attempts: u32,
+}
+
+/// Queues session events for delivery to the configured webhook
+pub struct WebhookNotifier {
+    tx: mpsc::Sender<WebhookEvent>,
+}
+
+impl WebhookNotifier {
+    /// Start delivering to `config.url`, or None if webhooks are disabled.
+    /// Must be called from within a Tokio runtime.
+    pub fn from_config(config: &WebhookConfig) -> Option<Self> {
+        let url = config.url.clone()?;
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .expect("Failed to build webhook HTTP client");
+        let endpoint = Endpoint {
+            client,
+            url,
+            secret: config.secret.clone().unwrap_or_default(),
+            max_attempts: config.max_attempts.max(1),
+        };
+        let (tx, rx) = mpsc::channel(WEBHOOK_QUEUE_CAPACITY);
+        tokio::spawn(deliver_events(endpoint, rx));
+        Some(Self { tx })
+    }
+
+    /// Queue an event. Never blocks; the event is dropped if the queue is full.
+    pub fn notify(&self, event: WebhookEvent) {
+        match self.tx.try_send(event) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                counter!("pathcollab_webhook_dropped_total").increment(1);
+                warn!("Webhook queue is full, dropping event");
+            }
+            Err(TrySendError::Closed(_)) => {
+                counter!("pathcollab_webhook_dropped_total").increment(1);
+                warn!("Webhook delivery task has stopped, dropping event");
+            }
+        }
+    }
+}
+
+/// Deliver queued events one at a time until every sender is gone
+async fn deliver_events(endpoint: Endpoint, mut rx: mpsc::Receiver<WebhookEvent>) {
+    while let Some(event) = rx.recv().await {
+        let body = match serde_json::to_vec(&event) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to serialize webhook event: {}", e);
+                continue;
+            }
+        };
+        let signature = sign(&endpoint.secret, &body);
+
+        let mut delay = INITIAL_RETRY_DELAY;
+        for attempt in 1..=endpoint.max_attempts {
+            let result = endpoint
+                .client
+                .post(&endpoint.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .body(body.clone())
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            match result {
+                Ok(_) => {
+                    counter!("pathcollab_webhook_deliveries_total").increment(1);
+                    break;
+                }
+                Err(e) if attempt < endpoint.max_attempts => {
+                    counter!("pathcollab_webhook_retries_total").increment(1);
+                    warn!(
+                        "Webhook delivery of {} failed (attempt {}): {}",
+                        event.id, attempt, e
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+                Err(e) => {
+                    counter!("pathcollab_webhook_dead_letters_total").increment(1);
+                    warn!(
+                        "Giving up on webhook event {} after {} attempts: {}",
+                        event.id, attempt, e
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::harness::{fixture_state, session_with_follower};
+    use axum::{Router, body::Bytes, extract::State, http::HeaderMap, routing::post};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_sign_matches_hmac_sha256() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    /// Local HTTP endpoint that forwards every request it receives
+    async fn mock_sink() -> (String, mpsc::UnboundedReceiver<(HeaderMap, Bytes)>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(
+                    |State(tx): State<mpsc::UnboundedSender<(HeaderMap, Bytes)>>,
+                     headers: HeaderMap,
+                     body: Bytes| async move {
+                        let _ = tx.send((headers, body));
+                    },
+                ),
+            )
+            .with_state(tx);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (format!("http://{}/hook", addr), rx)
+    }
+
+    #[tokio::test]
+    async fn test_participant_joined_is_delivered_signed() {
+        let (url, mut received) = mock_sink().await;
+        let config = WebhookConfig {
+            url: Some(url),
+            secret: Some("hook-secret".to_string()),
+            ..WebhookConfig::default()
+        };
+        let notifier = WebhookNotifier::from_config(&config).unwrap();
+        let state = fixture_state().with_webhooks(Arc::new(notifier));
+
+        let (_presenter, follower) = session_with_follower(&state).await;
+        let session_id = follower.session_id().unwrap();
+
+        let joined = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let (headers, body) = received.recv().await.expect("Sink should stay up");
+                let event: WebhookEvent = serde_json::from_slice(&body).unwrap();
+                if let WebhookEventKind::ParticipantJoined {
+                    role: ParticipantRole::Follower,
+                    ..
+                } = event.kind
+                {
+                    return (headers, body, event);
+                }
+            }
+        })
+        .await
+        .expect("participant.joined should be delivered");
+
+        let (headers, body, event) = joined;
+        assert_eq!(event.session_id, session_id);
+        assert_eq!(
+            headers[SIGNATURE_HEADER].to_str().unwrap(),
+            sign("hook-secret", &body)
+        );
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["event"], "participant.joined");
+    }
+}
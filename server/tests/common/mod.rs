@@ -4,13 +4,27 @@
 
 use async_trait::async_trait;
 use axum::{Json, Router, routing::get};
-use pathcollab_server::protocol::SlideInfo;
+use futures_util::{SinkExt, StreamExt};
+use pathcollab_server::protocol::{ClientMessage, ServerMessage, SessionSnapshot, SlideInfo};
 use pathcollab_server::server::AppState;
 use pathcollab_server::{SlideAppState, SlideError, SlideMetadata, SlideService, slide_routes};
 use serde::Serialize;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, client_async, connect_async, tungstenite, tungstenite::Message,
+};
 use tower_http::cors::{Any, CorsLayer};
 
+/// Client side of a test WebSocket connection
+pub type WsStream = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Client side of a WebSocket connection to a [`MemoryServer`]
+pub type MemoryWsStream = WebSocketStream<DuplexStream>;
+
 #[derive(Serialize)]
 pub struct HealthResponse {
     pub status: &'static str,
@@ -56,6 +70,10 @@ pub fn create_test_slide_info() -> SlideInfo {
         tile_size: 256,
         num_levels: 10,
         tile_url_template: "/api/slide/{id}/tile/{level}/{x}/{y}".to_string(),
+        mpp_x: None,
+        mpp_y: None,
+        objective_power: None,
+        has_overlay: false,
     }
 }
 
@@ -79,6 +97,8 @@ impl MockSlideService {
                 vendor: Some("mock".to_string()),
                 mpp_x: Some(0.25),
                 mpp_y: Some(0.25),
+                objective_power: Some(40.0),
+                has_overlay: false,
             }],
         }
     }
@@ -121,3 +141,192 @@ pub fn create_test_app_state_with_slides() -> AppState {
     let slide_service: Arc<dyn SlideService> = Arc::new(MockSlideService::new());
     AppState::new().with_slide_service(slide_service)
 }
+
+/// Serve `app` on a random local port
+pub async fn serve_app(app: Router) -> (SocketAddr, JoinHandle<()>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let handle = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    // Give server time to start
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    (addr, handle)
+}
+
+/// Serve the WebSocket endpoint for `state` on a random local port
+pub async fn start_test_server(state: AppState) -> (SocketAddr, JoinHandle<()>) {
+    let app = Router::new()
+        .route("/ws", get(pathcollab_server::server::ws_handler))
+        .with_state(state);
+    serve_app(app).await
+}
+
+/// Open a WebSocket connection to a test server
+pub async fn connect(addr: SocketAddr) -> WsStream {
+    connect_async(format!("ws://{}/ws", addr)).await.unwrap().0
+}
+
+/// Serves the WebSocket endpoint over in-memory connections
+///
+/// Nothing leaves the process, so tests using it can run with paused time
+/// (`#[tokio::test(start_paused = true)]`): while every task waits, no message
+/// is in flight and the clock can safely jump to the next timer.
+#[derive(Clone)]
+pub struct MemoryServer {
+    app: Router,
+}
+
+impl MemoryServer {
+    pub fn new(state: AppState) -> Self {
+        Self::for_app(
+            Router::new()
+                .route("/ws", get(pathcollab_server::server::ws_handler))
+                .with_state(state),
+        )
+    }
+
+    pub fn for_app(app: Router) -> Self {
+        Self { app }
+    }
+
+    /// Open a WebSocket connection to `/ws`
+    pub async fn connect(&self) -> MemoryWsStream {
+        self.try_connect().await.unwrap()
+    }
+
+    /// Open a WebSocket connection to `/ws`, returning the upgrade error if
+    /// the server refuses it
+    pub async fn try_connect(&self) -> Result<MemoryWsStream, tungstenite::Error> {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let service = hyper_util::service::TowerToHyperService::new(self.app.clone());
+        tokio::spawn(async move {
+            let _ = hyper::server::conn::http1::Builder::new()
+                .serve_connection(hyper_util::rt::TokioIo::new(server), service)
+                .with_upgrades()
+                .await;
+        });
+        Ok(client_async("ws://localhost/ws", client).await?.0)
+    }
+}
+
+pub async fn send<S>(ws: &mut WebSocketStream<S>, msg: &ClientMessage)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    ws.send(Message::Text(serde_json::to_string(msg).unwrap().into()))
+        .await
+        .unwrap();
+}
+
+/// Collect server messages until one matches `pred` (or the timeout elapses)
+pub async fn collect_until<S>(
+    ws: &mut WebSocketStream<S>,
+    mut pred: impl FnMut(&ServerMessage) -> bool,
+) -> Vec<ServerMessage>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut seen = Vec::new();
+    let _ = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(msg) = ws.next().await {
+            if let Ok(Message::Text(text)) = msg {
+                if let Ok(server_msg) = serde_json::from_str::<ServerMessage>(&text) {
+                    let done = pred(&server_msg);
+                    seen.push(server_msg);
+                    if done {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+    .await;
+    seen
+}
+
+/// Wait for the first server message matching `pred`
+pub async fn recv_until<S>(
+    ws: &mut WebSocketStream<S>,
+    mut pred: impl FnMut(&ServerMessage) -> bool,
+) -> Option<ServerMessage>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    collect_until(ws, |m| pred(m))
+        .await
+        .pop()
+        .filter(|m| pred(m))
+}
+
+/// Session created by [`create_session`]
+pub struct CreatedSession {
+    pub session: SessionSnapshot,
+    pub join_secret: String,
+    pub presenter_key: String,
+}
+
+/// Create a session on the test slide, panicking if it isn't created
+pub async fn create_session<S>(ws: &mut WebSocketStream<S>) -> CreatedSession
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    send(
+        ws,
+        &ClientMessage::CreateSession {
+            slide_id: "test-slide".to_string(),
+            max_followers: None,
+            seq: 1,
+        },
+    )
+    .await;
+    match recv_until(ws, |m| matches!(m, ServerMessage::SessionCreated { .. })).await {
+        Some(ServerMessage::SessionCreated {
+            session,
+            join_secret,
+            presenter_key,
+            ..
+        }) => CreatedSession {
+            session,
+            join_secret,
+            presenter_key,
+        },
+        _ => panic!("Session should be created"),
+    }
+}
+
+/// Join a session and return the server's answer: `SessionJoined`,
+/// `SessionUnchanged` or `SessionError`
+pub async fn join<S>(
+    ws: &mut WebSocketStream<S>,
+    session_id: &str,
+    join_secret: &str,
+    last_seen_rev: Option<u64>,
+) -> ServerMessage
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    send(
+        ws,
+        &ClientMessage::JoinSession {
+            session_id: session_id.to_string(),
+            join_secret: join_secret.to_string(),
+            last_seen_rev,
+            reconnect_token: None,
+            seq: 1,
+        },
+    )
+    .await;
+    recv_until(ws, |m| {
+        matches!(
+            m,
+            ServerMessage::SessionJoined { .. }
+                | ServerMessage::SessionUnchanged { .. }
+                | ServerMessage::SessionError { .. }
+        )
+    })
+    .await
+    .expect("Join should be answered")
+}
@@ -58,7 +58,7 @@ mod http_routes {
 
 mod session_management {
     use super::*;
-    use pathcollab_server::protocol::ParticipantRole;
+    use pathcollab_server::protocol::{CoordinateSpace, ParticipantRole};
     use pathcollab_server::session::manager::SessionManager;
     use uuid::Uuid;
 
@@ -171,7 +171,13 @@ mod session_management {
 
         // Update cursor
         let result = manager
-            .update_cursor(&session.id, follower.id, 100.0, 200.0)
+            .update_cursor(
+                &session.id,
+                follower.id,
+                100.0,
+                200.0,
+                CoordinateSpace::SlidePixels,
+            )
             .await;
 
         assert!(result.is_ok());
@@ -254,13 +260,13 @@ mod session_management {
 
         // Valid presenter key should authenticate
         let result = manager
-            .authenticate_presenter(&session.id, &presenter_key)
+            .authenticate_presenter(&session.id, presenter_id, &presenter_key)
             .await;
         assert!(result.is_ok());
 
         // Invalid presenter key should fail
         let result = manager
-            .authenticate_presenter(&session.id, "invalid_key")
+            .authenticate_presenter(&session.id, presenter_id, "invalid_key")
             .await;
         assert!(result.is_err());
     }
@@ -346,6 +352,11 @@ mod tile_serving {
         assert!(metadata["height"].is_number());
         assert!(metadata["tile_size"].is_number());
         assert!(metadata["num_levels"].is_number());
+
+        // Microns per pixel and magnification for scale bars
+        assert_eq!(metadata["mpp_x"], 0.25);
+        assert_eq!(metadata["mpp_y"], 0.25);
+        assert_eq!(metadata["objective_power"], 40.0);
     }
 
     /// Phase 1 spec: GET /api/slide/:id returns 404 for non-existent slide
@@ -380,36 +391,13 @@ mod tile_serving {
 
 mod websocket_protocol {
     use super::*;
-    use axum::{Router, routing::get};
     use pathcollab_server::protocol::{ClientMessage, ServerMessage};
-    use std::net::SocketAddr;
     use tokio_tungstenite::{connect_async, tungstenite::Message};
 
-    /// Start a test server on a random port
-    async fn start_test_server() -> (SocketAddr, tokio::task::JoinHandle<()>) {
-        let state = create_test_app_state_with_slides();
-
-        let app = Router::new()
-            .route("/ws", get(pathcollab_server::server::ws_handler))
-            .with_state(state);
-
-        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
-
-        let handle = tokio::spawn(async move {
-            axum::serve(listener, app).await.unwrap();
-        });
-
-        // Give server time to start
-        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-
-        (addr, handle)
-    }
-
     /// Phase 1 spec: WebSocket connection establishes successfully
     #[tokio::test]
     async fn test_websocket_connection_establishes() {
-        let (addr, server_handle) = start_test_server().await;
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
 
         let ws_url = format!("ws://{}/ws", addr);
         let result = connect_async(&ws_url).await;
@@ -419,130 +407,12 @@ mod websocket_protocol {
         server_handle.abort();
     }
 
-    /// Phase 1 spec: Server responds to ping with pong
-    #[tokio::test]
-    async fn test_ping_pong_protocol() {
-        use futures_util::{SinkExt, StreamExt};
-
-        let (addr, server_handle) = start_test_server().await;
-        let ws_url = format!("ws://{}/ws", addr);
-
-        let (mut ws_stream, _) = connect_async(&ws_url).await.unwrap();
-
-        // Send ping message
-        let ping_msg = ClientMessage::Ping { seq: 1 };
-        let ping_json = serde_json::to_string(&ping_msg).unwrap();
-        ws_stream
-            .send(Message::Text(ping_json.into()))
-            .await
-            .unwrap();
-
-        // Expect pong response
-        let mut received_pong = false;
-        let timeout = tokio::time::timeout(std::time::Duration::from_secs(2), async {
-            while let Some(msg) = ws_stream.next().await {
-                if let Ok(Message::Text(text)) = msg {
-                    if let Ok(server_msg) = serde_json::from_str::<ServerMessage>(&text) {
-                        if matches!(server_msg, ServerMessage::Pong) {
-                            received_pong = true;
-                            break;
-                        }
-                    }
-                }
-            }
-        });
-
-        let _ = timeout.await;
-        assert!(received_pong, "Server should respond with pong");
-
-        server_handle.abort();
-    }
-
-    /// Phase 1 spec: create_session returns session_created with valid IDs
-    #[tokio::test]
-    async fn test_create_session_over_websocket() {
-        use futures_util::{SinkExt, StreamExt};
-
-        let (addr, server_handle) = start_test_server().await;
-        let ws_url = format!("ws://{}/ws", addr);
-
-        let (mut ws_stream, _) = connect_async(&ws_url).await.unwrap();
-
-        // Send create_session message
-        let create_msg = ClientMessage::CreateSession {
-            slide_id: "test-slide".to_string(),
-            seq: 1,
-        };
-        let json = serde_json::to_string(&create_msg).unwrap();
-        ws_stream.send(Message::Text(json.into())).await.unwrap();
-
-        // Wait for session_created response
-        let mut session_created = false;
-        let mut session_id: Option<String> = None;
-        let mut join_secret: Option<String> = None;
-        let mut presenter_key: Option<String> = None;
-
-        let timeout = tokio::time::timeout(std::time::Duration::from_secs(5), async {
-            while let Some(msg) = ws_stream.next().await {
-                if let Ok(Message::Text(text)) = msg {
-                    if let Ok(server_msg) = serde_json::from_str::<ServerMessage>(&text) {
-                        match server_msg {
-                            ServerMessage::SessionCreated {
-                                session,
-                                join_secret: js,
-                                presenter_key: pk,
-                            } => {
-                                session_created = true;
-                                session_id = Some(session.id);
-                                join_secret = Some(js);
-                                presenter_key = Some(pk);
-                                break;
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            }
-        });
-
-        let _ = timeout.await;
-
-        // Phase 1 spec: SessionCreated must include all required fields
-        assert!(session_created, "Should receive session_created");
-        assert!(session_id.is_some(), "Should have session_id");
-        assert!(join_secret.is_some(), "Should have join_secret");
-        assert!(presenter_key.is_some(), "Should have presenter_key");
-
-        // Phase 1 spec: Session ID must be 10-char base32
-        let sid = session_id.unwrap();
-        assert_eq!(sid.len(), 10, "Session ID should be 10 characters");
-        assert!(
-            sid.chars()
-                .all(|c| "abcdefghijklmnopqrstuvwxyz234567".contains(c)),
-            "Session ID should be base32"
-        );
-
-        // Phase 1 spec: join_secret must have 128+ bits entropy (32+ hex chars)
-        assert!(
-            join_secret.unwrap().len() >= 32,
-            "join_secret should have sufficient entropy"
-        );
-
-        // Phase 1 spec: presenter_key must have 192+ bits entropy (48+ hex chars)
-        assert!(
-            presenter_key.unwrap().len() >= 48,
-            "presenter_key should have sufficient entropy"
-        );
-
-        server_handle.abort();
-    }
-
     /// Phase 1 spec: join_session with valid secret succeeds
     #[tokio::test]
     async fn test_join_session_over_websocket() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
         let ws_url = format!("ws://{}/ws", addr);
 
         // First connection: create session
@@ -550,6 +420,7 @@ mod websocket_protocol {
 
         let create_msg = ClientMessage::CreateSession {
             slide_id: "test-slide".to_string(),
+            max_followers: None,
             seq: 1,
         };
         ws1.send(Message::Text(
@@ -591,6 +462,7 @@ mod websocket_protocol {
             session_id: session_id.clone(),
             join_secret: join_secret.clone(),
             last_seen_rev: None,
+            reconnect_token: None,
             seq: 1,
         };
         ws2.send(Message::Text(
@@ -606,7 +478,7 @@ mod websocket_protocol {
             while let Some(msg) = ws2.next().await {
                 if let Ok(Message::Text(text)) = msg {
                     if let Ok(server_msg) = serde_json::from_str::<ServerMessage>(&text) {
-                        if let ServerMessage::SessionJoined { session, you } = server_msg {
+                        if let ServerMessage::SessionJoined { session, you, .. } = server_msg {
                             session_joined = true;
                             // Verify session matches
                             assert_eq!(session.id, session_id);
@@ -631,13 +503,14 @@ mod websocket_protocol {
     async fn test_join_session_invalid_secret_fails() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
         let ws_url = format!("ws://{}/ws", addr);
 
         // First: create session
         let (mut ws1, _) = connect_async(&ws_url).await.unwrap();
         let create_msg = ClientMessage::CreateSession {
             slide_id: "test-slide".to_string(),
+            max_followers: None,
             seq: 1,
         };
         ws1.send(Message::Text(
@@ -667,6 +540,7 @@ mod websocket_protocol {
             session_id: session_id.clone(),
             join_secret: "wrong_secret".to_string(),
             last_seen_rev: None,
+            reconnect_token: None,
             seq: 1,
         };
         ws2.send(Message::Text(
@@ -701,13 +575,16 @@ mod websocket_protocol {
     async fn test_ack_message_contains_seq() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
         let ws_url = format!("ws://{}/ws", addr);
 
         let (mut ws_stream, _) = connect_async(&ws_url).await.unwrap();
 
         // Send ping with specific seq
-        let ping_msg = ClientMessage::Ping { seq: 42 };
+        let ping_msg = ClientMessage::Ping {
+            seq: 42,
+            client_ts: None,
+        };
         ws_stream
             .send(Message::Text(
                 serde_json::to_string(&ping_msg).unwrap().into(),
@@ -746,40 +623,21 @@ mod websocket_protocol {
 
 mod phase2_presence {
     use super::*;
-    use axum::{Router, routing::get};
-    use pathcollab_server::protocol::{ClientMessage, ServerMessage};
-    use tokio_tungstenite::{connect_async, tungstenite::Message};
-
-    async fn start_test_server() -> (std::net::SocketAddr, tokio::task::JoinHandle<()>) {
-        let state = create_test_app_state_with_slides();
-
-        let app = Router::new()
-            .route("/ws", get(pathcollab_server::server::ws_handler))
-            .with_state(state);
-
-        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
-
-        let handle = tokio::spawn(async move {
-            axum::serve(listener, app).await.unwrap();
-        });
-
-        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-        (addr, handle)
-    }
+    use pathcollab_server::protocol::{ClientMessage, CoordinateSpace, ServerMessage};
+    use tokio_tungstenite::tungstenite::Message;
 
     /// Phase 2 spec: Cursor updates are stored and broadcast to session
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_cursor_update_broadcast_to_session() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
-        let ws_url = format!("ws://{}/ws", addr);
+        let server = MemoryServer::new(create_test_app_state_with_slides());
 
         // Presenter creates session
-        let (mut presenter, _) = connect_async(&ws_url).await.unwrap();
+        let mut presenter = server.connect().await;
         let create_msg = ClientMessage::CreateSession {
             slide_id: "test-slide".to_string(),
+            max_followers: None,
             seq: 1,
         };
         presenter
@@ -812,11 +670,12 @@ mod phase2_presence {
         assert!(!session_id.is_empty());
 
         // Follower joins session
-        let (mut follower, _) = connect_async(&ws_url).await.unwrap();
+        let mut follower = server.connect().await;
         let join_msg = ClientMessage::JoinSession {
             session_id: session_id.clone(),
             join_secret: join_secret.clone(),
             last_seen_rev: None,
+            reconnect_token: None,
             seq: 1,
         };
         follower
@@ -833,6 +692,7 @@ mod phase2_presence {
         let cursor_msg = ClientMessage::CursorUpdate {
             x: 500.0,
             y: 300.0,
+            space: CoordinateSpace::SlidePixels,
             seq: 2,
         };
         presenter
@@ -873,24 +733,22 @@ mod phase2_presence {
             received_cursor,
             "Follower should receive presenter's cursor update"
         );
-
-        server_handle.abort();
     }
 
     /// Phase 2 spec: Presenter viewport broadcast to followers at 10Hz
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_presenter_viewport_broadcast() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
-        let ws_url = format!("ws://{}/ws", addr);
+        let server = MemoryServer::new(create_test_app_state_with_slides());
 
         // Presenter creates session
-        let (mut presenter, _) = connect_async(&ws_url).await.unwrap();
+        let mut presenter = server.connect().await;
         presenter
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::CreateSession {
                     slide_id: "test-slide".to_string(),
+                    max_followers: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -920,13 +778,14 @@ mod phase2_presence {
         let _ = timeout.await;
 
         // Follower joins
-        let (mut follower, _) = connect_async(&ws_url).await.unwrap();
+        let mut follower = server.connect().await;
         follower
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::JoinSession {
                     session_id: session_id.clone(),
                     join_secret: join_secret.clone(),
                     last_seen_rev: None,
+                    reconnect_token: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -944,6 +803,7 @@ mod phase2_presence {
                     center_x: 0.5,
                     center_y: 0.5,
                     zoom: 2.0,
+                    transition_ms: None,
                     seq: 2,
                 })
                 .unwrap()
@@ -958,7 +818,7 @@ mod phase2_presence {
             while let Some(msg) = follower.next().await {
                 if let Ok(Message::Text(text)) = msg {
                     if let Ok(server_msg) = serde_json::from_str::<ServerMessage>(&text) {
-                        if let ServerMessage::PresenterViewport { viewport } = server_msg {
+                        if let ServerMessage::PresenterViewport { viewport, .. } = server_msg {
                             // Phase 2 spec: viewport has center_x, center_y, zoom
                             assert!((viewport.center_x - 0.5).abs() < 0.01);
                             assert!((viewport.center_y - 0.5).abs() < 0.01);
@@ -976,24 +836,22 @@ mod phase2_presence {
             received_viewport,
             "Follower should receive presenter viewport update"
         );
-
-        server_handle.abort();
     }
 
     /// Phase 2 spec: Snap to presenter returns current presenter viewport
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_snap_to_presenter() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
-        let ws_url = format!("ws://{}/ws", addr);
+        let server = MemoryServer::new(create_test_app_state_with_slides());
 
         // Presenter creates session
-        let (mut presenter, _) = connect_async(&ws_url).await.unwrap();
+        let mut presenter = server.connect().await;
         presenter
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::CreateSession {
                     slide_id: "test-slide".to_string(),
+                    max_followers: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1029,6 +887,7 @@ mod phase2_presence {
                     center_x: 0.7,
                     center_y: 0.3,
                     zoom: 4.0,
+                    transition_ms: None,
                     seq: 2,
                 })
                 .unwrap()
@@ -1039,13 +898,14 @@ mod phase2_presence {
         tokio::time::sleep(std::time::Duration::from_millis(200)).await;
 
         // Follower joins and snaps
-        let (mut follower, _) = connect_async(&ws_url).await.unwrap();
+        let mut follower = server.connect().await;
         follower
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::JoinSession {
                     session_id: session_id.clone(),
                     join_secret: join_secret.clone(),
                     last_seen_rev: None,
+                    reconnect_token: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1072,7 +932,7 @@ mod phase2_presence {
             while let Some(msg) = follower.next().await {
                 if let Ok(Message::Text(text)) = msg {
                     if let Ok(server_msg) = serde_json::from_str::<ServerMessage>(&text) {
-                        if let ServerMessage::PresenterViewport { viewport } = server_msg {
+                        if let ServerMessage::PresenterViewport { viewport, .. } = server_msg {
                             // Should receive the viewport presenter set earlier
                             if (viewport.zoom - 4.0).abs() < 0.01 {
                                 assert!((viewport.center_x - 0.7).abs() < 0.01);
@@ -1091,24 +951,22 @@ mod phase2_presence {
             received_viewport,
             "Snap to presenter should return presenter's viewport"
         );
-
-        server_handle.abort();
     }
 
     /// Phase 2 spec: Follower viewport updates don't broadcast (only presenter)
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_follower_viewport_not_broadcast() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
-        let ws_url = format!("ws://{}/ws", addr);
+        let server = MemoryServer::new(create_test_app_state_with_slides());
 
         // Presenter creates session
-        let (mut presenter, _) = connect_async(&ws_url).await.unwrap();
+        let mut presenter = server.connect().await;
         presenter
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::CreateSession {
                     slide_id: "test-slide".to_string(),
+                    max_followers: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1138,13 +996,14 @@ mod phase2_presence {
         let _ = timeout.await;
 
         // Follower joins
-        let (mut follower, _) = connect_async(&ws_url).await.unwrap();
+        let mut follower = server.connect().await;
         follower
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::JoinSession {
                     session_id: session_id.clone(),
                     join_secret: join_secret.clone(),
                     last_seen_rev: None,
+                    reconnect_token: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1162,6 +1021,7 @@ mod phase2_presence {
                     center_x: 0.1,
                     center_y: 0.1,
                     zoom: 1.0,
+                    transition_ms: None,
                     seq: 2,
                 })
                 .unwrap()
@@ -1176,7 +1036,7 @@ mod phase2_presence {
                 while let Some(msg) = presenter.next().await {
                     if let Ok(Message::Text(text)) = msg {
                         if let Ok(server_msg) = serde_json::from_str::<ServerMessage>(&text) {
-                            if let ServerMessage::PresenterViewport { viewport } = server_msg {
+                            if let ServerMessage::PresenterViewport { viewport, .. } = server_msg {
                                 // If we receive this with follower's viewport, that's wrong
                                 if (viewport.center_x - 0.1).abs() < 0.01 {
                                     return true;
@@ -1194,47 +1054,27 @@ mod phase2_presence {
             received_follower_viewport.is_err() || !received_follower_viewport.unwrap(),
             "Presenter should NOT receive follower's viewport updates"
         );
-
-        server_handle.abort();
     }
 }
 
 mod phase2_participants {
     use super::*;
-    use axum::{Router, routing::get};
     use pathcollab_server::protocol::{ClientMessage, ServerMessage};
     use tokio_tungstenite::{connect_async, tungstenite::Message};
 
-    async fn start_test_server() -> (std::net::SocketAddr, tokio::task::JoinHandle<()>) {
-        let state = create_test_app_state_with_slides();
-
-        let app = Router::new()
-            .route("/ws", get(pathcollab_server::server::ws_handler))
-            .with_state(state);
-
-        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
-
-        let handle = tokio::spawn(async move {
-            axum::serve(listener, app).await.unwrap();
-        });
-
-        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-        (addr, handle)
-    }
-
     /// Phase 2 spec: Participant names use adjective + animal format
     #[tokio::test]
     async fn test_participant_name_format() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
         let ws_url = format!("ws://{}/ws", addr);
 
         let (mut ws, _) = connect_async(&ws_url).await.unwrap();
         ws.send(Message::Text(
             serde_json::to_string(&ClientMessage::CreateSession {
                 slide_id: "test-slide".to_string(),
+                max_followers: None,
                 seq: 1,
             })
             .unwrap()
@@ -1287,7 +1127,7 @@ mod phase2_participants {
     async fn test_participant_color_assignment() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
         let ws_url = format!("ws://{}/ws", addr);
 
         // Create session
@@ -1296,6 +1136,7 @@ mod phase2_participants {
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::CreateSession {
                     slide_id: "test-slide".to_string(),
+                    max_followers: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1344,6 +1185,7 @@ mod phase2_participants {
                         session_id: session_id.clone(),
                         join_secret: join_secret.clone(),
                         last_seen_rev: None,
+                        reconnect_token: None,
                         seq: 1,
                     })
                     .unwrap()
@@ -1390,19 +1232,19 @@ mod phase2_participants {
     }
 
     /// Phase 2 spec: Participant joined/left events broadcast to session
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_participant_join_leave_events() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
-        let ws_url = format!("ws://{}/ws", addr);
+        let server = MemoryServer::new(create_test_app_state_with_slides());
 
         // Presenter creates session
-        let (mut presenter, _) = connect_async(&ws_url).await.unwrap();
+        let mut presenter = server.connect().await;
         presenter
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::CreateSession {
                     slide_id: "test-slide".to_string(),
+                    max_followers: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1435,7 +1277,7 @@ mod phase2_participants {
         tokio::time::sleep(std::time::Duration::from_millis(200)).await;
 
         // Follower joins
-        let (follower, _) = connect_async(&ws_url).await.unwrap();
+        let follower = server.connect().await;
         let (mut write, mut _read) = follower.split();
         write
             .send(Message::Text(
@@ -1443,6 +1285,7 @@ mod phase2_participants {
                     session_id: session_id.clone(),
                     join_secret: join_secret.clone(),
                     last_seen_rev: None,
+                    reconnect_token: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1499,8 +1342,6 @@ mod phase2_participants {
             received_leave,
             "Presenter should receive participant_left event"
         );
-
-        server_handle.abort();
     }
 
     /// Phase 2 spec: First user becomes presenter
@@ -1508,13 +1349,14 @@ mod phase2_participants {
     async fn test_first_user_is_presenter() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
         let ws_url = format!("ws://{}/ws", addr);
 
         let (mut ws, _) = connect_async(&ws_url).await.unwrap();
         ws.send(Message::Text(
             serde_json::to_string(&ClientMessage::CreateSession {
                 slide_id: "test-slide".to_string(),
+                max_followers: None,
                 seq: 1,
             })
             .unwrap()
@@ -1547,42 +1389,23 @@ mod phase2_participants {
 
 mod phase2_robustness {
     use super::*;
-    use axum::{Router, routing::get};
     use pathcollab_server::protocol::{ClientMessage, ServerMessage};
-    use tokio_tungstenite::{connect_async, tungstenite::Message};
-
-    async fn start_test_server() -> (std::net::SocketAddr, tokio::task::JoinHandle<()>) {
-        let state = create_test_app_state_with_slides();
-
-        let app = Router::new()
-            .route("/ws", get(pathcollab_server::server::ws_handler))
-            .with_state(state);
-
-        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
-
-        let handle = tokio::spawn(async move {
-            axum::serve(listener, app).await.unwrap();
-        });
-
-        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-        (addr, handle)
-    }
+    use tokio_tungstenite::tungstenite::Message;
 
     /// Phase 2 spec: Session survives participant reconnection
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_session_survives_follower_reconnect() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
-        let ws_url = format!("ws://{}/ws", addr);
+        let server = MemoryServer::new(create_test_app_state_with_slides());
 
         // Create session
-        let (mut presenter, _) = connect_async(&ws_url).await.unwrap();
+        let mut presenter = server.connect().await;
         presenter
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::CreateSession {
                     slide_id: "test-slide".to_string(),
+                    max_followers: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1613,7 +1436,7 @@ mod phase2_robustness {
         assert!(!session_id.is_empty());
 
         // Follower joins
-        let (follower, _) = connect_async(&ws_url).await.unwrap();
+        let follower = server.connect().await;
         let (mut write, _read) = follower.split();
         write
             .send(Message::Text(
@@ -1621,6 +1444,7 @@ mod phase2_robustness {
                     session_id: session_id.clone(),
                     join_secret: join_secret.clone(),
                     last_seen_rev: None,
+                    reconnect_token: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1637,13 +1461,14 @@ mod phase2_robustness {
         tokio::time::sleep(std::time::Duration::from_millis(500)).await;
 
         // Reconnect follower
-        let (mut follower2, _) = connect_async(&ws_url).await.unwrap();
+        let mut follower2 = server.connect().await;
         follower2
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::JoinSession {
                     session_id: session_id.clone(),
                     join_secret: join_secret.clone(),
                     last_seen_rev: None,
+                    reconnect_token: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1674,49 +1499,28 @@ mod phase2_robustness {
             rejoined,
             "Follower should be able to rejoin after disconnect"
         );
-
-        server_handle.abort();
     }
 }
 
 mod tissue_overlay_sync {
     use super::*;
-    use axum::{Router, routing::get};
     use pathcollab_server::protocol::{ClientMessage, ServerMessage};
-    use tokio_tungstenite::{connect_async, tungstenite::Message};
-
-    async fn start_test_server() -> (std::net::SocketAddr, tokio::task::JoinHandle<()>) {
-        let state = create_test_app_state_with_slides();
-
-        let app = Router::new()
-            .route("/ws", get(pathcollab_server::server::ws_handler))
-            .with_state(state);
-
-        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
-
-        let handle = tokio::spawn(async move {
-            axum::serve(listener, app).await.unwrap();
-        });
-
-        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-        (addr, handle)
-    }
+    use tokio_tungstenite::tungstenite::Message;
 
     /// Test: Presenter tissue overlay update is broadcast to followers
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_tissue_overlay_update_broadcast_to_follower() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
-        let ws_url = format!("ws://{}/ws", addr);
+        let server = MemoryServer::new(create_test_app_state_with_slides());
 
         // Presenter creates session
-        let (mut presenter_ws, _) = connect_async(&ws_url).await.unwrap();
+        let mut presenter_ws = server.connect().await;
         presenter_ws
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::CreateSession {
                     slide_id: "test-slide".to_string(),
+                    max_followers: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1748,13 +1552,14 @@ mod tissue_overlay_sync {
         assert!(!session_id.is_empty(), "Session should be created");
 
         // Follower joins session
-        let (mut follower_ws, _) = connect_async(&ws_url).await.unwrap();
+        let mut follower_ws = server.connect().await;
         follower_ws
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::JoinSession {
                     session_id: session_id.clone(),
                     join_secret: join_secret.clone(),
                     last_seen_rev: None,
+                    reconnect_token: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1835,24 +1640,22 @@ mod tissue_overlay_sync {
             received_tissue_types, tissue_classes,
             "Visible tissue types should match what presenter sent"
         );
-
-        server_handle.abort();
     }
 
     /// Test: Tissue overlay state is included in session snapshot when follower joins
-    #[tokio::test]
+    #[tokio::test(start_paused = true)]
     async fn test_tissue_overlay_state_included_in_session_snapshot() {
         use futures_util::{SinkExt, StreamExt};
 
-        let (addr, server_handle) = start_test_server().await;
-        let ws_url = format!("ws://{}/ws", addr);
+        let server = MemoryServer::new(create_test_app_state_with_slides());
 
         // Presenter creates session
-        let (mut presenter_ws, _) = connect_async(&ws_url).await.unwrap();
+        let mut presenter_ws = server.connect().await;
         presenter_ws
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::CreateSession {
                     slide_id: "test-slide".to_string(),
+                    max_followers: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1903,13 +1706,14 @@ mod tissue_overlay_sync {
         tokio::time::sleep(std::time::Duration::from_millis(200)).await;
 
         // NOW follower joins - they should get the tissue overlay state in the session snapshot
-        let (mut follower_ws, _) = connect_async(&ws_url).await.unwrap();
+        let mut follower_ws = server.connect().await;
         follower_ws
             .send(Message::Text(
                 serde_json::to_string(&ClientMessage::JoinSession {
                     session_id: session_id.clone(),
                     join_secret: join_secret.clone(),
                     last_seen_rev: None,
+                    reconnect_token: None,
                     seq: 1,
                 })
                 .unwrap()
@@ -1959,6 +1763,1365 @@ mod tissue_overlay_sync {
             snapshot_tissue_types, tissue_classes,
             "Visible tissue types in snapshot should match what presenter set"
         );
+    }
+}
+
+// ============================================================================
+// Rejoin Revision Tests
+// ============================================================================
+
+mod rejoin_revision {
+    use super::*;
+    use pathcollab_server::protocol::{ClientMessage, ServerMessage};
+
+    /// Join as a new follower, returning the socket, participant id and rev
+    async fn join_follower(
+        server: &MemoryServer,
+        created: &CreatedSession,
+    ) -> (MemoryWsStream, uuid::Uuid, u64) {
+        let mut ws = server.connect().await;
+        let ServerMessage::SessionJoined { session, you, .. } =
+            join(&mut ws, &created.session.id, &created.join_secret, None).await
+        else {
+            panic!("Follower should join");
+        };
+        (ws, you.id, session.rev)
+    }
+
+    /// Close the follower's socket and wait until the presenter sees it leave
+    async fn disconnect(mut ws: MemoryWsStream, id: uuid::Uuid, presenter_ws: &mut MemoryWsStream) {
+        ws.close(None).await.unwrap();
+        let left = recv_until(presenter_ws, |m| {
+            matches!(m, ServerMessage::ParticipantLeft { participant_id } if *participant_id == id)
+        })
+        .await;
+        assert!(left.is_some(), "Presenter should see the follower leave");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reconnect_with_current_rev_receives_session_unchanged() {
+        let server = MemoryServer::new(create_test_app_state_with_slides());
+
+        let mut presenter_ws = server.connect().await;
+        let created = create_session(&mut presenter_ws).await;
+        // Give time for the presenter to subscribe to session broadcasts
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        let (follower_ws, old_id, seen_rev) = join_follower(&server, &created).await;
+        disconnect(follower_ws, old_id, &mut presenter_ws).await;
+
+        // Reconnect with the rev from SessionJoined: only membership changed
+        let mut follower_ws = server.connect().await;
+        let reply = join(
+            &mut follower_ws,
+            &created.session.id,
+            &created.join_secret,
+            Some(seen_rev),
+        )
+        .await;
+        match reply {
+            ServerMessage::SessionUnchanged {
+                rev,
+                you,
+                presenter,
+                followers,
+                ..
+            } => {
+                assert!(rev > seen_rev, "Unchanged reply should carry the new rev");
+                assert_eq!(you.role, ParticipantRole::Follower);
+                assert_eq!(presenter.id, created.session.presenter.id);
+                let ids: Vec<uuid::Uuid> = followers.iter().map(|f| f.id).collect();
+                assert_eq!(ids, vec![you.id], "The old participant should be gone");
+            }
+            other => panic!("Expected session_unchanged, got {:?}", other),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reconnect_with_stale_rev_receives_full_snapshot() {
+        let server = MemoryServer::new(create_test_app_state_with_slides());
+
+        let mut presenter_ws = server.connect().await;
+        let created = create_session(&mut presenter_ws).await;
+        // Give time for the presenter to subscribe to session broadcasts
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        let (follower_ws, old_id, seen_rev) = join_follower(&server, &created).await;
+        disconnect(follower_ws, old_id, &mut presenter_ws).await;
+
+        // Presenter moves the viewport while the follower is away
+        send(
+            &mut presenter_ws,
+            &ClientMessage::ViewportUpdate {
+                center_x: 0.25,
+                center_y: 0.75,
+                zoom: 4.0,
+                transition_ms: None,
+                seq: 2,
+            },
+        )
+        .await;
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let mut follower_ws = server.connect().await;
+        let reply = join(
+            &mut follower_ws,
+            &created.session.id,
+            &created.join_secret,
+            Some(seen_rev),
+        )
+        .await;
+        match reply {
+            ServerMessage::SessionJoined { session, .. } => {
+                assert!(
+                    session.rev > seen_rev,
+                    "Snapshot should reflect newer state"
+                );
+                assert_eq!(session.presenter_viewport.zoom, 4.0);
+            }
+            other => panic!("Expected session_joined, got {:?}", other),
+        }
+    }
+}
+
+// ============================================================================
+// Presenter Audit Log Tests
+// ============================================================================
+
+mod audit_log {
+    use super::*;
+    use pathcollab_server::audit::{AuditAction, AuditRecord, FileAuditSink};
+    use pathcollab_server::protocol::{AckStatus, ClientMessage, ServerMessage};
+    use std::sync::Arc;
+
+    /// Test: A presenter slide change is appended to the audit log
+    #[tokio::test(start_paused = true)]
+    async fn test_slide_change_writes_audit_record() {
+        let audit_path =
+            std::env::temp_dir().join(format!("pathcollab-audit-{}.jsonl", uuid::Uuid::new_v4()));
+        let sink = FileAuditSink::open(&audit_path).unwrap();
+        let state = create_test_app_state_with_slides().with_audit_sink(Arc::new(sink));
+        let server = MemoryServer::new(state);
+
+        // Presenter creates session
+        let mut presenter_ws = server.connect().await;
+        let session = create_session(&mut presenter_ws).await.session;
+
+        // Presenter changes slide and waits for the ack
+        send(
+            &mut presenter_ws,
+            &ClientMessage::ChangeSlide {
+                slide_id: "test-slide".to_string(),
+                seq: 2,
+            },
+        )
+        .await;
+        let ack = recv_until(&mut presenter_ws, |m| {
+            matches!(m, ServerMessage::Ack { ack_seq: 2, .. })
+        })
+        .await;
+        assert!(
+            matches!(
+                ack,
+                Some(ServerMessage::Ack {
+                    status: AckStatus::Ok,
+                    ..
+                })
+            ),
+            "Slide change should succeed"
+        );
+
+        // Records are written by a background task, so poll for the line
+        let record = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            loop {
+                let contents = std::fs::read_to_string(&audit_path).unwrap_or_default();
+                let found = contents
+                    .lines()
+                    .map(|line| serde_json::from_str::<AuditRecord>(line).unwrap())
+                    .find(|r| matches!(r.action, AuditAction::SlideChange { .. }));
+                if let Some(record) = found {
+                    break record;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Slide change should be audited");
+
+        assert_eq!(record.session_id, session.id);
+        assert_eq!(record.actor_id, Some(session.presenter.id));
+        assert_eq!(
+            record.action,
+            AuditAction::SlideChange {
+                slide_id: "test-slide".to_string()
+            }
+        );
+        assert!(record.timestamp > 0);
+
+        let _ = std::fs::remove_file(&audit_path);
+    }
+}
+
+// ============================================================================
+// Drain Mode Tests
+// ============================================================================
+
+mod drain_mode {
+    use super::*;
+    use axum::{Router, routing::get};
+    use pathcollab_server::protocol::{ClientMessage, ErrorCode, ServerMessage};
+    use pathcollab_server::server::{admin_routes, ready};
+
+    const ADMIN_TOKEN: &str = "test-admin-token";
+
+    async fn start_drainable_server() -> (MemoryServer, Router) {
+        let state =
+            create_test_app_state_with_slides().with_admin_token(Some(ADMIN_TOKEN.to_string()));
+
+        let app = Router::new()
+            .route("/ws", get(pathcollab_server::server::ws_handler))
+            .route("/ready", get(ready))
+            .nest("/api", admin_routes())
+            .with_state(state);
+
+        (MemoryServer::for_app(app.clone()), app)
+    }
+
+    async fn post_drain(app: &Router, token: &str) -> StatusCode {
+        app.clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/admin/drain")
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    async fn get_ready(app: &Router) -> StatusCode {
+        app.clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/ready")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    /// Test: Draining rejects new sessions and joins while existing sessions keep broadcasting
+    #[tokio::test(start_paused = true)]
+    async fn test_drain_rejects_new_sessions_but_keeps_existing() {
+        let (server, app) = start_drainable_server().await;
+
+        // Existing session with a presenter and a follower
+        let mut presenter_ws = server.connect().await;
+        let created = create_session(&mut presenter_ws).await;
+        let session = created.session;
+
+        let mut follower_ws = server.connect().await;
+        assert!(
+            matches!(
+                join(&mut follower_ws, &session.id, &created.join_secret, None).await,
+                ServerMessage::SessionJoined { .. }
+            ),
+            "Follower should join before drain"
+        );
+
+        // A second follower that will drop and reconnect during the drain
+        let mut dropping_ws = server.connect().await;
+        let ServerMessage::SessionJoined {
+            session: joined,
+            reconnect_token,
+            ..
+        } = join(&mut dropping_ws, &session.id, &created.join_secret, None).await
+        else {
+            panic!("Follower should join before drain");
+        };
+
+        // Give time for the follower to subscribe to session broadcasts
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        // Enable drain (wrong token is refused)
+        assert_eq!(get_ready(&app).await, StatusCode::OK);
+        assert_eq!(post_drain(&app, "wrong").await, StatusCode::UNAUTHORIZED);
+        assert_eq!(post_drain(&app, ADMIN_TOKEN).await, StatusCode::OK);
+        assert_eq!(get_ready(&app).await, StatusCode::SERVICE_UNAVAILABLE);
+
+        // New sessions are rejected
+        let mut new_ws = server.connect().await;
+        send(
+            &mut new_ws,
+            &ClientMessage::CreateSession {
+                slide_id: "test-slide".to_string(),
+                max_followers: None,
+                seq: 1,
+            },
+        )
+        .await;
+        let reply = recv_until(&mut new_ws, |m| {
+            matches!(
+                m,
+                ServerMessage::SessionCreated { .. } | ServerMessage::SessionError { .. }
+            )
+        })
+        .await;
+        assert!(
+            matches!(
+                reply,
+                Some(ServerMessage::SessionError {
+                    code: ErrorCode::ServerDraining,
+                    ..
+                })
+            ),
+            "CreateSession should be rejected while draining, got {:?}",
+            reply
+        );
+
+        // New joins are rejected, even when they claim to have seen a revision
+        for last_seen_rev in [None, Some(0), Some(joined.rev)] {
+            let reply = join(
+                &mut new_ws,
+                &session.id,
+                &created.join_secret,
+                last_seen_rev,
+            )
+            .await;
+            assert!(
+                matches!(
+                    reply,
+                    ServerMessage::SessionError {
+                        code: ErrorCode::ServerDraining,
+                        ..
+                    }
+                ),
+                "JoinSession should be rejected while draining, got {:?}",
+                reply
+            );
+        }
+
+        // A follower holding a reconnect token from this server gets back in
+        drop(dropping_ws);
+        let mut reconnected_ws = server.connect().await;
+        send(
+            &mut reconnected_ws,
+            &ClientMessage::JoinSession {
+                session_id: session.id.clone(),
+                join_secret: created.join_secret.clone(),
+                last_seen_rev: Some(joined.rev),
+                reconnect_token: Some(reconnect_token),
+                seq: 1,
+            },
+        )
+        .await;
+        let reply = recv_until(&mut reconnected_ws, |m| {
+            matches!(
+                m,
+                ServerMessage::SessionJoined { .. }
+                    | ServerMessage::SessionUnchanged { .. }
+                    | ServerMessage::SessionError { .. }
+            )
+        })
+        .await;
+        assert!(
+            matches!(
+                reply,
+                Some(ServerMessage::SessionJoined { .. } | ServerMessage::SessionUnchanged { .. })
+            ),
+            "Reconnect should be allowed while draining, got {:?}",
+            reply
+        );
+
+        // The existing session still broadcasts presenter updates
+        send(
+            &mut presenter_ws,
+            &ClientMessage::ViewportUpdate {
+                center_x: 0.25,
+                center_y: 0.75,
+                zoom: 2.0,
+                transition_ms: None,
+                seq: 2,
+            },
+        )
+        .await;
+        let reply = recv_until(&mut follower_ws, |m| {
+            matches!(m, ServerMessage::PresenterViewport { viewport, .. } if (viewport.zoom - 2.0).abs() < 0.01)
+        })
+        .await;
+        assert!(
+            reply.is_some(),
+            "Follower should still receive presenter viewport while draining"
+        );
+    }
+}
+
+// ============================================================================
+// Snapshot Request Tests
+// ============================================================================
+
+mod request_snapshot {
+    use super::*;
+    use pathcollab_server::protocol::{ClientMessage, ServerMessage};
+
+    /// Test: A joined follower can request and receive the full current session state
+    #[tokio::test]
+    async fn test_follower_requests_full_snapshot() {
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
+
+        // Presenter creates session, follower joins
+        let mut presenter_ws = connect(addr).await;
+        let created = create_session(&mut presenter_ws).await;
+        let session = created.session;
+
+        let mut follower_ws = connect(addr).await;
+        let ServerMessage::SessionJoined { you, .. } =
+            join(&mut follower_ws, &session.id, &created.join_secret, None).await
+        else {
+            panic!("Follower should join");
+        };
+
+        // Presenter changes viewport and tissue overlay after the join
+        send(
+            &mut presenter_ws,
+            &ClientMessage::ViewportUpdate {
+                center_x: 0.3,
+                center_y: 0.6,
+                zoom: 4.0,
+                transition_ms: None,
+                seq: 2,
+            },
+        )
+        .await;
+        send(
+            &mut presenter_ws,
+            &ClientMessage::TissueOverlayUpdate {
+                enabled: true,
+                opacity: 0.4,
+                visible_tissue_types: vec![2, 5],
+                seq: 3,
+            },
+        )
+        .await;
+        recv_until(&mut presenter_ws, |m| {
+            matches!(m, ServerMessage::Ack { ack_seq: 3, .. })
+        })
+        .await
+        .expect("Tissue overlay update should be acked");
+
+        // Follower asks for a fresh snapshot
+        send(&mut follower_ws, &ClientMessage::RequestSnapshot { seq: 2 }).await;
+        let Some(ServerMessage::SessionSnapshot { session: snapshot }) =
+            recv_until(&mut follower_ws, |m| {
+                matches!(m, ServerMessage::SessionSnapshot { .. })
+            })
+            .await
+        else {
+            panic!("Follower should receive a session snapshot");
+        };
+
+        assert_eq!(snapshot.id, session.id);
+        assert_eq!(snapshot.slide.id, "test-slide");
+        assert!(
+            snapshot.rev > session.rev,
+            "Snapshot should reflect updates"
+        );
+        assert_eq!(snapshot.presenter.id, session.presenter.id);
+        assert!(
+            snapshot.followers.iter().any(|f| f.id == you.id),
+            "Snapshot should list the follower"
+        );
+        assert!((snapshot.presenter_viewport.zoom - 4.0).abs() < 0.01);
+        let tissue = snapshot
+            .tissue_overlay
+            .expect("Snapshot should include the tissue overlay state");
+        assert!(tissue.enabled);
+        assert_eq!(tissue.visible_tissue_types, vec![2, 5]);
+
+        server_handle.abort();
+    }
+
+    /// Test: Requesting a snapshot outside a session is rejected
+    #[tokio::test]
+    async fn test_request_snapshot_without_session_rejected() {
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
+
+        let mut ws = connect(addr).await;
+        send(&mut ws, &ClientMessage::RequestSnapshot { seq: 7 }).await;
+        let reply = recv_until(&mut ws, |m| matches!(m, ServerMessage::Ack { .. })).await;
+        assert!(
+            matches!(
+                reply,
+                Some(ServerMessage::Ack {
+                    ack_seq: 7,
+                    status: pathcollab_server::protocol::AckStatus::Rejected,
+                    ..
+                })
+            ),
+            "RequestSnapshot outside a session should be rejected, got {:?}",
+            reply
+        );
+
+        server_handle.abort();
+    }
+}
+
+// ============================================================================
+// WebSocket Keepalive Tests
+// ============================================================================
+
+mod ws_keepalive {
+    use super::*;
+    use futures_util::StreamExt;
+    use pathcollab_server::protocol::ClientMessage;
+    use pathcollab_server::server::{AppState, WsConfig};
+    use std::time::Duration;
+    use tokio::time::Instant;
+    use tokio_tungstenite::tungstenite::Message;
+
+    fn keepalive_state() -> AppState {
+        create_test_app_state_with_slides().with_ws_config(WsConfig {
+            ping_interval: Duration::from_millis(100),
+            ping_timeout: Duration::from_millis(100),
+            ..WsConfig::default()
+        })
+    }
+
+    /// Test: A connection that never sends anything is closed once the configured timeout elapses
+    #[tokio::test(start_paused = true)]
+    async fn test_unresponsive_connection_is_reaped() {
+        let state = keepalive_state();
+        let server = MemoryServer::new(state.clone());
+
+        let mut ws = server.connect().await;
+        let started = Instant::now();
+
+        // Drain server pings without ever replying until the server hangs up
+        let closed = tokio::time::timeout(Duration::from_secs(3), async {
+            while let Some(msg) = ws.next().await {
+                if matches!(msg, Ok(Message::Close(_)) | Err(_)) {
+                    break;
+                }
+            }
+        })
+        .await;
+
+        assert!(closed.is_ok(), "Unresponsive connection should be closed");
+        // interval + timeout = 200ms of silence; allow for one extra ping tick
+        assert!(
+            started.elapsed() < Duration::from_secs(1),
+            "Connection should be reaped on schedule, took {:?}",
+            started.elapsed()
+        );
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(state.connections.len(), 0, "Connection should be removed");
+    }
+
+    /// Test: A client that keeps talking outlives the keepalive timeout
+    #[tokio::test(start_paused = true)]
+    async fn test_active_connection_is_kept_alive() {
+        let state = keepalive_state();
+        let server = MemoryServer::new(state.clone());
+
+        let mut ws = server.connect().await;
+
+        for seq in 0..12 {
+            send(
+                &mut ws,
+                &ClientMessage::Ping {
+                    seq,
+                    client_ts: None,
+                },
+            )
+            .await;
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        assert_eq!(
+            state.connections.len(),
+            1,
+            "Active connection should stay open past the keepalive timeout"
+        );
+    }
+}
+
+// ============================================================================
+// Follower Cursor Suppression Tests
+// ============================================================================
+
+mod follower_cursors {
+    use super::*;
+    use pathcollab_server::protocol::{ClientMessage, CoordinateSpace, ServerMessage};
+    use std::time::Duration;
+
+    /// Connect a follower and return its socket and participant id
+    async fn join_follower(
+        server: &MemoryServer,
+        session_id: &str,
+        join_secret: &str,
+    ) -> (MemoryWsStream, uuid::Uuid) {
+        let mut ws = server.connect().await;
+        let ServerMessage::SessionJoined { you, .. } =
+            join(&mut ws, session_id, join_secret, None).await
+        else {
+            panic!("Follower should join");
+        };
+        (ws, you.id)
+    }
+
+    /// Test: With follower cursors hidden, only the presenter's cursor is broadcast
+    #[tokio::test(start_paused = true)]
+    async fn test_hidden_follower_cursors_not_broadcast() {
+        let server = MemoryServer::new(create_test_app_state_with_slides());
+
+        // Presenter creates session
+        let mut presenter_ws = server.connect().await;
+        let created = create_session(&mut presenter_ws).await;
+        let (session_id, presenter_id) = (created.session.id, created.session.presenter.id);
+
+        // One follower moves its cursor, the other observes
+        let (mut mover_ws, mover_id) =
+            join_follower(&server, &session_id, &created.join_secret).await;
+        let (mut observer_ws, _) = join_follower(&server, &session_id, &created.join_secret).await;
+
+        // Give time for the followers to subscribe to session broadcasts
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // Presenter hides follower cursors
+        send(
+            &mut presenter_ws,
+            &ClientMessage::SetFollowerCursors {
+                visible: false,
+                seq: 2,
+            },
+        )
+        .await;
+        let toggled = recv_until(&mut observer_ws, |m| {
+            matches!(m, ServerMessage::FollowerCursorsToggled { .. })
+        })
+        .await;
+        assert!(
+            matches!(
+                toggled,
+                Some(ServerMessage::FollowerCursorsToggled { visible: false })
+            ),
+            "Observer should be told follower cursors are hidden"
+        );
+
+        // Follower cursor first, then the presenter's
+        send(
+            &mut mover_ws,
+            &ClientMessage::CursorUpdate {
+                x: 10.0,
+                y: 20.0,
+                space: CoordinateSpace::SlidePixels,
+                seq: 2,
+            },
+        )
+        .await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        send(
+            &mut presenter_ws,
+            &ClientMessage::CursorUpdate {
+                x: 30.0,
+                y: 40.0,
+                space: CoordinateSpace::SlidePixels,
+                seq: 3,
+            },
+        )
+        .await;
+
+        let seen = collect_until(&mut observer_ws, |m| {
+            matches!(m, ServerMessage::PresenceDelta { changed, .. }
+                if changed.iter().any(|c| c.participant_id == presenter_id))
+        })
+        .await;
+        let cursor_senders: Vec<_> = seen
+            .iter()
+            .filter_map(|m| match m {
+                ServerMessage::PresenceDelta { changed, .. } => Some(changed),
+                _ => None,
+            })
+            .flatten()
+            .map(|c| c.participant_id)
+            .collect();
+
+        assert!(
+            cursor_senders.contains(&presenter_id),
+            "Presenter cursor should still be broadcast"
+        );
+        assert!(
+            !cursor_senders.contains(&mover_id),
+            "Follower cursor should not be broadcast while hidden"
+        );
+    }
+}
+
+// ============================================================================
+// Presence Staleness Tests
+// ============================================================================
+mod presence_staleness {
+    use super::*;
+    use pathcollab_server::protocol::{ClientMessage, CoordinateSpace, ServerMessage};
+    use std::time::Duration;
+
+    /// Test: An idle cursor is reported as removed and re-added when it moves again
+    ///
+    /// Runs on real time: cursor idleness is measured against wall-clock timestamps.
+    #[tokio::test]
+    async fn test_idle_cursor_reported_removed() {
+        let state = create_test_app_state_with_slides();
+        let server = MemoryServer::new(state.clone());
+
+        // Presenter creates session, follower joins
+        let mut presenter_ws = server.connect().await;
+        let created = create_session(&mut presenter_ws).await;
+
+        let mut follower_ws = server.connect().await;
+        let ServerMessage::SessionJoined { you, .. } = join(
+            &mut follower_ws,
+            &created.session.id,
+            &created.join_secret,
+            None,
+        )
+        .await
+        else {
+            panic!("Follower should join");
+        };
+        let follower_id = you.id;
+
+        // Give time for the presenter to subscribe to session broadcasts
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let cursor = ClientMessage::CursorUpdate {
+            x: 10.0,
+            y: 20.0,
+            space: CoordinateSpace::SlidePixels,
+            seq: 2,
+        };
+        let moved = |m: &ServerMessage| {
+            matches!(m, ServerMessage::PresenceDelta { changed, .. }
+                if changed.iter().any(|c| c.participant_id == follower_id))
+        };
+        send(&mut follower_ws, &cursor).await;
+        assert!(
+            recv_until(&mut presenter_ws, moved).await.is_some(),
+            "Presenter should see the follower cursor"
+        );
+
+        // Let the cursor go idle past a short threshold, then sweep
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        state
+            .broadcast_stale_cursors(Duration::from_millis(50))
+            .await;
+        let removed = recv_until(
+            &mut presenter_ws,
+            |m| matches!(m, ServerMessage::PresenceDelta { removed, .. } if !removed.is_empty()),
+        )
+        .await;
+        assert!(
+            matches!(removed, Some(ServerMessage::PresenceDelta { ref removed, .. })
+                if removed == &vec![follower_id]),
+            "Idle follower should be listed as removed, got {:?}",
+            removed
+        );
+
+        // Moving again re-adds the cursor
+        send(&mut follower_ws, &cursor).await;
+        assert!(
+            recv_until(&mut presenter_ws, moved).await.is_some(),
+            "Follower cursor should be re-added after moving"
+        );
+    }
+}
+
+// ============================================================================
+// Protocol Negotiation Tests
+// ============================================================================
+mod protocol_negotiation {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use pathcollab_server::protocol::{ClientMessage, ErrorCode, PROTOCOL_VERSION, ServerMessage};
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// Wait for the server's close frame and return its code
+    async fn recv_close_code(ws: &mut WsStream) -> Option<u16> {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(msg) = ws.next().await {
+                if let Ok(Message::Close(frame)) = msg {
+                    return frame.map(|f| u16::from(f.code));
+                }
+            }
+            None
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    /// Test: A compatible Hello gets a Welcome and session operations proceed
+    #[tokio::test]
+    async fn test_compatible_hello_proceeds() {
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
+        let mut ws = connect(addr).await;
+
+        send(
+            &mut ws,
+            &ClientMessage::Hello {
+                protocol_version: "1.7".to_string(),
+                features: None,
+                seq: 1,
+            },
+        )
+        .await;
+        let welcome = recv_until(&mut ws, |m| matches!(m, ServerMessage::Welcome { .. })).await;
+        assert!(
+            matches!(welcome, Some(ServerMessage::Welcome { ref protocol_version, .. })
+                if protocol_version == PROTOCOL_VERSION),
+            "Compatible client should be welcomed, got {:?}",
+            welcome
+        );
+
+        // Session creation proceeds after Hello
+        create_session(&mut ws).await;
+
+        server_handle.abort();
+    }
+
+    /// Test: An incompatible major version is rejected and cannot create sessions
+    #[tokio::test]
+    async fn test_incompatible_hello_rejected() {
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
+        let mut ws = connect(addr).await;
+
+        send(
+            &mut ws,
+            &ClientMessage::Hello {
+                protocol_version: "2.0".to_string(),
+                features: None,
+                seq: 1,
+            },
+        )
+        .await;
+        let rejected = recv_until(&mut ws, |m| {
+            matches!(
+                m,
+                ServerMessage::Welcome { .. } | ServerMessage::SessionError { .. }
+            )
+        })
+        .await;
+        assert!(
+            matches!(
+                rejected,
+                Some(ServerMessage::SessionError {
+                    code: ErrorCode::UnsupportedProtocol,
+                    ..
+                })
+            ),
+            "Incompatible client should be rejected, got {:?}",
+            rejected
+        );
+
+        // The rejection is fatal: the server closes with a protocol error
+        assert_eq!(recv_close_code(&mut ws).await, Some(1002));
+
+        server_handle.abort();
+    }
+
+    /// Test: A message over the size limit closes the socket with "message too big"
+    #[tokio::test]
+    async fn test_oversized_message_closes_connection() {
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
+        let mut ws = connect(addr).await;
+
+        let oversized = format!(
+            r#"{{"type":"ping","seq":1,"padding":"{}"}}"#,
+            "x".repeat(128 * 1024)
+        );
+        ws.send(Message::Text(oversized.into())).await.unwrap();
+
+        let error = recv_until(&mut ws, |m| matches!(m, ServerMessage::SessionError { .. })).await;
+        assert!(
+            matches!(
+                error,
+                Some(ServerMessage::SessionError {
+                    code: ErrorCode::MessageTooLarge,
+                    ..
+                })
+            ),
+            "Oversized message should be reported, got {:?}",
+            error
+        );
+        assert_eq!(recv_close_code(&mut ws).await, Some(1009));
+
+        server_handle.abort();
+    }
+}
+
+// ============================================================================
+// Session Participants HTTP Tests
+// ============================================================================
+mod session_participants {
+    use super::*;
+    use axum::{Router, routing::get};
+    use pathcollab_server::protocol::{ParticipantRole, ServerMessage};
+    use pathcollab_server::server::session_routes;
+    use pathcollab_server::server::sessions::{
+        JOIN_SECRET_HEADER, PRESENTER_KEY_HEADER, ParticipantListResponse,
+    };
+
+    async fn get_participants(
+        app: &Router,
+        session_id: &str,
+        header: Option<(&str, &str)>,
+    ) -> (StatusCode, Vec<u8>) {
+        let mut request =
+            Request::builder().uri(format!("/api/session/{}/participants", session_id));
+        if let Some((name, value)) = header {
+            request = request.header(name, value);
+        }
+        let response = app
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, body.to_vec())
+    }
+
+    /// Test: Session members can list participants; others get 401/404
+    #[tokio::test]
+    async fn test_list_participants_requires_credentials() {
+        let app = Router::new()
+            .route("/ws", get(pathcollab_server::server::ws_handler))
+            .nest("/api", session_routes())
+            .with_state(create_test_app_state_with_slides());
+        let (addr, server_handle) = serve_app(app.clone()).await;
+
+        // Presenter creates session, one follower joins
+        let mut presenter_ws = connect(addr).await;
+        let CreatedSession {
+            session,
+            join_secret,
+            presenter_key,
+        } = create_session(&mut presenter_ws).await;
+
+        let mut follower_ws = connect(addr).await;
+        let ServerMessage::SessionJoined { you, .. } =
+            join(&mut follower_ws, &session.id, &join_secret, None).await
+        else {
+            panic!("Follower should join");
+        };
+
+        // Join secret and presenter key both authorize
+        for header in [
+            (JOIN_SECRET_HEADER, join_secret.as_str()),
+            (PRESENTER_KEY_HEADER, presenter_key.as_str()),
+        ] {
+            let (status, body) = get_participants(&app, &session.id, Some(header)).await;
+            assert_eq!(status, StatusCode::OK);
+            let list: ParticipantListResponse = serde_json::from_slice(&body).unwrap();
+            assert_eq!(list.participants.len(), 2);
+            assert_eq!(list.participants[0].id, session.presenter.id);
+            assert_eq!(list.participants[0].role, ParticipantRole::Presenter);
+            assert_eq!(list.participants[1].id, you.id);
+            assert_eq!(list.participants[1].role, ParticipantRole::Follower);
+        }
+
+        // Missing or wrong credentials are refused
+        let (status, _) = get_participants(&app, &session.id, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) =
+            get_participants(&app, &session.id, Some((JOIN_SECRET_HEADER, "wrong"))).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // Unknown sessions are 404
+        let (status, _) =
+            get_participants(&app, "nosuchsess", Some((JOIN_SECRET_HEADER, &join_secret))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        server_handle.abort();
+    }
+
+    /// Test: Features disabled via SESSION_FEATURES are not advertised
+    #[tokio::test]
+    async fn test_features_lists_only_enabled() {
+        use pathcollab_server::AppState;
+        use pathcollab_server::server::sessions::FeaturesResponse;
+        use pathcollab_server::session::manager::SessionManager;
+        use pathcollab_server::session::state::SessionConfig;
+        use std::sync::Arc;
+
+        async fn get_features(state: AppState) -> Vec<String> {
+            let response = session_routes()
+                .with_state(state)
+                .oneshot(Request::get("/features").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<FeaturesResponse>(&body)
+                .unwrap()
+                .features
+        }
+
+        let features = get_features(create_test_app_state_with_slides()).await;
+        assert_eq!(features, vec!["chat".to_string()]);
+
+        let manager = SessionManager::with_config(SessionConfig {
+            features: Vec::new(),
+            ..Default::default()
+        });
+        let state = create_test_app_state_with_slides().with_session_manager(Arc::new(manager));
+        assert!(get_features(state).await.is_empty());
+    }
+}
+
+// ============================================================================
+// Viewport Rewind Tests
+// ============================================================================
+mod viewport_rewind {
+    use super::*;
+    use pathcollab_server::protocol::{ClientMessage, ServerMessage};
+    use std::time::Duration;
+
+    /// Test: Rewinding two steps broadcasts the viewport from two updates ago
+    #[tokio::test(start_paused = true)]
+    async fn test_rewind_broadcasts_historical_viewport() {
+        let server = MemoryServer::new(create_test_app_state_with_slides());
+
+        // Presenter creates session
+        let mut presenter_ws = server.connect().await;
+        let created = create_session(&mut presenter_ws).await;
+
+        // Follower joins and waits for broadcasts
+        let mut follower_ws = server.connect().await;
+        join(
+            &mut follower_ws,
+            &created.session.id,
+            &created.join_secret,
+            None,
+        )
+        .await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // Presenter pans through four positions, then rewinds two steps
+        for (i, x) in [0.1, 0.2, 0.3, 0.4].into_iter().enumerate() {
+            send(
+                &mut presenter_ws,
+                &ClientMessage::ViewportUpdate {
+                    center_x: x,
+                    center_y: 0.5,
+                    zoom: 1.0,
+                    transition_ms: None,
+                    seq: 2 + i as u64,
+                },
+            )
+            .await;
+        }
+        send(
+            &mut presenter_ws,
+            &ClientMessage::RewindViewport { steps: 2, seq: 10 },
+        )
+        .await;
+
+        let mut viewports = 0;
+        let seen = collect_until(&mut follower_ws, |m| {
+            if matches!(m, ServerMessage::PresenterViewport { .. }) {
+                viewports += 1;
+            }
+            viewports == 5
+        })
+        .await;
+        let xs: Vec<f64> = seen
+            .iter()
+            .filter_map(|m| match m {
+                ServerMessage::PresenterViewport { viewport, .. } => Some(viewport.center_x),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            xs,
+            vec![0.1, 0.2, 0.3, 0.4, 0.2],
+            "Rewind should broadcast the viewport from two updates ago"
+        );
+    }
+}
+
+// ============================================================================
+// WebSocket Subprotocol Tests
+// ============================================================================
+mod websocket_subprotocol {
+    use super::*;
+    use pathcollab_server::server::{WS_SUBPROTOCOL, WsConfig};
+    use tokio_tungstenite::{
+        connect_async,
+        tungstenite::{Error as WsError, client::IntoClientRequest, http::header},
+    };
+
+    async fn start_server(
+        strict_subprotocol: bool,
+    ) -> (std::net::SocketAddr, tokio::task::JoinHandle<()>) {
+        let state = create_test_app_state_with_slides().with_ws_config(WsConfig {
+            strict_subprotocol,
+            ..WsConfig::default()
+        });
+        start_test_server(state).await
+    }
+
+    fn request_with_protocols(
+        addr: std::net::SocketAddr,
+        protocols: &str,
+    ) -> tokio_tungstenite::tungstenite::handshake::client::Request {
+        let mut request = format!("ws://{}/ws", addr).into_client_request().unwrap();
+        request
+            .headers_mut()
+            .insert(header::SEC_WEBSOCKET_PROTOCOL, protocols.parse().unwrap());
+        request
+    }
+
+    #[tokio::test]
+    async fn test_subprotocol_is_negotiated() {
+        let (addr, server_handle) = start_server(false).await;
+
+        let request = request_with_protocols(addr, &format!("chat, {WS_SUBPROTOCOL}"));
+        let (_ws, response) = connect_async(request).await.unwrap();
+
+        assert_eq!(
+            response.headers()[header::SEC_WEBSOCKET_PROTOCOL],
+            WS_SUBPROTOCOL
+        );
+
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_strict_mode_rejects_unknown_subprotocol() {
+        let (addr, server_handle) = start_server(true).await;
+
+        let rejected = connect_async(request_with_protocols(addr, "chat")).await;
+        match rejected {
+            Err(WsError::Http(response)) => {
+                assert_eq!(response.status(), StatusCode::BAD_REQUEST)
+            }
+            other => panic!("expected HTTP rejection, got {:?}", other.map(|_| ())),
+        }
+
+        // Clients that don't request a subprotocol are still accepted
+        let ws_url = format!("ws://{}/ws", addr);
+        assert!(connect_async(&ws_url).await.is_ok());
+
+        let accepted = connect_async(request_with_protocols(addr, WS_SUBPROTOCOL)).await;
+        assert!(accepted.is_ok());
+
+        server_handle.abort();
+    }
+}
+
+// ============================================================================
+// Connection Limit Tests
+// ============================================================================
+mod connection_limit {
+    use super::*;
+    use pathcollab_server::server::WsConfig;
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::Error as WsError;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_upgrade_beyond_max_connections_is_rejected() {
+        let state = create_test_app_state_with_slides().with_ws_config(WsConfig {
+            max_connections: 1,
+            ..WsConfig::default()
+        });
+        let server = MemoryServer::new(state.clone());
+
+        let first = server.connect().await;
+        match server.try_connect().await {
+            Err(WsError::Http(response)) => {
+                assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE)
+            }
+            other => panic!("expected HTTP rejection, got {:?}", other.map(|_| ())),
+        }
+
+        // Closing the first connection frees its slot
+        drop(first);
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while state
+            .open_connections
+            .load(std::sync::atomic::Ordering::Acquire)
+            > 0
+        {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "slot never released"
+            );
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(server.try_connect().await.is_ok());
+    }
+}
+
+// ============================================================================
+// Duplicate Session Request Tests
+// ============================================================================
+mod duplicate_session_requests {
+    use super::*;
+    use pathcollab_server::protocol::{AckStatus, ClientMessage, ErrorCode, ServerMessage};
+
+    /// Assert the request `seq` was rejected with `AlreadyInSession`
+    async fn assert_already_in_session(ws: &mut WsStream, seq: u64) {
+        let seen = collect_until(
+            ws,
+            |m| matches!(m, ServerMessage::Ack { ack_seq, .. } if *ack_seq == seq),
+        )
+        .await;
+
+        assert!(
+            seen.iter().any(|m| matches!(
+                m,
+                ServerMessage::SessionError {
+                    code: ErrorCode::AlreadyInSession,
+                    ..
+                }
+            )),
+            "Expected AlreadyInSession error, got {:?}",
+            seen
+        );
+        assert!(matches!(
+            seen.last(),
+            Some(ServerMessage::Ack {
+                status: AckStatus::Rejected,
+                ..
+            })
+        ));
+        assert!(
+            !seen
+                .iter()
+                .any(|m| matches!(m, ServerMessage::SessionCreated { .. })),
+            "No second session should be created"
+        );
+    }
+
+    /// Test: A second CreateSession on the same connection is rejected
+    #[tokio::test]
+    async fn test_double_create_rejected() {
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
+
+        let mut presenter_ws = connect(addr).await;
+        let created = create_session(&mut presenter_ws).await;
+
+        send(
+            &mut presenter_ws,
+            &ClientMessage::CreateSession {
+                slide_id: "test-slide".to_string(),
+                max_followers: None,
+                seq: 2,
+            },
+        )
+        .await;
+        assert_already_in_session(&mut presenter_ws, 2).await;
+
+        // The first session is still live and joinable
+        let mut follower_ws = connect(addr).await;
+        let reply = join(
+            &mut follower_ws,
+            &created.session.id,
+            &created.join_secret,
+            None,
+        )
+        .await;
+        assert!(matches!(reply, ServerMessage::SessionJoined { .. }));
+
+        server_handle.abort();
+    }
+
+    /// Test: A presenter cannot join another session on the same connection
+    #[tokio::test]
+    async fn test_create_then_join_rejected() {
+        let (addr, server_handle) = start_test_server(create_test_app_state_with_slides()).await;
+
+        let mut other_ws = connect(addr).await;
+        let other = create_session(&mut other_ws).await;
+
+        let mut presenter_ws = connect(addr).await;
+        create_session(&mut presenter_ws).await;
+
+        send(
+            &mut presenter_ws,
+            &ClientMessage::JoinSession {
+                session_id: other.session.id,
+                join_secret: other.join_secret,
+                last_seen_rev: None,
+                reconnect_token: None,
+                seq: 2,
+            },
+        )
+        .await;
+        assert_already_in_session(&mut presenter_ws, 2).await;
+
+        server_handle.abort();
+    }
+}
+
+// ============================================================================
+// In-process TLS Tests
+// ============================================================================
+
+mod tls_termination {
+    use super::*;
+    use pathcollab_server::tls::{load_rustls_config, serve_tls};
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/tls")
+            .join(name)
+    }
+
+    /// Test: With a self-signed certificate, /health is served over HTTPS
+    #[tokio::test]
+    async fn test_health_over_https() {
+        let config = load_rustls_config(&fixture("cert.pem"), &fixture("key.pem"))
+            .await
+            .expect("Fixture certificate should load");
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server_handle = tokio::spawn(serve_tls(listener, create_test_app(), config));
+
+        let cert = std::fs::read(fixture("cert.pem")).unwrap();
+        let client = reqwest::Client::builder()
+            .add_root_certificate(reqwest::Certificate::from_pem(&cert).unwrap())
+            .build()
+            .unwrap();
+        let response = client
+            .get(format!("https://localhost:{port}/health"))
+            .send()
+            .await
+            .expect("TLS handshake should succeed");
+
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["status"], "ok");
+
+        // Plain HTTP is not served on a TLS listener
+        assert!(
+            reqwest::get(format!("http://localhost:{port}/health"))
+                .await
+                .map_or(true, |r| !r.status().is_success())
+        );
 
         server_handle.abort();
     }
@@ -360,7 +360,7 @@ impl BenchmarkRunner {
             let scenario = ComprehensiveStressScenario::new(stress_config.clone());
             let results = scenario.run().await?;
 
-            let passed = results.meets_budgets();
+            let passed = results.meets_budgets(&stress_config.budgets);
             if !passed {
                 all_passed = false;
             }
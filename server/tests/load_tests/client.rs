@@ -42,6 +42,9 @@ pub enum ClientMessage {
         zoom: f64,
         seq: u64,
     },
+    SnapToPresenter {
+        seq: u64,
+    },
     Ping {
         seq: u64,
     },
@@ -74,6 +77,12 @@ pub enum ServerMessage {
     PresenterViewport {
         viewport: serde_json::Value,
     },
+    ParticipantLeft {
+        participant_id: String,
+    },
+    SessionEnded {
+        reason: String,
+    },
     SessionError {
         code: String,
         message: String,
@@ -122,7 +131,8 @@ impl LoadTestClient {
             ClientMessage::PresenterAuth { seq, .. } => *seq,
             ClientMessage::CursorUpdate { seq, .. } => *seq,
             ClientMessage::ViewportUpdate { seq, .. } => *seq,
-            ClientMessage::Ping { seq } => *seq,
+            ClientMessage::SnapToPresenter { seq } => *seq,
+            ClientMessage::Ping { seq, .. } => *seq,
         };
 
         let json = serde_json::to_string(&msg)?;
@@ -211,6 +221,76 @@ impl LoadTestClient {
         Err("Connection closed before SessionJoined received".into())
     }
 
+    /// Claim the presenter role in the joined session with its presenter key
+    pub async fn authenticate_presenter(
+        &mut self,
+        presenter_key: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let seq = self.next_seq();
+        let msg = ClientMessage::PresenterAuth {
+            presenter_key: presenter_key.to_string(),
+            seq,
+        };
+        self.send(msg).await?;
+
+        while let Some(result) = self.ws.next().await {
+            let msg = result?;
+            if let Message::Text(text) = msg {
+                if let Ok(server_msg) = serde_json::from_str::<ServerMessage>(&text) {
+                    match server_msg {
+                        ServerMessage::Ack {
+                            ack_seq,
+                            status,
+                            reason,
+                        } if ack_seq == seq => {
+                            if status == "ok" {
+                                self.presenter_key = Some(presenter_key.to_string());
+                                return Ok(());
+                            }
+                            return Err(format!(
+                                "Presenter auth rejected: {}",
+                                reason.unwrap_or_default()
+                            )
+                            .into());
+                        }
+                        ServerMessage::SessionEnded { reason } => {
+                            return Err(format!("Session ended: {}", reason).into());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Err("Connection closed before presenter auth was acknowledged".into())
+    }
+
+    /// Ask for the presenter viewport; true if the server served it, false
+    /// if it refused (e.g. because the session has no presenter)
+    pub async fn snap_to_presenter(
+        &mut self,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let seq = self.next_seq();
+        self.send(ClientMessage::SnapToPresenter { seq }).await?;
+
+        while let Some(result) = self.ws.next().await {
+            let msg = result?;
+            if let Message::Text(text) = msg {
+                if let Ok(server_msg) = serde_json::from_str::<ServerMessage>(&text) {
+                    match server_msg {
+                        ServerMessage::Ack {
+                            ack_seq, status, ..
+                        } if ack_seq == seq => return Ok(status == "ok"),
+                        ServerMessage::SessionEnded { reason } => {
+                            return Err(format!("Session ended: {}", reason).into());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Err("Connection closed before snap was acknowledged".into())
+    }
+
     /// Send cursor update
     pub async fn send_cursor(
         &mut self,
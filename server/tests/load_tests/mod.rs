@@ -48,24 +48,64 @@ impl BenchmarkTier {
     }
 }
 
+/// How `LatencyStats` keeps its samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LatencyMode {
+    /// Keep every sample, for exact percentiles on small runs
+    #[default]
+    Exact,
+    /// Count samples in a histogram; memory stays bounded and percentiles
+    /// are within 1% of the exact value
+    Streaming,
+}
+
 /// Latency statistics collected during load test
 #[derive(Debug, Default)]
 pub struct LatencyStats {
+    /// Every recorded sample (empty in streaming mode)
     pub samples: Vec<Duration>,
+    histogram: Option<LatencyHistogram>,
 }
 
 impl LatencyStats {
     pub fn new() -> Self {
         Self {
             samples: Vec::new(),
+            histogram: None,
+        }
+    }
+
+    /// Stats that estimate percentiles from a histogram instead of storing samples
+    pub fn streaming() -> Self {
+        Self {
+            samples: Vec::new(),
+            histogram: Some(LatencyHistogram::default()),
+        }
+    }
+
+    pub fn with_mode(mode: LatencyMode) -> Self {
+        match mode {
+            LatencyMode::Exact => Self::new(),
+            LatencyMode::Streaming => Self::streaming(),
         }
     }
 
     pub fn record(&mut self, latency: Duration) {
-        self.samples.push(latency);
+        match self.histogram {
+            Some(ref mut histogram) => histogram.record(latency),
+            None => self.samples.push(latency),
+        }
     }
 
-    /// Calculate percentile (0-100)
+    /// Number of recorded samples
+    pub fn count(&self) -> usize {
+        match self.histogram {
+            Some(ref histogram) => histogram.total as usize,
+            None => self.samples.len(),
+        }
+    }
+
+    /// Calculate percentile (0-100) exactly from the stored samples
     fn percentile(&self, p: f64) -> Option<Duration> {
         if self.samples.is_empty() {
             return None;
@@ -78,8 +118,89 @@ impl LatencyStats {
         Some(sorted[idx.min(sorted.len() - 1)])
     }
 
+    /// Percentile (0-100), estimated in streaming mode
+    fn quantile(&self, p: f64) -> Option<Duration> {
+        match self.histogram {
+            Some(ref histogram) => histogram.percentile(p),
+            None => self.percentile(p),
+        }
+    }
+
+    /// Calculate P50
+    pub fn p50(&self) -> Option<Duration> {
+        self.quantile(50.0)
+    }
+
+    /// Calculate P95
+    pub fn p95(&self) -> Option<Duration> {
+        self.quantile(95.0)
+    }
+
     /// Calculate P99
     pub fn p99(&self) -> Option<Duration> {
-        self.percentile(99.0)
+        self.quantile(99.0)
+    }
+}
+
+/// Sub-buckets per power of two, as a bit count (2^7 = 128)
+const SUB_BUCKET_BITS: u32 = 7;
+const SUB_BUCKETS: u64 = 1 << SUB_BUCKET_BITS;
+
+/// Log-linear histogram of latencies in microseconds
+///
+/// Values below `SUB_BUCKETS` get a bucket each; larger values share
+/// `SUB_BUCKETS` buckets per power of two, so a bucket never spans more than
+/// 1/128 of the values it holds. Memory grows with the log of the largest
+/// latency, not with the number of samples.
+#[derive(Debug, Default)]
+struct LatencyHistogram {
+    counts: Vec<u64>,
+    total: u64,
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, latency: Duration) {
+        let index = Self::bucket_index(latency.as_micros() as u64);
+        if index >= self.counts.len() {
+            self.counts.resize(index + 1, 0);
+        }
+        self.counts[index] += 1;
+        self.total += 1;
+    }
+
+    /// Percentile (0-100), ranked the same way as `LatencyStats::percentile`
+    fn percentile(&self, p: f64) -> Option<Duration> {
+        if self.total == 0 {
+            return None;
+        }
+        let rank = ((p / 100.0) * (self.total - 1) as f64).round() as u64;
+        let mut seen = 0;
+        for (index, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen > rank {
+                return Some(Duration::from_micros(Self::bucket_value(index)));
+            }
+        }
+        None
+    }
+
+    fn bucket_index(micros: u64) -> usize {
+        if micros < SUB_BUCKETS {
+            return micros as usize;
+        }
+        let shift = u64::from(63 - micros.leading_zeros() - SUB_BUCKET_BITS);
+        let sub_bucket = (micros >> shift) - SUB_BUCKETS;
+        (SUB_BUCKETS + shift * SUB_BUCKETS + sub_bucket) as usize
+    }
+
+    /// Midpoint of the values counted in bucket `index`
+    fn bucket_value(index: usize) -> u64 {
+        let index = index as u64;
+        if index < SUB_BUCKETS {
+            return index;
+        }
+        let shift = (index - SUB_BUCKETS) / SUB_BUCKETS;
+        let sub_bucket = SUB_BUCKETS + (index - SUB_BUCKETS) % SUB_BUCKETS;
+        (sub_bucket << shift) + ((1 << shift) >> 1)
     }
 }
@@ -15,12 +15,15 @@
 //! | Smoke    | 5        | 10    | 10s      |
 //! | Standard | 25       | 50    | 30s      |
 //! | Stress   | 100      | 200   | 60s      |
+//!
+//! Each tier has its own performance budgets (see [`PerformanceBudgets`]),
+//! which can be overridden with `BUDGET_*` environment variables.
 
 #![allow(clippy::collapsible_if)]
 
 use super::super::BenchmarkTier;
-use super::super::LatencyStats;
 use super::super::client::{LoadTestClient, ServerMessage, fetch_first_slide};
+use super::super::{LatencyMode, LatencyStats};
 use rand::Rng;
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
@@ -51,6 +54,10 @@ pub struct ComprehensiveStressConfig {
     pub overlay_request_hz: u32,
     /// Seed for deterministic randomization
     pub seed: u64,
+    /// Thresholds a run must stay within to pass
+    pub budgets: PerformanceBudgets,
+    /// Whether latencies are kept exactly or estimated in bounded memory
+    pub latency_mode: LatencyMode,
 }
 
 impl Default for ComprehensiveStressConfig {
@@ -65,13 +72,17 @@ impl Default for ComprehensiveStressConfig {
             tile_request_hz: 5,
             overlay_request_hz: 2,
             seed: 12345,
+            budgets: PerformanceBudgets::default(),
+            latency_mode: LatencyMode::Exact,
         }
     }
 }
 
 impl ComprehensiveStressConfig {
-    /// Create configuration for a specific benchmark tier
+    /// Create configuration for a specific benchmark tier, with the tier's
+    /// budgets adjusted by any `BUDGET_*` environment overrides
     pub fn for_tier(tier: BenchmarkTier) -> Self {
+        let budgets = PerformanceBudgets::for_tier(tier).with_env_overrides();
         match tier {
             BenchmarkTier::Smoke => Self {
                 num_sessions: 5, // 10 users
@@ -80,6 +91,7 @@ impl ComprehensiveStressConfig {
                 viewport_hz: 5,
                 tile_request_hz: 2,
                 overlay_request_hz: 1,
+                budgets,
                 ..Default::default()
             },
             BenchmarkTier::Standard => Self {
@@ -89,6 +101,7 @@ impl ComprehensiveStressConfig {
                 viewport_hz: 10,
                 tile_request_hz: 5,
                 overlay_request_hz: 2,
+                budgets,
                 ..Default::default()
             },
             BenchmarkTier::Stress => Self {
@@ -98,6 +111,8 @@ impl ComprehensiveStressConfig {
                 viewport_hz: 10,
                 tile_request_hz: 5,
                 overlay_request_hz: 2,
+                budgets,
+                latency_mode: LatencyMode::Streaming,
                 ..Default::default()
             },
         }
@@ -132,19 +147,78 @@ pub struct ComprehensiveStressResults {
 }
 
 /// Performance budgets for benchmarks
-pub mod budgets {
-    use std::time::Duration;
-
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerformanceBudgets {
     /// Maximum acceptable P99 cursor broadcast latency
-    pub const CURSOR_P99_MAX: Duration = Duration::from_millis(100);
+    pub cursor_p99_max: Duration,
     /// Maximum acceptable P99 viewport broadcast latency
-    pub const VIEWPORT_P99_MAX: Duration = Duration::from_millis(150);
+    pub viewport_p99_max: Duration,
     /// Maximum acceptable P99 tile serving latency
-    pub const TILE_P99_MAX: Duration = Duration::from_millis(500);
+    pub tile_p99_max: Duration,
     /// Maximum acceptable P99 overlay latency
-    pub const OVERLAY_P99_MAX: Duration = Duration::from_millis(1000);
-    /// Maximum acceptable error rate
-    pub const ERROR_RATE_MAX: f64 = 0.01; // 1%
+    pub overlay_p99_max: Duration,
+    /// Maximum acceptable error rate (0.0 to 1.0)
+    pub error_rate_max: f64,
+}
+
+impl Default for PerformanceBudgets {
+    fn default() -> Self {
+        Self {
+            cursor_p99_max: Duration::from_millis(100),
+            viewport_p99_max: Duration::from_millis(150),
+            tile_p99_max: Duration::from_millis(500),
+            overlay_p99_max: Duration::from_millis(1000),
+            error_rate_max: 0.01, // 1%
+        }
+    }
+}
+
+impl PerformanceBudgets {
+    /// Budgets for a specific benchmark tier
+    pub fn for_tier(tier: BenchmarkTier) -> Self {
+        match tier {
+            // Smoke runs on small CI machines, where tail latencies are noisier
+            BenchmarkTier::Smoke => Self {
+                cursor_p99_max: Duration::from_millis(200),
+                viewport_p99_max: Duration::from_millis(300),
+                tile_p99_max: Duration::from_millis(1000),
+                overlay_p99_max: Duration::from_millis(2000),
+                error_rate_max: 0.02, // 2%
+            },
+            BenchmarkTier::Standard | BenchmarkTier::Stress => Self::default(),
+        }
+    }
+
+    /// Replace budgets set in the environment: `BUDGET_CURSOR_P99_MS`,
+    /// `BUDGET_VIEWPORT_P99_MS`, `BUDGET_TILE_P99_MS`, `BUDGET_OVERLAY_P99_MS`
+    /// and `BUDGET_ERROR_RATE` (a fraction, e.g. `0.05`)
+    pub fn with_env_overrides(mut self) -> Self {
+        let millis = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+        };
+        if let Some(max) = millis("BUDGET_CURSOR_P99_MS") {
+            self.cursor_p99_max = max;
+        }
+        if let Some(max) = millis("BUDGET_VIEWPORT_P99_MS") {
+            self.viewport_p99_max = max;
+        }
+        if let Some(max) = millis("BUDGET_TILE_P99_MS") {
+            self.tile_p99_max = max;
+        }
+        if let Some(max) = millis("BUDGET_OVERLAY_P99_MS") {
+            self.overlay_p99_max = max;
+        }
+        if let Some(max) = std::env::var("BUDGET_ERROR_RATE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            self.error_rate_max = max;
+        }
+        self
+    }
 }
 
 impl ComprehensiveStressResults {
@@ -180,8 +254,8 @@ impl ComprehensiveStressResults {
     /// Minimum samples required to consider a latency measurement valid
     const MIN_LATENCY_SAMPLES: usize = 10;
 
-    /// Check if results meet performance budgets
-    pub fn meets_budgets(&self) -> bool {
+    /// Check if results meet the given performance budgets
+    pub fn meets_budgets(&self, budgets: &PerformanceBudgets) -> bool {
         // WebSocket latency budgets
         // Note: The server doesn't send Acks for cursor/viewport updates (fire-and-forget
         // for performance), so latency samples may be empty. That's OK - we check if
@@ -189,21 +263,21 @@ impl ComprehensiveStressResults {
         let cursor_ok = self
             .cursor_latencies
             .p99()
-            .map(|p| p <= budgets::CURSOR_P99_MAX)
+            .map(|p| p <= budgets.cursor_p99_max)
             .unwrap_or(true); // OK if no samples (server doesn't Ack cursor updates)
 
         let viewport_ok = self
             .viewport_latencies
             .p99()
-            .map(|p| p <= budgets::VIEWPORT_P99_MAX)
+            .map(|p| p <= budgets.viewport_p99_max)
             .unwrap_or(true); // OK if no samples (server doesn't Ack viewport updates)
 
         // HTTP latency budgets - require samples if we had successful requests
         let tile_ok = if self.http_requests_success > 0 {
             self.tile_latencies
                 .p99()
-                .map(|p| p <= budgets::TILE_P99_MAX)
-                .unwrap_or_else(|| self.tile_latencies.samples.len() >= Self::MIN_LATENCY_SAMPLES)
+                .map(|p| p <= budgets.tile_p99_max)
+                .unwrap_or_else(|| self.tile_latencies.count() >= Self::MIN_LATENCY_SAMPLES)
         } else {
             true
         };
@@ -212,11 +286,11 @@ impl ComprehensiveStressResults {
         let overlay_ok = self
             .overlay_latencies
             .p99()
-            .map(|p| p <= budgets::OVERLAY_P99_MAX)
+            .map(|p| p <= budgets.overlay_p99_max)
             .unwrap_or(true); // OK if no overlay data
 
         // Error rate budget
-        let error_rate_ok = self.error_rate() < budgets::ERROR_RATE_MAX;
+        let error_rate_ok = self.error_rate() < budgets.error_rate_max;
 
         cursor_ok && viewport_ok && tile_ok && overlay_ok && error_rate_ok
     }
@@ -394,10 +468,11 @@ impl ComprehensiveStressScenario {
         drop(tx);
 
         // Collect events
-        let mut cursor_latencies = LatencyStats::new();
-        let mut viewport_latencies = LatencyStats::new();
-        let mut tile_latencies = LatencyStats::new();
-        let mut overlay_latencies = LatencyStats::new();
+        let mode = self.config.latency_mode;
+        let mut cursor_latencies = LatencyStats::with_mode(mode);
+        let mut viewport_latencies = LatencyStats::with_mode(mode);
+        let mut tile_latencies = LatencyStats::with_mode(mode);
+        let mut overlay_latencies = LatencyStats::with_mode(mode);
 
         let collect_duration = self.config.duration + Duration::from_secs(10);
         let collect_start = Instant::now();
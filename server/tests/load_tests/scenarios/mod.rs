@@ -1,10 +1,18 @@
 //! Load test scenarios
 //!
-//! Single comprehensive benchmark that tests all hot paths:
+//! Comprehensive benchmark that tests all hot paths:
 //! - WebSocket cursor/viewport broadcasts
 //! - HTTP tile serving
 //! - HTTP overlay requests
+//!
+//! Presenter churn, which exercises the presenter grace period by
+//! repeatedly disconnecting and reconnecting presenters.
 
 pub mod comprehensive;
+pub mod presenter_churn;
 
-pub use comprehensive::{ComprehensiveStressConfig, ComprehensiveStressScenario};
+pub use comprehensive::{
+    ComprehensiveStressConfig, ComprehensiveStressResults, ComprehensiveStressScenario,
+    PerformanceBudgets,
+};
+pub use presenter_churn::{PresenterChurnConfig, PresenterChurnScenario};
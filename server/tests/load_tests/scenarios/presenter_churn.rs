@@ -0,0 +1,352 @@
+//! Presenter churn scenario
+//!
+//! Repeatedly disconnects and reconnects the presenter of many sessions at
+//! once, while a follower stays connected to each. Every cycle:
+//!
+//! 1. The presenter closes its socket, which puts the session into
+//!    `PresenterDisconnected` and starts the grace period
+//! 2. The follower waits to be told the presenter left
+//! 3. After a short offline period the presenter reconnects, rejoins with the
+//!    join secret and re-authenticates with its presenter key
+//! 4. The follower snaps to the presenter, which the server only serves once
+//!    the session has a presenter again
+//!
+//! Sessions that end, refuse the returning presenter, or stay presenterless
+//! after it re-authenticates are reported as lost.
+//!
+//! ## Benchmark Tiers
+//!
+//! | Tier     | Sessions | Cycles | Offline     |
+//! |----------|----------|--------|-------------|
+//! | Smoke    | 5        | 3      | 200-1000ms  |
+//! | Standard | 25       | 5      | 200-2000ms  |
+//! | Stress   | 100      | 10     | 200-2000ms  |
+
+#![allow(clippy::collapsible_if)]
+
+use super::super::BenchmarkTier;
+use super::super::LatencyStats;
+use super::super::client::{LoadTestClient, ServerMessage, fetch_first_slide};
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::time::{Duration, Instant};
+
+/// How long a follower waits to be told the presenter left
+const LEAVE_NOTICE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Configuration for the presenter churn scenario
+#[derive(Debug, Clone)]
+pub struct PresenterChurnConfig {
+    /// Number of sessions (each has 1 presenter + 1 follower = 2 users)
+    pub num_sessions: usize,
+    /// Disconnect/reconnect cycles per presenter
+    pub cycles: usize,
+    /// Shortest time a presenter stays disconnected
+    pub offline_min: Duration,
+    /// Longest time a presenter stays disconnected; keep well below the
+    /// server's presenter grace period
+    pub offline_max: Duration,
+    /// Server WebSocket URL
+    pub ws_url: String,
+    /// Server HTTP base URL
+    pub http_url: String,
+    /// Seed for deterministic randomization
+    pub seed: u64,
+}
+
+impl Default for PresenterChurnConfig {
+    fn default() -> Self {
+        Self {
+            num_sessions: 25,
+            cycles: 5,
+            offline_min: Duration::from_millis(200),
+            offline_max: Duration::from_millis(2000),
+            ws_url: "ws://127.0.0.1:8080/ws".to_string(),
+            http_url: "http://127.0.0.1:8080".to_string(),
+            seed: 12345,
+        }
+    }
+}
+
+impl PresenterChurnConfig {
+    /// Create configuration for a specific benchmark tier
+    pub fn for_tier(tier: BenchmarkTier) -> Self {
+        match tier {
+            BenchmarkTier::Smoke => Self {
+                num_sessions: 5, // 10 users
+                cycles: 3,
+                offline_max: Duration::from_millis(1000),
+                ..Default::default()
+            },
+            BenchmarkTier::Standard => Self {
+                num_sessions: 25, // 50 users
+                cycles: 5,
+                ..Default::default()
+            },
+            BenchmarkTier::Stress => Self {
+                num_sessions: 100, // 200 users
+                cycles: 10,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Results of the presenter churn scenario
+#[derive(Debug, Default)]
+pub struct PresenterChurnResults {
+    pub sessions_created: u64,
+    pub reconnects_attempted: u64,
+    pub reconnects_succeeded: u64,
+    /// Presenter departures the follower was never told about
+    pub leave_notices_missed: u64,
+    /// Sessions that ended, turned the presenter away or stayed presenterless
+    /// during churn
+    pub sessions_lost: u64,
+    /// Re-authentications after which the session still had no presenter
+    pub presenterless_after_reauth: u64,
+
+    /// Presenter socket closed → follower receives `participant_left`
+    /// (how quickly the server starts the grace period)
+    pub leave_latencies: LatencyStats,
+    /// Presenter reconnect → presenter auth acknowledged
+    pub reauth_latencies: LatencyStats,
+    /// Presenter socket closed → follower's snap to presenter served
+    /// (time the session spends without a presenter)
+    pub disconnected_durations: LatencyStats,
+
+    /// Test duration
+    pub duration: Duration,
+}
+
+impl PresenterChurnResults {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Print a summary of the run
+    pub fn print_summary(&self) {
+        let ms = |d: Option<Duration>| d.map(|d| d.as_secs_f64() * 1000.0).unwrap_or(0.0);
+        println!(
+            "   Sessions: {} created, {} lost | Reconnects: {}/{} | Missed leave notices: {} | Presenterless after re-auth: {}",
+            self.sessions_created,
+            self.sessions_lost,
+            self.reconnects_succeeded,
+            self.reconnects_attempted,
+            self.leave_notices_missed,
+            self.presenterless_after_reauth
+        );
+        println!(
+            "   Leave P99: {:.1}ms | Re-auth P99: {:.1}ms | Presenterless P99: {:.1}ms",
+            ms(self.leave_latencies.p99()),
+            ms(self.reauth_latencies.p99()),
+            ms(self.disconnected_durations.p99())
+        );
+    }
+}
+
+/// What happened to one session during the run
+#[derive(Debug, Default)]
+struct SessionChurnOutcome {
+    reconnects_attempted: u64,
+    reconnects_succeeded: u64,
+    leave_notices_missed: u64,
+    presenterless_after_reauth: u64,
+    lost: bool,
+    leave_latencies: Vec<Duration>,
+    reauth_latencies: Vec<Duration>,
+    disconnected_durations: Vec<Duration>,
+}
+
+/// Presenter churn scenario
+pub struct PresenterChurnScenario {
+    config: PresenterChurnConfig,
+}
+
+impl PresenterChurnScenario {
+    pub fn new(config: PresenterChurnConfig) -> Self {
+        Self { config }
+    }
+
+    /// Run the presenter churn scenario
+    pub async fn run(
+        &self,
+    ) -> Result<PresenterChurnResults, Box<dyn std::error::Error + Send + Sync>> {
+        let start = Instant::now();
+        let mut results = PresenterChurnResults::new();
+
+        let slide = fetch_first_slide(&self.config.http_url).await?;
+        println!("Using slide: {} ({})", slide.name, slide.id);
+        println!(
+            "Starting presenter churn: {} sessions × {} cycles",
+            self.config.num_sessions, self.config.cycles
+        );
+
+        let mut join_handles = Vec::new();
+        for session_idx in 0..self.config.num_sessions {
+            let mut presenter = match LoadTestClient::connect(&self.config.ws_url).await {
+                Ok(client) => client,
+                Err(e) => {
+                    eprintln!("Failed to connect presenter {}: {}", session_idx, e);
+                    continue;
+                }
+            };
+            if let Err(e) = presenter.create_session(&slide.id).await {
+                eprintln!("Failed to create session {}: {}", session_idx, e);
+                continue;
+            }
+            let session_id = presenter.session_id.clone().unwrap();
+            let join_secret = presenter.join_secret.clone().unwrap();
+
+            let mut follower = match LoadTestClient::connect(&self.config.ws_url).await {
+                Ok(client) => client,
+                Err(e) => {
+                    eprintln!("Failed to connect follower {}: {}", session_idx, e);
+                    continue;
+                }
+            };
+            if let Err(e) = follower.join_session(&session_id, &join_secret).await {
+                eprintln!("Follower {} failed to join: {}", session_idx, e);
+                continue;
+            }
+            results.sessions_created += 1;
+
+            join_handles.push(tokio::spawn(churn_session(
+                self.config.clone(),
+                presenter,
+                follower,
+                self.config.seed.wrapping_add(session_idx as u64),
+            )));
+        }
+
+        for handle in join_handles {
+            let Ok(outcome) = handle.await else {
+                results.sessions_lost += 1;
+                continue;
+            };
+            results.reconnects_attempted += outcome.reconnects_attempted;
+            results.reconnects_succeeded += outcome.reconnects_succeeded;
+            results.leave_notices_missed += outcome.leave_notices_missed;
+            results.presenterless_after_reauth += outcome.presenterless_after_reauth;
+            results.sessions_lost += u64::from(outcome.lost);
+            for latency in outcome.leave_latencies {
+                results.leave_latencies.record(latency);
+            }
+            for latency in outcome.reauth_latencies {
+                results.reauth_latencies.record(latency);
+            }
+            for duration in outcome.disconnected_durations {
+                results.disconnected_durations.record(duration);
+            }
+        }
+
+        results.duration = start.elapsed();
+        Ok(results)
+    }
+}
+
+/// Disconnect and reconnect one session's presenter `config.cycles` times
+async fn churn_session(
+    config: PresenterChurnConfig,
+    mut presenter: LoadTestClient,
+    mut follower: LoadTestClient,
+    seed: u64,
+) -> SessionChurnOutcome {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut outcome = SessionChurnOutcome::default();
+    let session_id = presenter.session_id.clone().unwrap();
+    let join_secret = presenter.join_secret.clone().unwrap();
+    let presenter_key = presenter.presenter_key.clone().unwrap();
+
+    for _ in 0..config.cycles {
+        let disconnected_at = Instant::now();
+        let _ = presenter.close().await;
+
+        // The follower learns the presenter left once the server has
+        // processed the disconnect and started the grace period
+        let mut notified = false;
+        while disconnected_at.elapsed() < LEAVE_NOTICE_TIMEOUT {
+            match follower.recv_timeout(Duration::from_millis(100)).await {
+                Ok(Some(ServerMessage::ParticipantLeft { .. })) => {
+                    outcome.leave_latencies.push(disconnected_at.elapsed());
+                    notified = true;
+                    break;
+                }
+                Ok(Some(ServerMessage::SessionEnded { .. })) | Err(_) => {
+                    outcome.lost = true;
+                    return outcome;
+                }
+                Ok(_) => {}
+            }
+        }
+        if !notified {
+            outcome.leave_notices_missed += 1;
+        }
+
+        let offline = rng.random_range(config.offline_min..=config.offline_max);
+        tokio::time::sleep(offline.saturating_sub(disconnected_at.elapsed())).await;
+
+        outcome.reconnects_attempted += 1;
+        let reconnect_start = Instant::now();
+        let reconnected = async {
+            let mut client = LoadTestClient::connect(&config.ws_url).await?;
+            client.join_session(&session_id, &join_secret).await?;
+            client.authenticate_presenter(&presenter_key).await?;
+            client.join_secret = Some(join_secret.clone());
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(client)
+        }
+        .await;
+        match reconnected {
+            Ok(client) => {
+                outcome.reauth_latencies.push(reconnect_start.elapsed());
+                outcome.reconnects_succeeded += 1;
+                presenter = client;
+            }
+            Err(e) => {
+                eprintln!(
+                    "Presenter of session {} could not return: {}",
+                    session_id, e
+                );
+                outcome.lost = true;
+                let _ = follower.close().await;
+                return outcome;
+            }
+        }
+
+        // The ack alone does not show the server took the presenter back:
+        // the follower's snap is only served by a session with a presenter
+        match follower.snap_to_presenter().await {
+            Ok(true) => {
+                outcome
+                    .disconnected_durations
+                    .push(disconnected_at.elapsed());
+            }
+            Ok(false) => {
+                eprintln!(
+                    "Session {} has no presenter after re-authentication",
+                    session_id
+                );
+                outcome.presenterless_after_reauth += 1;
+                outcome.lost = true;
+                let _ = presenter.close().await;
+                let _ = follower.close().await;
+                return outcome;
+            }
+            Err(e) => {
+                eprintln!(
+                    "Session {} ended after re-authentication: {}",
+                    session_id, e
+                );
+                outcome.lost = true;
+                let _ = presenter.close().await;
+                let _ = follower.close().await;
+                return outcome;
+            }
+        }
+    }
+
+    let _ = presenter.close().await;
+    let _ = follower.close().await;
+    outcome
+}
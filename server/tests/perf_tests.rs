@@ -29,6 +29,9 @@
 //! # Full stress test (manual/release)
 //! cargo test --test perf_tests bench_stress --release -- --ignored --nocapture
 //!
+//! # Presenter disconnect/reconnect churn
+//! cargo test --test perf_tests bench_presenter_churn_smoke --release -- --ignored --nocapture
+//!
 //! # Save current results as baseline
 //! SAVE_BASELINE=1 cargo test --test perf_tests bench_smoke --release -- --ignored --nocapture
 //! ```
@@ -36,13 +39,29 @@
 //! ## Baseline Management
 //!
 //! Baselines are stored in `.benchmark-baseline.json`. Set `SAVE_BASELINE=1` to update.
+//!
+//! ## Budgets
+//!
+//! Each tier has its own latency and error-rate budgets; smoke is the most
+//! lenient. Override any of them with `BUDGET_CURSOR_P99_MS`,
+//! `BUDGET_VIEWPORT_P99_MS`, `BUDGET_TILE_P99_MS`, `BUDGET_OVERLAY_P99_MS` or
+//! `BUDGET_ERROR_RATE`:
+//!
+//! ```bash
+//! BUDGET_TILE_P99_MS=2000 cargo test --test perf_tests bench_smoke --release -- --ignored --nocapture
+//! ```
 
 #![allow(clippy::collapsible_if)]
 
 mod load_tests;
 
-use load_tests::BenchmarkTier;
 use load_tests::benchmark::{BenchmarkRunConfig, BenchmarkRunner};
+use load_tests::scenarios::{
+    ComprehensiveStressConfig, ComprehensiveStressResults, PerformanceBudgets,
+    PresenterChurnConfig, PresenterChurnScenario,
+};
+use load_tests::{BenchmarkTier, LatencyStats};
+use std::time::Duration;
 
 /// Run a benchmark for the given tier with warm-up, iterations, and comparison
 async fn run_benchmark(tier: BenchmarkTier) {
@@ -106,3 +125,79 @@ async fn bench_standard() {
 async fn bench_stress() {
     run_benchmark(BenchmarkTier::Stress).await;
 }
+
+/// Presenter churn, smoke tier: presenters of 5 sessions disconnect and
+/// return 3 times each, well within the grace period
+///
+/// - Duration: ~10 seconds
+/// - Purpose: Catch grace-period and presenter re-auth races
+#[tokio::test]
+#[ignore = "requires running server"]
+async fn bench_presenter_churn_smoke() {
+    let config = PresenterChurnConfig::for_tier(BenchmarkTier::Smoke);
+    let expected_reconnects = (config.num_sessions * config.cycles) as u64;
+    let results = PresenterChurnScenario::new(config)
+        .run()
+        .await
+        .expect("Presenter churn should complete");
+    results.print_summary();
+
+    assert_eq!(results.sessions_lost, 0, "Sessions lost during churn");
+    assert_eq!(
+        results.presenterless_after_reauth, 0,
+        "Sessions left presenterless after re-auth"
+    );
+    assert_eq!(results.reconnects_succeeded, expected_reconnects);
+}
+
+/// `meets_budgets` judges results against the budgets in the config
+#[test]
+fn test_meets_budgets_uses_config_budgets() {
+    let mut results = ComprehensiveStressResults::new();
+    results.http_requests_sent = 100;
+    results.http_requests_success = 100;
+    for _ in 0..20 {
+        results.tile_latencies.record(Duration::from_millis(800));
+    }
+
+    let strict = ComprehensiveStressConfig::default();
+    assert!(!results.meets_budgets(&strict.budgets));
+
+    let lenient = ComprehensiveStressConfig {
+        budgets: PerformanceBudgets {
+            tile_p99_max: Duration::from_millis(1000),
+            ..PerformanceBudgets::default()
+        },
+        ..ComprehensiveStressConfig::default()
+    };
+    assert!(results.meets_budgets(&lenient.budgets));
+
+    let smoke = PerformanceBudgets::for_tier(BenchmarkTier::Smoke);
+    assert!(smoke.tile_p99_max > PerformanceBudgets::for_tier(BenchmarkTier::Stress).tile_p99_max);
+}
+
+/// The streaming estimator tracks exact percentiles closely without storing samples
+#[test]
+fn test_streaming_percentiles_match_exact() {
+    let mut exact = LatencyStats::new();
+    let mut streaming = LatencyStats::streaming();
+    // Mostly fast responses with a long tail: 0.5ms up to ~1s
+    for i in 0..10_000u64 {
+        let micros = 500 + (i * i * 7919) % 10_000 + if i % 50 == 0 { i * 100 } else { 0 };
+        let latency = Duration::from_micros(micros);
+        exact.record(latency);
+        streaming.record(latency);
+    }
+
+    assert!(streaming.samples.is_empty());
+    assert_eq!(streaming.count(), exact.count());
+    for (estimate, actual) in [
+        (streaming.p50(), exact.p50()),
+        (streaming.p95(), exact.p95()),
+        (streaming.p99(), exact.p99()),
+    ] {
+        let (estimate, actual) = (estimate.unwrap(), actual.unwrap());
+        let error = (estimate.as_secs_f64() - actual.as_secs_f64()).abs() / actual.as_secs_f64();
+        assert!(error < 0.01, "estimate {estimate:?} vs exact {actual:?}");
+    }
+}
@@ -46,6 +46,8 @@ export interface SlideInfo {
   tile_size: number
   num_levels: number
   tile_url_template: string
+  /** Whether the slide has an overlay to offer overlay controls for */
+  has_overlay?: boolean
 }
 
 /** Cell overlay state as received from server (snake_case) */
@@ -130,8 +132,10 @@ interface CursorWithParticipant {
   name: string
   color: string
   is_presenter: boolean
+  /** Level-0 slide pixels */
   x: number
   y: number
+  space?: 'slide_pixels'
 }
 
 interface UseSessionOptions {
@@ -288,6 +292,24 @@ export function useSession({
           break
         }
 
+        case 'participant_renamed': {
+          const participantId = message.participant_id as string
+          const name = message.name as string
+          setSession((prev) => {
+            if (!prev) return prev
+            return {
+              ...prev,
+              presenter:
+                prev.presenter.id === participantId ? { ...prev.presenter, name } : prev.presenter,
+              followers: prev.followers.map((p) => (p.id === participantId ? { ...p, name } : p)),
+            }
+          })
+          setCursors((prev) =>
+            prev.map((c) => (c.participant_id === participantId ? { ...c, name } : c))
+          )
+          break
+        }
+
         case 'participant_left': {
           const participantId = message.participant_id as string
           setSession((prev) => {
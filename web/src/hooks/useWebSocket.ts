@@ -32,6 +32,12 @@ interface UseWebSocketReturn {
   latency: number | null
 }
 
+/** Close codes the server uses for fatal errors; reconnecting would fail the same way */
+const FATAL_CLOSE_CODES = new Set([
+  1002, // protocol error (unsupported protocol version)
+  1009, // message too big
+])
+
 const DEFAULT_RECONNECT_INTERVAL = 1000
 const DEFAULT_MAX_RECONNECT_ATTEMPTS = 10
 
@@ -139,14 +145,18 @@ export function useWebSocket({
         onOpenRef.current?.()
       }
 
-      ws.onclose = () => {
+      ws.onclose = (event) => {
         setStatus('disconnected')
         setLatency(null)
         pingTimestampsRef.current.clear()
         onCloseRef.current?.()
 
         // Schedule reconnection
-        if (shouldReconnect && reconnectAttemptsRef.current < maxReconnectAttempts) {
+        if (
+          shouldReconnect &&
+          !FATAL_CLOSE_CODES.has(event.code) &&
+          reconnectAttemptsRef.current < maxReconnectAttempts
+        ) {
           const delay = reconnectInterval * Math.pow(2, reconnectAttemptsRef.current)
           setStatus('reconnecting')
           reconnectAttemptsRef.current++